@@ -0,0 +1,113 @@
+use crate::database::{get_connection, log_sql};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+/// Settings key the frontend timer store persists its timers under
+const TIMER_STORAGE_KEY: &str = "timer-instances";
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AlarmSource {
+    Timer,
+    Event,
+    Reminder,
+}
+
+/// The next thing due to go off
+#[derive(Serialize, Clone, Debug)]
+pub struct AlarmInfo {
+    pub source: AlarmSource,
+    pub id: String,
+    pub title: String,
+    /// Unix timestamp in seconds
+    pub fires_at: f64,
+}
+
+/// Timer as stored by the frontend timer store (`useTimerStore`)
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredTimer {
+    id: String,
+    name: String,
+    /// Seconds left at `last_start_time`
+    remaining: f64,
+    is_running: bool,
+    /// Milliseconds since the epoch
+    last_start_time: Option<f64>,
+}
+
+/// End times of the app's running timers
+fn running_timers(app_handle: &AppHandle) -> Vec<AlarmInfo> {
+    let json = get_connection(app_handle).ok().and_then(|conn| {
+        let sql = "SELECT value FROM settings WHERE key = ?1";
+        log_sql(sql);
+        conn.query_row(sql, [TIMER_STORAGE_KEY], |row| row.get::<_, String>(0))
+            .ok()
+    });
+
+    let timers: Vec<StoredTimer> = json
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+
+    timers
+        .into_iter()
+        .filter(|timer| timer.is_running)
+        .filter_map(|timer| {
+            let started = timer.last_start_time?;
+            Some(AlarmInfo {
+                source: AlarmSource::Timer,
+                id: timer.id,
+                title: timer.name,
+                fires_at: started / 1000.0 + timer.remaining,
+            })
+        })
+        .collect()
+}
+
+/// Get the soonest upcoming alarm across the app's running timers, timed calendar
+/// events and reminders with a due date. None if nothing is scheduled.
+///
+/// System alarms (the macOS Clock app, Windows Alarms & Clock) have no public API,
+/// so they are not included.
+#[tauri::command]
+pub async fn get_next_system_alarm(app_handle: AppHandle) -> Option<AlarmInfo> {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0);
+
+    let mut candidates = running_timers(&app_handle);
+
+    let events = crate::calendar::get_upcoming_events(None)
+        .await
+        .unwrap_or_default();
+    candidates.extend(
+        events
+            .into_iter()
+            // All-day events have no meaningful start time to count down to
+            .filter(|event| !event.is_all_day)
+            .map(|event| AlarmInfo {
+                source: AlarmSource::Event,
+                id: event.id,
+                title: event.title,
+                fires_at: event.start_date,
+            }),
+    );
+
+    let reminders = crate::calendar::get_reminders(None, None)
+        .await
+        .unwrap_or_default();
+    candidates.extend(reminders.into_iter().filter_map(|reminder| {
+        Some(AlarmInfo {
+            source: AlarmSource::Reminder,
+            fires_at: reminder.due_date?,
+            id: reminder.id,
+            title: reminder.title,
+        })
+    }));
+
+    candidates
+        .into_iter()
+        .filter(|alarm| alarm.fires_at > now)
+        .min_by(|a, b| a.fires_at.total_cmp(&b.fires_at))
+}
@@ -1,7 +1,7 @@
-use crate::models::NowPlayingData;
-use crate::utils::{base64_encode, fetch_artwork_from_url};
+use crate::models::{AutomationDenied, MediaAppInfo, NowPlayingData};
+use crate::utils::{base64_encode, fetch_artwork_from_url, parse_locale_f64};
 use log;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use tauri::Emitter;
 
 /// Global state for audio levels (updated by audio monitoring thread)
@@ -10,6 +10,15 @@ static AUDIO_LEVELS: std::sync::OnceLock<std::sync::Mutex<Vec<f64>>> = std::sync
 /// Global state to track if media is playing (to pause simulation)
 static IS_PLAYING: AtomicBool = AtomicBool::new(false);
 
+/// Whether volume changes should avoid the system volume HUD (route through per-app volume)
+static SUPPRESS_VOLUME_HUD: AtomicBool = AtomicBool::new(true);
+
+/// Seconds before the end of a track at which `track-ending` fires
+static TRACK_ENDING_THRESHOLD_SECS: AtomicU32 = AtomicU32::new(5);
+
+/// Whether `track-ending` has already fired for the current track
+static TRACK_ENDING_FIRED: AtomicBool = AtomicBool::new(false);
+
 /// Cache for current track info to avoid refetching artwork
 /// Format: (title, artist, artwork_base64)
 static TRACK_CACHE: std::sync::OnceLock<
@@ -21,7 +30,7 @@ static LAST_PLAYED: std::sync::OnceLock<std::sync::Mutex<Option<NowPlayingData>>
     std::sync::OnceLock::new();
 
 pub fn init_audio_state() {
-    let _ = AUDIO_LEVELS.set(std::sync::Mutex::new(vec![0.15; 6]));
+    let _ = AUDIO_LEVELS.set(std::sync::Mutex::new(vec![0.15; VISUALIZER_BANDS]));
     let _ = TRACK_CACHE.set(std::sync::Mutex::new((None, None, None)));
     let _ = LAST_PLAYED.set(std::sync::Mutex::new(None));
 }
@@ -30,7 +39,7 @@ fn get_audio_levels_internal() -> Vec<f64> {
     AUDIO_LEVELS
         .get()
         .map(|m| m.lock().unwrap().clone())
-        .unwrap_or_else(|| vec![0.15; 6])
+        .unwrap_or_else(|| vec![0.15; VISUALIZER_BANDS])
 }
 
 fn set_audio_levels(levels: Vec<f64>) {
@@ -45,6 +54,352 @@ pub fn get_audio_levels() -> Vec<f64> {
     get_audio_levels_internal()
 }
 
+/// Number of frequency bands the visualizer produces
+const VISUALIZER_BANDS: usize = 6;
+/// Visualizer update rate
+const VISUALIZER_FPS: u32 = 30;
+/// Lowest and highest band values, so bars never vanish or clip
+const VISUALIZER_FLOOR: f64 = 0.08;
+const VISUALIZER_CEILING: f64 = 0.92;
+
+/// Parameters the visualizer renders with, for previews
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct VisualizerConfig {
+    pub band_count: usize,
+    pub fps: u32,
+    /// Multiplier applied to raw levels
+    pub gain: f64,
+    pub floor: f64,
+    pub ceiling: f64,
+}
+
+/// Current band values (same as get_audio_levels), for rendering a static preview
+#[tauri::command]
+pub fn get_visualizer_snapshot() -> Vec<f64> {
+    get_audio_levels_internal()
+}
+
+/// How the visualizer is configured, so a preview can draw matching bars
+#[tauri::command]
+pub fn get_visualizer_config() -> VisualizerConfig {
+    VisualizerConfig {
+        band_count: VISUALIZER_BANDS,
+        fps: VISUALIZER_FPS,
+        // Levels are used as produced, there is no gain control yet
+        gain: 1.0,
+        floor: VISUALIZER_FLOOR,
+        ceiling: VISUALIZER_CEILING,
+    }
+}
+
+/// Frequency range a visualizer bar represents, for labelling the bars
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct BandInfo {
+    pub index: usize,
+    pub label: String,
+    pub freq_low_hz: f64,
+    pub freq_high_hz: f64,
+}
+
+/// Named ranges behind the default bands, lowest first
+const VISUALIZER_BAND_RANGES: [(&str, f64, f64); 6] = [
+    ("Bass", 20.0, 150.0),
+    ("Low-mid", 150.0, 400.0),
+    ("Mid", 400.0, 1000.0),
+    ("High-mid", 1000.0, 2500.0),
+    ("Presence", 2500.0, 6000.0),
+    ("Brilliance", 6000.0, 20000.0),
+];
+
+/// Split 20 Hz-20 kHz into `count` bands. The default count uses the named ranges,
+/// other counts are spaced logarithmically and labelled by the range their centre falls in.
+fn visualizer_bands(count: usize) -> Vec<BandInfo> {
+    if count == VISUALIZER_BAND_RANGES.len() {
+        return VISUALIZER_BAND_RANGES
+            .iter()
+            .enumerate()
+            .map(|(index, (label, low, high))| BandInfo {
+                index,
+                label: label.to_string(),
+                freq_low_hz: *low,
+                freq_high_hz: *high,
+            })
+            .collect();
+    }
+
+    let (min_hz, max_hz) = (20.0f64, 20000.0f64);
+    let ratio = (max_hz / min_hz).powf(1.0 / count as f64);
+    (0..count)
+        .map(|index| {
+            let low = min_hz * ratio.powi(index as i32);
+            let high = low * ratio;
+            let centre = (low * high).sqrt();
+            let label = VISUALIZER_BAND_RANGES
+                .iter()
+                .find(|(_, range_low, range_high)| centre >= *range_low && centre < *range_high)
+                .map(|(label, _, _)| *label)
+                .unwrap_or("Brilliance");
+            BandInfo {
+                index,
+                label: label.to_string(),
+                freq_low_hz: low.round(),
+                freq_high_hz: high.round(),
+            }
+        })
+        .collect()
+}
+
+/// Frequency range and label of each visualizer bar, for a legend or accessibility text
+#[tauri::command]
+pub fn get_visualizer_bands() -> Vec<BandInfo> {
+    visualizer_bands(VISUALIZER_BANDS)
+}
+
+/// Default length of the visualizer cross-fade on track changes and stops
+const DEFAULT_VISUALIZER_CROSSFADE_MS: u32 = 400;
+/// Longest allowed cross-fade, 0 disables it
+const MAX_VISUALIZER_CROSSFADE_MS: u32 = 3000;
+
+/// How long the visualizer takes to settle on a track change or stop (persisted)
+static VISUALIZER_CROSSFADE_MS: AtomicU32 = AtomicU32::new(DEFAULT_VISUALIZER_CROSSFADE_MS);
+
+/// Set by get_now_playing when the track changes, taken by the visualizer loop
+static VISUALIZER_TRACK_CHANGED: AtomicBool = AtomicBool::new(false);
+
+/// Blend `from` towards `to` with an eased `progress` (0.0-1.0)
+fn crossfade_levels(from: &[f64], to: &[f64], progress: f64) -> Vec<f64> {
+    let p = progress.clamp(0.0, 1.0);
+    let eased = p * p * (3.0 - 2.0 * p);
+    from.iter()
+        .zip(to)
+        .map(|(a, b)| a + (b - a) * eased)
+        .collect()
+}
+
+fn load_visualizer_crossfade(app_handle: &tauri::AppHandle) {
+    let stored = crate::database::get_connection(app_handle)
+        .ok()
+        .and_then(|conn| {
+            let sql = "SELECT value FROM settings WHERE key = 'visualizer_crossfade_ms'";
+            crate::database::log_sql(sql);
+            conn.query_row(sql, [], |row| row.get::<_, String>(0)).ok()
+        })
+        .and_then(|value| value.parse::<u32>().ok())
+        .filter(|ms| *ms <= MAX_VISUALIZER_CROSSFADE_MS);
+
+    if let Some(ms) = stored {
+        VISUALIZER_CROSSFADE_MS.store(ms, Ordering::Relaxed);
+    }
+}
+
+/// How long visualizer bars take to settle when the track changes or playback stops
+/// (default 400ms, 0 snaps immediately)
+#[tauri::command]
+pub fn set_visualizer_crossfade_ms(app_handle: tauri::AppHandle, ms: u32) -> Result<(), String> {
+    if ms > MAX_VISUALIZER_CROSSFADE_MS {
+        return Err(format!(
+            "Cross-fade must be between 0 and {} ms",
+            MAX_VISUALIZER_CROSSFADE_MS
+        ));
+    }
+
+    let conn = crate::database::get_connection(&app_handle).map_err(|e| e.to_string())?;
+    let sql = "INSERT OR REPLACE INTO settings (key, value) VALUES ('visualizer_crossfade_ms', ?1)";
+    crate::database::log_sql(sql);
+    conn.execute(sql, [ms.to_string()])
+        .map_err(|e| e.to_string())?;
+
+    VISUALIZER_CROSSFADE_MS.store(ms, Ordering::Relaxed);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_visualizer_crossfade_ms() -> u32 {
+    VISUALIZER_CROSSFADE_MS.load(Ordering::Relaxed)
+}
+
+/// Where the visualizer takes its levels from (persisted as `visualizer_source`)
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VisualizerSource {
+    /// Captured system audio
+    Real,
+    /// Generated levels that only follow the play state
+    Simulated,
+    /// Real when capture is possible, simulated otherwise
+    Auto,
+}
+
+impl VisualizerSource {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "real" => Some(Self::Real),
+            "simulated" => Some(Self::Simulated),
+            "auto" => Some(Self::Auto),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Real => "real",
+            Self::Simulated => "simulated",
+            Self::Auto => "auto",
+        }
+    }
+}
+
+static VISUALIZER_SOURCE: std::sync::RwLock<VisualizerSource> =
+    std::sync::RwLock::new(VisualizerSource::Auto);
+
+/// Whether system audio can be captured right now (backend present and permission granted).
+/// There is no capture backend yet on any platform, see `FeatureMatrix::audio_capture`.
+/// Features that only make sense on captured audio are deferred until one exists:
+/// - tempo (BPM) detection for beat-synced animations
+/// - per-output-device level calibration (rolling peak per device, persisted)
+/// - the raw FFT spectrum for plugin visualizers, behind an "audio" plugin permission
+/// - silent capture detection (`audio-capture-silent`, capture health, simulation fallback)
+fn real_capture_available() -> bool {
+    false
+}
+
+/// The source actually used: Real or Simulated, never Auto
+fn effective_visualizer_source() -> VisualizerSource {
+    let requested = VISUALIZER_SOURCE
+        .read()
+        .map(|source| *source)
+        .unwrap_or(VisualizerSource::Auto);
+
+    match requested {
+        VisualizerSource::Simulated => VisualizerSource::Simulated,
+        VisualizerSource::Real | VisualizerSource::Auto if real_capture_available() => {
+            VisualizerSource::Real
+        }
+        _ => VisualizerSource::Simulated,
+    }
+}
+
+fn load_visualizer_source(app_handle: &tauri::AppHandle) {
+    let stored = crate::database::get_connection(app_handle)
+        .ok()
+        .and_then(|conn| {
+            let sql = "SELECT value FROM settings WHERE key = 'visualizer_source'";
+            crate::database::log_sql(sql);
+            conn.query_row(sql, [], |row| row.get::<_, String>(0)).ok()
+        })
+        .and_then(|value| VisualizerSource::parse(&value));
+
+    if let Some(source) = stored {
+        if let Ok(mut current) = VISUALIZER_SOURCE.write() {
+            *current = source;
+        }
+    }
+}
+
+/// Choose whether the visualizer reacts to real audio ("real"), stays simulated
+/// ("simulated") or uses real audio when capture is permitted ("auto", the default).
+/// Returns the source that will actually be used.
+#[tauri::command]
+pub fn set_visualizer_source(
+    app_handle: tauri::AppHandle,
+    source: String,
+) -> Result<VisualizerSource, String> {
+    let parsed =
+        VisualizerSource::parse(&source).ok_or(format!("Invalid visualizer source: {}", source))?;
+
+    let conn = crate::database::get_connection(&app_handle).map_err(|e| e.to_string())?;
+    let sql = "INSERT OR REPLACE INTO settings (key, value) VALUES ('visualizer_source', ?1)";
+    crate::database::log_sql(sql);
+    conn.execute(sql, [parsed.as_str()])
+        .map_err(|e| e.to_string())?;
+
+    if let Ok(mut current) = VISUALIZER_SOURCE.write() {
+        *current = parsed;
+    }
+
+    let effective = effective_visualizer_source();
+    if parsed == VisualizerSource::Real && effective != VisualizerSource::Real {
+        log::warn!("Real audio capture is unavailable, the visualizer stays simulated");
+    }
+    Ok(effective)
+}
+
+/// The configured visualizer source ("real", "simulated" or "auto")
+#[tauri::command]
+pub fn get_visualizer_source() -> VisualizerSource {
+    VISUALIZER_SOURCE
+        .read()
+        .map(|source| *source)
+        .unwrap_or(VisualizerSource::Auto)
+}
+
+/// Artwork sent with now-playing data is limited to this size by default
+const DEFAULT_ARTWORK_MAX_DIMENSION: u32 = 512;
+/// Accepted range for set_artwork_max_dimension (0 sends artwork at full size)
+const ARTWORK_MAX_DIMENSION_RANGE: std::ops::RangeInclusive<u32> = 64..=4096;
+
+/// Longest side of artwork sent with now-playing data, in pixels (0 for full size)
+static ARTWORK_MAX_DIMENSION: AtomicU32 = AtomicU32::new(DEFAULT_ARTWORK_MAX_DIMENSION);
+
+/// Base64 artwork for now-playing data, downscaled to ARTWORK_MAX_DIMENSION so a
+/// 3000px cover doesn't travel over IPC on every track change
+fn encode_artwork(data: &[u8]) -> String {
+    let max_dimension = ARTWORK_MAX_DIMENSION.load(Ordering::Relaxed);
+    if max_dimension == 0 {
+        return base64_encode(data);
+    }
+
+    match crate::utils::downscale_image(data, max_dimension) {
+        Some(resized) => base64_encode(&resized),
+        None => base64_encode(data),
+    }
+}
+
+fn load_artwork_max_dimension(app_handle: &tauri::AppHandle) {
+    let stored = crate::database::get_connection(app_handle)
+        .ok()
+        .and_then(|conn| {
+            let sql = "SELECT value FROM settings WHERE key = 'artwork_max_dimension'";
+            crate::database::log_sql(sql);
+            conn.query_row(sql, [], |row| row.get::<_, String>(0)).ok()
+        })
+        .and_then(|value| value.parse::<u32>().ok())
+        .filter(|px| *px == 0 || ARTWORK_MAX_DIMENSION_RANGE.contains(px));
+
+    if let Some(px) = stored {
+        ARTWORK_MAX_DIMENSION.store(px, Ordering::Relaxed);
+    }
+}
+
+/// Limit the artwork sent with now-playing data to `px` on its longest side (default 512,
+/// 0 for full size). get_high_res_artwork always returns full size for the expanded view.
+#[tauri::command]
+pub fn set_artwork_max_dimension(app_handle: tauri::AppHandle, px: u32) -> Result<(), String> {
+    if px != 0 && !ARTWORK_MAX_DIMENSION_RANGE.contains(&px) {
+        return Err(format!(
+            "Artwork size must be 0 (full size) or between {} and {} px",
+            ARTWORK_MAX_DIMENSION_RANGE.start(),
+            ARTWORK_MAX_DIMENSION_RANGE.end()
+        ));
+    }
+
+    let conn = crate::database::get_connection(&app_handle).map_err(|e| e.to_string())?;
+    let sql = "INSERT OR REPLACE INTO settings (key, value) VALUES ('artwork_max_dimension', ?1)";
+    crate::database::log_sql(sql);
+    conn.execute(sql, [px.to_string()])
+        .map_err(|e| e.to_string())?;
+
+    ARTWORK_MAX_DIMENSION.store(px, Ordering::Relaxed);
+    // Re-fetch the current artwork at the new size on the next query
+    clear_track_cache();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_artwork_max_dimension() -> u32 {
+    ARTWORK_MAX_DIMENSION.load(Ordering::Relaxed)
+}
+
 fn get_cached_track() -> (Option<String>, Option<String>, Option<String>) {
     TRACK_CACHE
         .get()
@@ -63,12 +418,82 @@ fn is_track_changed(title: &Option<String>, artist: &Option<String>) -> bool {
     cached.0 != *title || cached.1 != *artist
 }
 
+/// Forget the cached track and artwork, returns the number of entries dropped
+pub fn clear_track_cache() -> usize {
+    let cached = get_cached_track();
+    set_cached_track(None, None, None);
+    usize::from(cached.0.is_some() || cached.1.is_some() || cached.2.is_some())
+}
+
+/// Forget the last played track, returns the number of entries dropped
+pub fn clear_last_played() -> usize {
+    LAST_PLAYED
+        .get()
+        .and_then(|m| m.lock().ok())
+        .map(|mut guard| usize::from(guard.take().is_some()))
+        .unwrap_or(0)
+}
+
 fn save_last_played(data: &NowPlayingData) {
     if let Some(m) = LAST_PLAYED.get() {
         *m.lock().unwrap() = Some(data.clone());
     }
 }
 
+/// Explicit featuring separators inside a single artist string. Commas, semicolons and " & "
+/// are left alone since they are part of names like "Tyler, The Creator" or
+/// "Earth, Wind & Fire"; sources that know the individual artists report a list instead.
+const ARTIST_SEPARATORS: [&str; 5] = [" feat. ", " Feat. ", " ft. ", " Ft. ", " featuring "];
+
+/// Trim and collapse whitespace in each name, dropping empties and case-insensitive duplicates
+fn dedup_artists<'a>(names: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut artists: Vec<String> = Vec::new();
+    for name in names {
+        let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+        let lower = name.to_lowercase();
+        if !name.is_empty() && !artists.iter().any(|a| a.to_lowercase() == lower) {
+            artists.push(name);
+        }
+    }
+    artists
+}
+
+/// Split one artist string ("A feat. B") into de-duplicated names
+fn split_artists(artist: &str) -> Vec<String> {
+    let mut parts = vec![artist.to_string()];
+    for separator in ARTIST_SEPARATORS {
+        parts = parts
+            .iter()
+            .flat_map(|part| part.split(separator))
+            .map(str::to_string)
+            .collect();
+    }
+    dedup_artists(parts.iter().map(String::as_str))
+}
+
+/// (artist, artists) fields for a source that reports one artist string.
+/// The string is kept as the app formats it (e.g. "A feat. B"), only whitespace is normalized.
+fn artist_fields_from_string(artist: Option<String>) -> (Option<String>, Option<Vec<String>>) {
+    let artist = artist
+        .map(|a| a.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|a| !a.is_empty());
+    let artists = artist.as_deref().map(split_artists);
+    (artist, artists)
+}
+
+/// (artist, artists) fields for a source that reports a list of artists (MPRIS)
+#[cfg(target_os = "linux")]
+fn artist_fields_from_list<'a>(
+    names: impl IntoIterator<Item = &'a str>,
+) -> (Option<String>, Option<Vec<String>>) {
+    let artists = dedup_artists(names);
+    if artists.is_empty() {
+        (None, None)
+    } else {
+        (Some(artists.join(", ")), Some(artists))
+    }
+}
+
 fn get_last_played_or_default(levels: Vec<f64>) -> NowPlayingData {
     if let Some(m) = LAST_PLAYED.get() {
         if let Ok(guard) = m.lock() {
@@ -86,407 +511,874 @@ fn get_last_played_or_default(levels: Vec<f64>) -> NowPlayingData {
     }
 }
 
-/// Get currently playing music information
-/// Tries multiple sources: Spotify, Music.app, Safari
-#[tauri::command]
-pub async fn get_now_playing() -> NowPlayingData {
-    #[cfg(target_os = "macos")]
-    {
-        use std::process::Command;
-        use sysinfo::System;
+/// Payload for the `track-ending` event
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TrackEnding {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    /// Seconds left in the track
+    pub remaining: f64,
+}
 
-        // Static system monitor to avoid re-initialization
-        static SYSTEM: std::sync::OnceLock<std::sync::Mutex<System>> = std::sync::OnceLock::new();
+fn load_track_ending_threshold(app_handle: &tauri::AppHandle) {
+    let stored = crate::database::get_connection(app_handle)
+        .ok()
+        .and_then(|conn| {
+            let sql = "SELECT value FROM settings WHERE key = 'track_ending_threshold_secs'";
+            crate::database::log_sql(sql);
+            conn.query_row(sql, [], |row| row.get::<_, String>(0)).ok()
+        })
+        .and_then(|value| value.parse::<u32>().ok());
+
+    if let Some(seconds) = stored {
+        TRACK_ENDING_THRESHOLD_SECS.store(seconds, Ordering::Relaxed);
+    }
+}
+
+/// Set how many seconds before the end of a track `track-ending` is emitted (default 5, persisted)
+#[tauri::command]
+pub fn set_track_ending_threshold(
+    app_handle: tauri::AppHandle,
+    seconds: u32,
+) -> Result<(), String> {
+    let conn = crate::database::get_connection(&app_handle).map_err(|e| e.to_string())?;
+    let sql =
+        "INSERT OR REPLACE INTO settings (key, value) VALUES ('track_ending_threshold_secs', ?1)";
+    crate::database::log_sql(sql);
+    conn.execute(sql, [seconds.to_string()])
+        .map_err(|e| e.to_string())?;
+
+    TRACK_ENDING_THRESHOLD_SECS.store(seconds, Ordering::Relaxed);
+    Ok(())
+}
 
-        let apps_running = {
-            let sys_lock = SYSTEM.get_or_init(|| std::sync::Mutex::new(System::new_all()));
+/// Emit `track-ending` once per track when elapsed time gets within the threshold of the duration
+fn check_track_ending(app_handle: &tauri::AppHandle, data: &NowPlayingData, track_changed: bool) {
+    if track_changed {
+        TRACK_ENDING_FIRED.store(false, Ordering::Relaxed);
+    }
 
-            if let Ok(mut sys) = sys_lock.lock() {
-                sys.refresh_all();
-                let processes = sys.processes();
+    let (duration, elapsed) = match (data.duration, data.elapsed_time) {
+        (Some(d), Some(e)) if data.is_playing && d > 0.0 => (d, e),
+        _ => return,
+    };
 
-                let spotify = processes.values().any(|p| p.name() == "Spotify");
-                let music = processes.values().any(|p| p.name() == "Music");
-                let safari = processes.values().any(|p| p.name() == "Safari");
+    let remaining = duration - elapsed;
+    let threshold = TRACK_ENDING_THRESHOLD_SECS.load(Ordering::Relaxed) as f64;
 
-                Some((spotify, music, safari))
-            } else {
-                None
-            }
-        };
+    if remaining >= 0.0
+        && remaining <= threshold
+        && !TRACK_ENDING_FIRED.swap(true, Ordering::Relaxed)
+    {
+        let _ = app_handle.emit(
+            "track-ending",
+            TrackEnding {
+                title: data.title.clone(),
+                artist: data.artist.clone(),
+                remaining,
+            },
+        );
+    }
+}
 
-        let (spotify_running, music_running, safari_running) =
-            apps_running.unwrap_or((false, false, false));
+/// Static system monitor to avoid re-initialization (macOS)
+#[cfg(target_os = "macos")]
+static SYSTEM: std::sync::OnceLock<std::sync::Mutex<sysinfo::System>> = std::sync::OnceLock::new();
 
-        // If no relevant apps are running, return early with no overhead
-        if !spotify_running && !music_running && !safari_running {
-            IS_PLAYING.store(false, Ordering::Relaxed);
-            return get_last_played_or_default(get_audio_levels());
+/// Check which of the given process names are currently running (macOS)
+#[cfg(target_os = "macos")]
+fn processes_running<const N: usize>(names: [&str; N]) -> [bool; N] {
+    let mut running = [false; N];
+    let sys_lock = SYSTEM.get_or_init(|| std::sync::Mutex::new(sysinfo::System::new_all()));
+
+    if let Ok(mut sys) = sys_lock.lock() {
+        sys.refresh_all();
+        for process in sys.processes().values() {
+            for (i, name) in names.iter().enumerate() {
+                if process.name() == *name {
+                    running[i] = true;
+                }
+            }
         }
+    }
 
-        // Default to not playing before check
-        IS_PLAYING.store(false, Ordering::Relaxed);
+    running
+}
 
-        // Build dynamic script based on running apps only
-        let mut script = String::new();
+/// Get currently playing music information
+/// Tries multiple sources: Spotify, Music.app, Safari. When several are playing,
+/// the one with a moving position, audible output and longest playback wins
+#[tauri::command]
+pub async fn get_now_playing(app_handle: tauri::AppHandle) -> NowPlayingData {
+    let data = query_now_playing(app_handle.clone()).await;
+    notify_playback_state(&app_handle);
+    data
+}
 
-        // Check Spotify
-        if spotify_running {
-            script.push_str(r#"
-                tell application "Spotify"
-                    if player state is playing then
-                        set trackName to name of current track
-                        set artistName to artist of current track
-                        set albumName to album of current track
-                        set trackDuration to (duration of current track) / 1000
-                        set trackPosition to player position
-                        set artUrl to artwork url of current track
-                        return "playing|" & trackName & "|" & artistName & "|" & albumName & "|" & trackDuration & "|" & trackPosition & "|" & artUrl & "|spotify"
-                    end if
-                end tell
-            "#);
-        }
+/// Whether `playback-state-changed` events are wanted
+static PLAYBACK_STATE_SUBSCRIBED: AtomicBool = AtomicBool::new(false);
 
-        // Check Music
-        if music_running {
-            script.push_str(r#"
-                tell application "Music"
-                    if player state is playing then
-                        set trackName to name of current track
-                        set artistName to artist of current track
-                        set albumName to album of current track
-                        set trackDuration to duration of current track
-                        set trackPosition to player position
-                        return "playing|" & trackName & "|" & artistName & "|" & albumName & "|" & trackDuration & "|" & trackPosition & "||music"
-                    end if
-                end tell
-            "#);
-        }
+/// Last play state sent as `playback-state-changed`
+static LAST_PLAYBACK_STATE: AtomicBool = AtomicBool::new(false);
 
-        // Check Safari
-        if safari_running {
-            script.push_str(r#"
-                tell application "Safari"
-                    try
-                        repeat with w in windows
-                            repeat with t in tabs of w
-                                try
-                                    set tabURL to URL of t
-                                    set tabName to name of t
+/// Payload for `playback-state-changed`
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PlaybackState {
+    pub is_playing: bool,
+}
 
-                                    if tabURL contains "youtube.com" or tabURL contains "music.youtube.com" or tabURL contains "open.spotify.com" or tabURL contains "soundcloud.com" then
-                                        try
-                                            set jsResult to do JavaScript "
-                                                (function() {
-                                                    var video = document.querySelector('video');
-                                                    var audio = document.querySelector('audio');
-                                                    var activeMedia = (video && !video.paused && !video.ended) ? video : (audio && !audio.paused && !audio.ended) ? audio : null;
-
-                                                    if (!activeMedia) return 'paused';
-
-                                                    var duration = activeMedia.duration || '';
-                                                    var currentTime = activeMedia.currentTime || '';
-
-                                                    var art = '';
-                                                    try {
-                                                        if (location.hostname.includes('spotify')) {
-                                                            var img = document.querySelector('img[alt^=\"Now playing\"]') || document.querySelector('img[data-testid=\"cover-art-image\"]');
-                                                            if (img) art = img.src;
-                                                        } else if (location.hostname.includes('music.youtube')) {
-                                                            var img = document.querySelector('.ytmusic-player-bar.style-scope img');
-                                                            if (img) art = img.src;
-                                                        }
-
-                                                        if (!art) {
-                                                            var icon = document.querySelector('link[rel*=\"icon\"]');
-                                                            if (icon) art = icon.href;
-                                                        }
-                                                    } catch (e) {}
-
-                                                    return 'playing|' + art + '|' + duration + '|' + currentTime;
-                                                })();
-                                            " in t
-
-                                            if jsResult starts with "playing" then
-                                                set AppleScript's text item delimiters to "|"
-                                                set jsParts to text items of jsResult
-                                                set artUrl to ""
-                                                set trackDuration to ""
-                                                set trackPosition to ""
-
-                                                if (count of jsParts) > 1 then
-                                                    set artUrl to item 2 of jsParts
-                                                end if
-                                                if (count of jsParts) > 2 then
-                                                    set trackDuration to item 3 of jsParts
-                                                end if
-                                                if (count of jsParts) > 3 then
-                                                    set trackPosition to item 4 of jsParts
-                                                end if
-
-                                                return "playing|" & tabName & "|Safari|" & tabURL & "|" & trackDuration & "|" & trackPosition & "|" & artUrl & "|safari"
-                                            end if
-                                        on error
-                                            if tabName starts with "▶" then
-                                                return "playing|" & tabName & "|Safari|" & tabURL & "|||safari"
-                                            end if
-                                        end try
-                                    end if
-                                end try
-                            end repeat
-                        end repeat
-                    end try
-                end tell
-            "#);
-        }
+/// Emit `playback-state-changed` if IS_PLAYING flipped since the last emit.
+/// Called once per now-playing query, after IS_PLAYING has settled.
+fn notify_playback_state(app_handle: &tauri::AppHandle) {
+    if !PLAYBACK_STATE_SUBSCRIBED.load(Ordering::Relaxed) {
+        return;
+    }
 
-        script.push_str("\nreturn \"not_playing\"");
+    let is_playing = IS_PLAYING.load(Ordering::Relaxed);
+    if LAST_PLAYBACK_STATE.swap(is_playing, Ordering::Relaxed) != is_playing {
+        let _ = app_handle.emit("playback-state-changed", PlaybackState { is_playing });
+    }
+}
 
-        if let Ok(result) = Command::new("osascript").arg("-e").arg(&script).output() {
-            let stdout = String::from_utf8_lossy(&result.stdout).trim().to_string();
-            let parts: Vec<&str> = stdout.split('|').collect();
+/// Emit `playback-state-changed` ({ is_playing }) whenever playback starts or stops,
+/// as detected by the regular now-playing queries. The current state is emitted right away.
+#[tauri::command]
+pub fn subscribe_playback_state(app_handle: tauri::AppHandle) {
+    let is_playing = IS_PLAYING.load(Ordering::Relaxed);
+    LAST_PLAYBACK_STATE.store(is_playing, Ordering::Relaxed);
+    PLAYBACK_STATE_SUBSCRIBED.store(true, Ordering::Relaxed);
 
-            if parts.len() >= 8 && parts[0] == "playing" {
-                IS_PLAYING.store(true, Ordering::Relaxed);
-                let app_id = parts.last().unwrap_or(&"");
+    let _ = app_handle.emit("playback-state-changed", PlaybackState { is_playing });
+}
 
-                let title = if parts[1].is_empty() {
-                    None
-                } else {
-                    Some(parts[1].to_string())
-                };
-                let artist = if parts[2].is_empty() {
-                    None
-                } else {
-                    Some(parts[2].to_string())
-                };
+#[tauri::command]
+pub fn unsubscribe_playback_state() {
+    PLAYBACK_STATE_SUBSCRIBED.store(false, Ordering::Relaxed);
+}
 
-                // Fetch artwork if track changed
-                let artwork = if is_track_changed(&title, &artist) {
-                    if *app_id == "music" {
-                        get_music_app_artwork()
-                    } else if !parts[6].is_empty() {
-                        let art = fetch_artwork_from_url(parts[6]);
-                        art
-                    } else {
-                        None
-                    }
-                } else {
-                    get_cached_track().2
-                };
+/// A source reporting playback, before one is picked as now playing
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+struct PlayingSource {
+    /// Track info without artwork (fetched only for the picked source)
+    data: NowPlayingData,
+    /// Short source id (spotify, music, safari, or the MPRIS player name)
+    app_id: String,
+    artwork_url: Option<String>,
+    /// Muted or at zero volume, e.g. a background tab
+    muted: bool,
+    /// Site icon of a browser tab source
+    favicon_url: Option<String>,
+}
 
-                if is_track_changed(&title, &artist) {
-                    set_cached_track(title.clone(), artist.clone(), artwork.clone());
-                }
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+impl PlayingSource {
+    /// Identifies the same playback across queries. Dedicated players are one source each
+    /// (their album changes with every track); Safari tabs are told apart by their URL, which
+    /// is reported in the album field.
+    fn key(&self) -> String {
+        if self.app_id == "safari" {
+            format!(
+                "{}|{}",
+                self.app_id,
+                self.data.album.as_deref().unwrap_or_default()
+            )
+        } else {
+            self.app_id.clone()
+        }
+    }
+}
 
-                let data = NowPlayingData {
-                    title: if *app_id == "safari" {
-                        // Clean YouTube title
-                        title.map(|t| t.trim_end_matches(" - YouTube").to_string())
-                    } else {
-                        title
-                    },
-                    artist,
-                    album: if parts[3].is_empty() {
-                        None
-                    } else {
-                        Some(parts[3].to_string())
-                    },
-                    artwork_base64: artwork,
-                    duration: parts[4].replace(',', ".").parse().ok(),
-                    elapsed_time: parts[5].replace(',', ".").parse().ok(),
-                    is_playing: true,
-                    audio_levels: Some(get_audio_levels_internal()),
-                    app_name: match *app_id {
-                        "spotify" => Some("Spotify".to_string()),
-                        "music" => Some("Music".to_string()),
-                        "safari" => Some("Safari".to_string()),
-                        _ => None,
-                    },
-                };
+/// When a playing source was first seen and when its position last moved
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+struct SourceActivity {
+    position: Option<f64>,
+    moved_at: std::time::Instant,
+    playing_since: std::time::Instant,
+}
 
-                save_last_played(&data);
-                return data;
-            }
-        }
+/// Activity of the sources seen playing in the last query, keyed by PlayingSource::key
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+static SOURCE_ACTIVITY: std::sync::OnceLock<
+    std::sync::Mutex<std::collections::HashMap<String, SourceActivity>>,
+> = std::sync::OnceLock::new();
 
-        get_last_played_or_default(get_audio_levels())
-    }
+/// A source whose position hasn't moved for this long is treated as stale
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+const STALE_POSITION_AFTER: std::time::Duration = std::time::Duration::from_secs(5);
 
-    #[cfg(target_os = "windows")]
-    {
-        use windows::Media::Control::GlobalSystemMediaTransportControlsSessionManager;
-        use windows::Storage::Streams::DataReader;
+/// Score a playing source: moving position and audible output matter most,
+/// then how long it has been playing, then dedicated players over browser tabs
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn score_source(source: &PlayingSource, activity: &SourceActivity) -> f64 {
+    let mut score = 0.0;
 
-        if let Ok(manager) = GlobalSystemMediaTransportControlsSessionManager::RequestAsync() {
-            if let Ok(manager) = manager.await {
-                if let Ok(session) = manager.GetCurrentSession() {
-                    if let Ok(properties) = session.TryGetMediaPropertiesAsync().unwrap().await {
-                        let title = properties.Title().ok().map(|h| h.to_string());
-                        let artist = properties.Artist().ok().map(|h| h.to_string());
-                        let album = properties.AlbumTitle().ok().map(|h| h.to_string());
+    if source.muted {
+        score -= 100.0;
+    }
 
-                        // Check playback status
-                        let playback_info = session.GetPlaybackInfo().unwrap();
-                        let is_playing = playback_info.PlaybackStatus().unwrap() == windows::Media::Control::GlobalSystemMediaTransportControlsSessionPlaybackStatus::Playing;
+    score += match source.data.elapsed_time {
+        Some(_) if activity.moved_at.elapsed() < STALE_POSITION_AFTER => 50.0,
+        Some(_) => 0.0,
+        // No position reported, so staleness can't be judged
+        None => 25.0,
+    };
 
-                        // Get timeline
-                        let timeline = session.GetTimelineProperties().unwrap();
-                        let duration = timeline
-                            .EndTime()
-                            .ok()
-                            .map(|t| t.Duration as f64 / 10_000_000.0);
-                        let position = timeline
-                            .Position()
-                            .ok()
-                            .map(|t| t.Duration as f64 / 10_000_000.0);
+    // Up to 10 points for ten minutes of continuous playback
+    score += activity.playing_since.elapsed().as_secs_f64().min(600.0) / 60.0;
 
-                        // Artwork
-                        // Getting stream from IRandomAccessStreamReference
-                        let mut artwork_base64 = None;
-                        if let Ok(thumb_ref) = properties.Thumbnail() {
-                            if let Ok(stream) = thumb_ref.OpenReadAsync().unwrap().await {
-                                let size = stream.Size().unwrap() as usize;
-                                let reader = DataReader::CreateDataReader(&stream).unwrap();
-                                if reader.LoadAsync(size as u32).unwrap().await.is_ok() {
-                                    let mut buffer = vec![0u8; size];
-                                    if reader.ReadBytes(&mut buffer).is_ok() {
-                                        artwork_base64 = Some(base64_encode(&buffer));
-                                    }
-                                }
-                            }
-                        }
+    if source.app_id != "safari" {
+        score += 1.0;
+    }
 
-                        IS_PLAYING.store(is_playing, Ordering::Relaxed);
+    score
+}
 
-                        let data = NowPlayingData {
-                            title,
-                            artist,
-                            album,
-                            artwork_base64,
-                            duration,
-                            elapsed_time: position,
-                            is_playing,
-                            audio_levels: Some(get_audio_levels_internal()),
-                            app_name: Some("System".to_string()),
-                        };
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn source_activity_store(
+) -> &'static std::sync::Mutex<std::collections::HashMap<String, SourceActivity>> {
+    SOURCE_ACTIVITY.get_or_init(|| std::sync::Mutex::new(std::collections::HashMap::new()))
+}
 
-                        save_last_played(&data);
-                        return data;
-                    }
-                }
-            }
+/// Record which sources are playing and whose position moved. Only the now-playing query
+/// calls this, so listing sources in between doesn't skew the staleness tracking.
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn update_source_activity(sources: &[PlayingSource]) {
+    use std::time::Instant;
+
+    let mut activity = match source_activity_store().lock() {
+        Ok(guard) => guard,
+        Err(_) => return,
+    };
+
+    // Forget sources that stopped, so their playing time starts over when they resume
+    let keys: std::collections::HashSet<String> = sources.iter().map(|s| s.key()).collect();
+    activity.retain(|key, _| keys.contains(key));
+
+    let now = Instant::now();
+    for source in sources {
+        let entry = activity.entry(source.key()).or_insert(SourceActivity {
+            position: source.data.elapsed_time,
+            moved_at: now,
+            playing_since: now,
+        });
+
+        let moved = match (entry.position, source.data.elapsed_time) {
+            (Some(previous), Some(current)) => (current - previous).abs() > 0.01,
+            _ => false,
+        };
+        if moved {
+            entry.moved_at = now;
         }
-
-        get_last_played_or_default(get_audio_levels())
+        entry.position = source.data.elapsed_time;
     }
+}
 
+/// Sort the sources best first by their recorded activity, without changing it. Sources
+/// update_source_activity hasn't seen yet count as just started.
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn rank_sources(sources: Vec<PlayingSource>) -> Vec<PlayingSource> {
+    let now = std::time::Instant::now();
+    let activity = match source_activity_store().lock() {
+        Ok(guard) => guard,
+        Err(_) => return sources,
+    };
+
+    let mut scored: Vec<(f64, PlayingSource)> = sources
+        .into_iter()
+        .map(|source| {
+            let score = match activity.get(&source.key()) {
+                Some(entry) => score_source(&source, entry),
+                None => score_source(
+                    &source,
+                    &SourceActivity {
+                        position: source.data.elapsed_time,
+                        moved_at: now,
+                        playing_since: now,
+                    },
+                ),
+            };
+            (score, source)
+        })
+        .collect();
+
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored.into_iter().map(|(_, source)| source).collect()
+}
+
+/// Artwork for a picked source (Music.app artwork is extracted locally on macOS)
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn fetch_source_artwork(
+    app_handle: &tauri::AppHandle,
+    app_id: &str,
+    artwork_url: Option<&str>,
+) -> Option<String> {
+    #[cfg(target_os = "macos")]
+    if app_id == "music" {
+        return get_music_app_artwork(app_handle).map(|data| encode_artwork(&data));
+    }
     #[cfg(target_os = "linux")]
-    {
-        // Use zbus to query MPRIS
-        // This is a simplified implementation, ideally we'd iterate over names
-        use zbus::zvariant::Value;
-        use zbus::{proxy, Connection};
+    let _ = (&app_handle, &app_id);
 
-        // Define a simple proxy for MPRIS Player
-        #[proxy(
-            interface = "org.mpris.MediaPlayer2.Player",
-            default_path = "/org/mpris/MediaPlayer2"
-        )]
-        trait Player {
-            #[zbus(property)]
-            fn playback_status(&self) -> zbus::Result<String>;
-            #[zbus(property)]
-            fn metadata(
-                &self,
-            ) -> zbus::Result<std::collections::HashMap<String, zbus::zvariant::OwnedValue>>;
-            #[zbus(property)]
-            fn position(&self) -> zbus::Result<i64>;
+    artwork_url
+        .and_then(crate::utils::fetch_artwork_bytes)
+        .map(|data| encode_artwork(&data))
+}
+
+/// Classify what's playing as "music", "podcast" or "audiobook" from the player's media kind
+/// (Music.app), track id (Spotify) or genre, defaulting to "music"
+fn content_type_from_hints(kind: Option<&str>, genre: Option<&str>) -> String {
+    for hint in [kind, genre].into_iter().flatten() {
+        let hint = hint.to_lowercase();
+        if hint.contains("podcast") || hint.contains("episode") {
+            return "podcast".to_string();
         }
+        if hint.contains("audiobook") || hint == "book" {
+            return "audiobook".to_string();
+        }
+    }
+    "music".to_string()
+}
 
-        if let Ok(conn) = Connection::session().await {
-            // Find a media player (simplified: grabbing first one or a specific one)
-            // Real impl should list names org.mpris.MediaPlayer2.*
+/// Turn the best playing source into the now-playing result, or fall back to the last played track
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn finish_now_playing(
+    app_handle: &tauri::AppHandle,
+    sources: Vec<PlayingSource>,
+) -> NowPlayingData {
+    update_source_activity(&sources);
+    let best = match rank_sources(sources).into_iter().next() {
+        Some(best) => best,
+        None => {
+            IS_PLAYING.store(false, Ordering::Relaxed);
+            return get_last_played_or_default(get_audio_levels());
+        }
+    };
+    IS_PLAYING.store(true, Ordering::Relaxed);
+
+    let PlayingSource {
+        mut data,
+        app_id,
+        artwork_url,
+        favicon_url,
+        ..
+    } = best;
+
+    if let Ok(mut favicon) = CURRENT_SOURCE_FAVICON.write() {
+        *favicon = favicon_url;
+    }
 
-            // We can list names
-            let proxy = zbus::fdo::DBusProxy::new(&conn).await.unwrap();
-            let names = proxy.list_names().await.unwrap();
+    let track_changed = is_track_changed(&data.title, &data.artist);
 
-            for name in names {
-                if name.starts_with("org.mpris.MediaPlayer2.") {
-                    let player = PlayerProxy::builder(&conn)
-                        .destination(name.clone())
-                        .unwrap()
-                        .build()
-                        .await
-                        .unwrap();
+    // Fetch artwork if track changed
+    let artwork = if track_changed {
+        fetch_source_artwork(app_handle, &app_id, artwork_url.as_deref())
+    } else {
+        get_cached_track().2
+    };
 
-                    if let Ok(status) = player.playback_status().await {
-                        if status == "Playing" {
-                            IS_PLAYING.store(true, Ordering::Relaxed);
+    if track_changed {
+        set_cached_track(data.title.clone(), data.artist.clone(), artwork.clone());
+    }
 
-                            let mut title = None;
-                            let mut artist = None;
-                            let mut album = None;
-                            let mut duration = None;
-                            let mut artwork_url = None;
+    data.artwork_base64 = artwork;
+    data.audio_levels = Some(get_audio_levels_internal());
 
-                            if let Ok(metadata) = player.metadata().await {
-                                if let Some(t) = metadata.get("xesam:title") {
-                                    if let Value::Str(v) = &**t {
-                                        title = Some(v.to_string());
-                                    }
-                                }
-                                if let Some(a) = metadata.get("xesam:artist") {
-                                    // artist is often array of strings
-                                    if let Value::Array(v) = &**a {
-                                        if let Ok(Some(Value::Str(s))) = v.get(0) {
-                                            artist = Some(s.to_string());
-                                        }
-                                    }
-                                }
-                                if let Some(a) = metadata.get("xesam:album") {
-                                    if let Value::Str(v) = &**a {
-                                        album = Some(v.to_string());
-                                    }
-                                }
-                                if let Some(d) = metadata.get("mpris:length") {
-                                    if let Value::I64(v) = &**d {
-                                        duration = Some(*v as f64 / 1_000_000.0);
-                                    } else if let Value::U64(v) = &**d {
-                                        duration = Some(*v as f64 / 1_000_000.0);
-                                    }
-                                }
-                                if let Some(u) = metadata.get("mpris:artUrl") {
-                                    if let Value::Str(v) = &**u {
-                                        artwork_url = Some(v.to_string());
+    if track_changed {
+        VISUALIZER_TRACK_CHANGED.store(true, Ordering::Relaxed);
+    }
+    check_track_ending(app_handle, &data, track_changed);
+    crate::scrobble::track_progress(app_handle, &data, track_changed);
+    crate::play_history::record_play(app_handle, &data, track_changed);
+    save_last_played(&data);
+    data
+}
+
+/// Parse one `playing|title|artist|album|duration|position|artUrl|app|muted[|genre|year|output|kind]`
+/// line, Safari tabs end in `muted|favicon` instead (macOS)
+#[cfg(target_os = "macos")]
+fn parse_playing_source(line: &str) -> Option<PlayingSource> {
+    let parts: Vec<&str> = line.split('|').collect();
+    if parts.len() < 9 || parts[0] != "playing" {
+        return None;
+    }
+
+    let non_empty = |s: &str| (!s.is_empty()).then(|| s.to_string());
+    let app_id = parts[7];
+
+    let title = non_empty(parts[1]);
+    let title = if app_id == "safari" {
+        // Clean YouTube title
+        title.map(|t| t.trim_end_matches(" - YouTube").to_string())
+    } else {
+        title
+    };
+
+    let (artist, artists) = artist_fields_from_string(non_empty(parts[2]));
+
+    // Fields after `muted` are track details for players, the favicon for Safari tabs
+    let track_extra = |index: usize| parts.get(index).copied().filter(|_| app_id != "safari");
+    let genre = track_extra(9).and_then(|genre| non_empty(genre));
+    let content_type = content_type_from_hints(track_extra(12), genre.as_deref());
+
+    Some(PlayingSource {
+        data: NowPlayingData {
+            title,
+            artist,
+            artists,
+            album: non_empty(parts[3]),
+            genre,
+            // Music reports 0 when the year is unknown
+            year: track_extra(10)
+                .and_then(|year| year.trim().parse().ok())
+                .filter(|year| *year > 0),
+            output_target: track_extra(11).and_then(|target| non_empty(target)),
+            artwork_base64: None,
+            duration: parse_locale_f64(parts[4]),
+            elapsed_time: parse_locale_f64(parts[5]),
+            is_playing: true,
+            audio_levels: None,
+            app_name: match app_id {
+                "spotify" => Some("Spotify".to_string()),
+                "music" => Some("Music".to_string()),
+                "safari" => Some("Safari".to_string()),
+                _ => None,
+            },
+            content_type: Some(content_type),
+        },
+        app_id: app_id.to_string(),
+        artwork_url: non_empty(parts[6]),
+        muted: parts[8] == "true",
+        favicon_url: if app_id == "safari" {
+            parts.get(9).and_then(|favicon| non_empty(favicon))
+        } else {
+            None
+        },
+    })
+}
+
+/// Every source currently playing: Spotify, Music.app and media tabs in Safari (macOS)
+#[cfg(target_os = "macos")]
+fn query_playing_sources(app_handle: &tauri::AppHandle) -> Vec<PlayingSource> {
+    let [spotify_running, music_running, safari_running] =
+        processes_running(["Spotify", "Music", "Safari"]);
+
+    // If no relevant apps are running, return early with no overhead
+    if !spotify_running && !music_running && !safari_running {
+        return Vec::new();
+    }
+
+    // Build dynamic script based on running apps only, collecting one line per playing source
+    let mut script = String::from("set playingSources to {}\n");
+
+    // Check Spotify
+    if spotify_running {
+        script.push_str(r#"
+            tell application "Spotify"
+                if player state is playing then
+                    set trackName to name of current track
+                    set artistName to artist of current track
+                    set albumName to album of current track
+                    set trackDuration to (duration of current track) / 1000
+                    set trackPosition to player position
+                    set artUrl to artwork url of current track
+                    set isMuted to (sound volume is 0)
+                    -- Podcast episodes have spotify:episode: ids
+                    set trackKind to ""
+                    try
+                        if (spotify url of current track) starts with "spotify:episode:" then set trackKind to "episode"
+                    end try
+                    set end of playingSources to "playing|" & trackName & "|" & artistName & "|" & albumName & "|" & trackDuration & "|" & trackPosition & "|" & artUrl & "|spotify|" & (isMuted as text) & "||||" & trackKind
+                end if
+            end tell
+        "#);
+    }
+
+    // Check Music
+    if music_running {
+        script.push_str(r#"
+            tell application "Music"
+                if player state is playing then
+                    set trackName to name of current track
+                    set artistName to artist of current track
+                    set albumName to album of current track
+                    set trackDuration to duration of current track
+                    set trackPosition to player position
+                    set isMuted to (mute or sound volume is 0)
+                    -- Optional extras, a failure here must not lose the track
+                    set trackGenre to ""
+                    set trackYear to ""
+                    set outputTarget to ""
+                    set trackKind to ""
+                    try
+                        set trackGenre to genre of current track
+                        set trackYear to (year of current track) as text
+                    end try
+                    try
+                        set trackKind to (media kind of current track) as text
+                    end try
+                    try
+                        set AppleScript's text item delimiters to ", "
+                        set outputTarget to (name of current AirPlay devices) as text
+                    end try
+                    set AppleScript's text item delimiters to ""
+                    set end of playingSources to "playing|" & trackName & "|" & artistName & "|" & albumName & "|" & trackDuration & "|" & trackPosition & "||music|" & (isMuted as text) & "|" & trackGenre & "|" & trackYear & "|" & outputTarget & "|" & trackKind
+                end if
+            end tell
+        "#);
+    }
+
+    // Check Safari
+    if safari_running {
+        script.push_str(r#"
+            tell application "Safari"
+                try
+                    repeat with w in windows
+                        repeat with t in tabs of w
+                            try
+                                set tabURL to URL of t
+                                set tabName to name of t
+
+                                if tabURL contains "youtube.com" or tabURL contains "music.youtube.com" or tabURL contains "open.spotify.com" or tabURL contains "soundcloud.com" then
+                                    try
+                                        set jsResult to do JavaScript "
+                                            (function() {
+                                                var video = document.querySelector('video');
+                                                var audio = document.querySelector('audio');
+                                                var activeMedia = (video && !video.paused && !video.ended) ? video : (audio && !audio.paused && !audio.ended) ? audio : null;
+
+                                                if (!activeMedia) return 'paused';
+
+                                                var duration = activeMedia.duration || '';
+                                                var currentTime = activeMedia.currentTime || '';
+                                                var muted = activeMedia.muted || activeMedia.volume === 0;
+
+                                                var art = '';
+                                                var favicon = '';
+                                                try {
+                                                    if (location.hostname.includes('spotify')) {
+                                                        var img = document.querySelector('img[alt^=\"Now playing\"]') || document.querySelector('img[data-testid=\"cover-art-image\"]');
+                                                        if (img) art = img.src;
+                                                    } else if (location.hostname.includes('music.youtube')) {
+                                                        var img = document.querySelector('.ytmusic-player-bar.style-scope img');
+                                                        if (img) art = img.src;
+                                                    }
+
+                                                    var icon = document.querySelector('link[rel*=\"icon\"]');
+                                                    if (icon) favicon = icon.href;
+                                                    if (!art) art = favicon;
+                                                } catch (e) {}
+
+                                                return 'playing|' + art + '|' + duration + '|' + currentTime + '|' + muted + '|' + favicon;
+                                            })();
+                                        " in t
+
+                                        if jsResult starts with "playing" then
+                                            set AppleScript's text item delimiters to "|"
+                                            set jsParts to text items of jsResult
+                                            set artUrl to ""
+                                            set trackDuration to ""
+                                            set trackPosition to ""
+                                            set isMuted to "false"
+                                            set faviconUrl to ""
+
+                                            if (count of jsParts) > 1 then
+                                                set artUrl to item 2 of jsParts
+                                            end if
+                                            if (count of jsParts) > 2 then
+                                                set trackDuration to item 3 of jsParts
+                                            end if
+                                            if (count of jsParts) > 3 then
+                                                set trackPosition to item 4 of jsParts
+                                            end if
+                                            if (count of jsParts) > 4 then
+                                                set isMuted to item 5 of jsParts
+                                            end if
+                                            if (count of jsParts) > 5 then
+                                                set faviconUrl to item 6 of jsParts
+                                            end if
+
+                                            set end of playingSources to "playing|" & tabName & "|Safari|" & tabURL & "|" & trackDuration & "|" & trackPosition & "|" & artUrl & "|safari|" & isMuted & "|" & faviconUrl
+                                        end if
+                                    on error
+                                        if tabName starts with "▶" then
+                                            set end of playingSources to "playing|" & tabName & "|Safari|" & tabURL & "|||safari|false"
+                                        end if
+                                    end try
+                                end if
+                            end try
+                        end repeat
+                    end repeat
+                end try
+            end tell
+        "#);
+    }
+
+    script.push_str(
+        r#"
+        set AppleScript's text item delimiters to linefeed
+        return playingSources as text
+    "#,
+    );
+
+    match run_osascript(app_handle, &script) {
+        Ok(stdout) => stdout.lines().filter_map(parse_playing_source).collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Every MPRIS player currently playing (Linux)
+#[cfg(target_os = "linux")]
+async fn query_playing_sources() -> Vec<PlayingSource> {
+    use zbus::zvariant::Value;
+    use zbus::{proxy, Connection};
+
+    // Define a simple proxy for MPRIS Player
+    #[proxy(
+        interface = "org.mpris.MediaPlayer2.Player",
+        default_path = "/org/mpris/MediaPlayer2"
+    )]
+    trait Player {
+        #[zbus(property)]
+        fn playback_status(&self) -> zbus::Result<String>;
+        #[zbus(property)]
+        fn metadata(
+            &self,
+        ) -> zbus::Result<std::collections::HashMap<String, zbus::zvariant::OwnedValue>>;
+        #[zbus(property)]
+        fn position(&self) -> zbus::Result<i64>;
+        #[zbus(property)]
+        fn volume(&self) -> zbus::Result<f64>;
+    }
+
+    let mut sources = Vec::new();
+
+    let conn = match Connection::session().await {
+        Ok(conn) => conn,
+        Err(_) => return sources,
+    };
+    let names = match zbus::fdo::DBusProxy::new(&conn).await {
+        Ok(proxy) => proxy.list_names().await.unwrap_or_default(),
+        Err(_) => return sources,
+    };
+
+    for name in names {
+        if !name.starts_with("org.mpris.MediaPlayer2.") {
+            continue;
+        }
+
+        let player = match PlayerProxy::builder(&conn).destination(name.clone()) {
+            Ok(builder) => match builder.build().await {
+                Ok(player) => player,
+                Err(_) => continue,
+            },
+            Err(_) => continue,
+        };
+
+        if player.playback_status().await.ok().as_deref() != Some("Playing") {
+            continue;
+        }
+
+        let mut title = None;
+        let mut artist = None;
+        let mut artists = None;
+        let mut album = None;
+        let mut genre = None;
+        let mut year = None;
+        let mut duration = None;
+        let mut artwork_url = None;
+        let mut track_id = None;
+
+        if let Ok(metadata) = player.metadata().await {
+            if let Some(t) = metadata.get("xesam:title") {
+                if let Value::Str(v) = &**t {
+                    title = Some(v.to_string());
+                }
+            }
+            if let Some(a) = metadata.get("xesam:artist") {
+                // Spec says an array of strings, some players send a plain string
+                (artist, artists) = match &**a {
+                    Value::Array(v) => {
+                        artist_fields_from_list(v.inner().iter().filter_map(|value| match value {
+                            Value::Str(s) => Some(s.as_str()),
+                            _ => None,
+                        }))
+                    }
+                    Value::Str(s) => artist_fields_from_string(Some(s.to_string())),
+                    _ => (None, None),
+                };
+            }
+            if let Some(a) = metadata.get("xesam:album") {
+                if let Value::Str(v) = &**a {
+                    album = Some(v.to_string());
+                }
+            }
+            if let Some(g) = metadata.get("xesam:genre") {
+                // Like xesam:artist, an array of strings or sometimes a plain string
+                genre = match &**g {
+                    Value::Array(v) => v.inner().iter().find_map(|value| match value {
+                        Value::Str(s) if !s.is_empty() => Some(s.to_string()),
+                        _ => None,
+                    }),
+                    Value::Str(s) if !s.is_empty() => Some(s.to_string()),
+                    _ => None,
+                };
+            }
+            if let Some(c) = metadata.get("xesam:contentCreated") {
+                // ISO 8601 date, the year is all we need
+                if let Value::Str(v) = &**c {
+                    year = v.get(..4).and_then(|y| y.parse().ok());
+                }
+            }
+            if let Some(d) = metadata.get("mpris:length") {
+                if let Value::I64(v) = &**d {
+                    duration = Some(*v as f64 / 1_000_000.0);
+                } else if let Value::U64(v) = &**d {
+                    duration = Some(*v as f64 / 1_000_000.0);
+                }
+            }
+            if let Some(u) = metadata.get("mpris:artUrl") {
+                if let Value::Str(v) = &**u {
+                    artwork_url = Some(v.to_string());
+                }
+            }
+            if let Some(id) = metadata.get("mpris:trackid") {
+                // Spotify uses /com/spotify/episode/... for podcast episodes
+                track_id = match &**id {
+                    Value::ObjectPath(v) => Some(v.to_string()),
+                    Value::Str(v) => Some(v.to_string()),
+                    _ => None,
+                };
+            }
+        }
+        let content_type = content_type_from_hints(track_id.as_deref(), genre.as_deref());
+
+        let position = player.position().await.ok().map(|p| p as f64 / 1_000_000.0);
+        let muted = player.volume().await.map(|v| v <= 0.0).unwrap_or(false);
+        let app_id = name.replace("org.mpris.MediaPlayer2.", "");
+
+        sources.push(PlayingSource {
+            data: NowPlayingData {
+                title,
+                artist,
+                artists,
+                album,
+                genre,
+                year,
+                output_target: None,
+                artwork_base64: None,
+                duration,
+                elapsed_time: position,
+                is_playing: true,
+                audio_levels: None,
+                app_name: Some(app_id.clone()),
+                content_type: Some(content_type),
+            },
+            app_id,
+            artwork_url,
+            muted,
+            favicon_url: None,
+        });
+    }
+
+    sources
+}
+
+async fn query_now_playing(app_handle: tauri::AppHandle) -> NowPlayingData {
+    #[cfg(target_os = "macos")]
+    {
+        // Default to not playing before check
+        IS_PLAYING.store(false, Ordering::Relaxed);
+
+        let sources = query_playing_sources(&app_handle);
+        finish_now_playing(&app_handle, sources)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Media::Control::GlobalSystemMediaTransportControlsSessionManager;
+        use windows::Storage::Streams::DataReader;
+
+        if let Ok(manager) = GlobalSystemMediaTransportControlsSessionManager::RequestAsync() {
+            if let Ok(manager) = manager.await {
+                if let Ok(session) = manager.GetCurrentSession() {
+                    if let Ok(properties) = session.TryGetMediaPropertiesAsync().unwrap().await {
+                        let title = properties.Title().ok().map(|h| h.to_string());
+                        let (artist, artists) = artist_fields_from_string(
+                            properties.Artist().ok().map(|h| h.to_string()),
+                        );
+                        let album = properties.AlbumTitle().ok().map(|h| h.to_string());
+
+                        // Check playback status
+                        let playback_info = session.GetPlaybackInfo().unwrap();
+                        let is_playing = playback_info.PlaybackStatus().unwrap() == windows::Media::Control::GlobalSystemMediaTransportControlsSessionPlaybackStatus::Playing;
+
+                        // Get timeline
+                        let timeline = session.GetTimelineProperties().unwrap();
+                        let duration = timeline
+                            .EndTime()
+                            .ok()
+                            .map(|t| t.Duration as f64 / 10_000_000.0);
+                        let position = timeline
+                            .Position()
+                            .ok()
+                            .map(|t| t.Duration as f64 / 10_000_000.0);
+
+                        // Artwork
+                        // Getting stream from IRandomAccessStreamReference
+                        let mut artwork_base64 = None;
+                        if let Ok(thumb_ref) = properties.Thumbnail() {
+                            if let Ok(stream) = thumb_ref.OpenReadAsync().unwrap().await {
+                                let size = stream.Size().unwrap() as usize;
+                                let reader = DataReader::CreateDataReader(&stream).unwrap();
+                                if reader.LoadAsync(size as u32).unwrap().await.is_ok() {
+                                    let mut buffer = vec![0u8; size];
+                                    if reader.ReadBytes(&mut buffer).is_ok() {
+                                        artwork_base64 = Some(encode_artwork(&buffer));
                                     }
                                 }
                             }
+                        }
+
+                        IS_PLAYING.store(is_playing, Ordering::Relaxed);
 
-                            let position =
-                                player.position().await.ok().map(|p| p as f64 / 1_000_000.0);
-
-                            let artwork_base64 = if let Some(url) = artwork_url {
-                                fetch_artwork_from_url(&url)
-                            } else {
-                                None
-                            };
-
-                            let data = NowPlayingData {
-                                title,
-                                artist,
-                                album,
-                                artwork_base64,
-                                duration,
-                                elapsed_time: position,
-                                is_playing: true,
-                                audio_levels: Some(get_audio_levels_internal()),
-                                app_name: Some(name.replace("org.mpris.MediaPlayer2.", "")),
-                            };
-                            save_last_played(&data);
-                            return data;
+                        let track_changed = is_track_changed(&title, &artist);
+                        if track_changed {
+                            set_cached_track(title.clone(), artist.clone(), artwork_base64.clone());
                         }
+
+                        let genre = properties
+                            .Genres()
+                            .ok()
+                            .and_then(|genres| genres.GetAt(0).ok())
+                            .map(|g| g.to_string())
+                            .filter(|g| !g.is_empty());
+                        let content_type = content_type_from_hints(None, genre.as_deref());
+
+                        let data = NowPlayingData {
+                            title,
+                            artist,
+                            artists,
+                            album,
+                            genre,
+                            year: None,
+                            output_target: None,
+                            artwork_base64,
+                            duration,
+                            elapsed_time: position,
+                            is_playing,
+                            audio_levels: Some(get_audio_levels_internal()),
+                            app_name: Some("System".to_string()),
+                            content_type: Some(content_type),
+                        };
+
+                        if track_changed {
+                            VISUALIZER_TRACK_CHANGED.store(true, Ordering::Relaxed);
+                        }
+                        check_track_ending(&app_handle, &data, track_changed);
+                        crate::scrobble::track_progress(&app_handle, &data, track_changed);
+                        crate::play_history::record_play(&app_handle, &data, track_changed);
+                        save_last_played(&data);
+                        return data;
                     }
                 }
             }
@@ -494,87 +1386,636 @@ pub async fn get_now_playing() -> NowPlayingData {
 
         get_last_played_or_default(get_audio_levels())
     }
+
+    #[cfg(target_os = "linux")]
+    {
+        let sources = query_playing_sources().await;
+        finish_now_playing(&app_handle, sources)
+    }
+}
+
+/// List every source that is currently playing, best match first (the one `get_now_playing`
+/// reports), so the UI can let the user pick when several players are active.
+/// Artwork is not included to keep this cheap.
+#[tauri::command]
+pub async fn get_all_playing_sources(app_handle: tauri::AppHandle) -> Vec<NowPlayingData> {
+    #[cfg(target_os = "macos")]
+    {
+        let sources = query_playing_sources(&app_handle);
+        rank_sources(sources).into_iter().map(|s| s.data).collect()
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let _ = &app_handle;
+        let sources = query_playing_sources().await;
+        rank_sources(sources).into_iter().map(|s| s.data).collect()
+    }
+
+    // Windows already arbitrates between sessions, its current session is listed first
+    #[cfg(target_os = "windows")]
+    {
+        let _ = &app_handle;
+        use windows::Media::Control::{
+            GlobalSystemMediaTransportControlsSessionManager,
+            GlobalSystemMediaTransportControlsSessionPlaybackStatus,
+        };
+
+        let manager = match GlobalSystemMediaTransportControlsSessionManager::RequestAsync() {
+            Ok(request) => match request.await {
+                Ok(manager) => manager,
+                Err(_) => return Vec::new(),
+            },
+            Err(_) => return Vec::new(),
+        };
+        let current_id = manager
+            .GetCurrentSession()
+            .and_then(|session| session.SourceAppUserModelId())
+            .map(|id| id.to_string())
+            .ok();
+
+        let mut playing = Vec::new();
+        if let Ok(sessions) = manager.GetSessions() {
+            for session in sessions {
+                let is_playing = session
+                    .GetPlaybackInfo()
+                    .and_then(|info| info.PlaybackStatus())
+                    .map(|status| {
+                        status == GlobalSystemMediaTransportControlsSessionPlaybackStatus::Playing
+                    })
+                    .unwrap_or(false);
+                if !is_playing {
+                    continue;
+                }
+
+                let properties = match session.TryGetMediaPropertiesAsync() {
+                    Ok(request) => match request.await {
+                        Ok(properties) => properties,
+                        Err(_) => continue,
+                    },
+                    Err(_) => continue,
+                };
+                let timeline = session.GetTimelineProperties().ok();
+
+                let (artist, artists) =
+                    artist_fields_from_string(properties.Artist().ok().map(|h| h.to_string()));
+                let genre = properties
+                    .Genres()
+                    .ok()
+                    .and_then(|genres| genres.GetAt(0).ok())
+                    .map(|g| g.to_string())
+                    .filter(|g| !g.is_empty());
+                let content_type = content_type_from_hints(None, genre.as_deref());
+
+                playing.push(NowPlayingData {
+                    title: properties.Title().ok().map(|h| h.to_string()),
+                    artist,
+                    artists,
+                    album: properties.AlbumTitle().ok().map(|h| h.to_string()),
+                    genre,
+                    year: None,
+                    output_target: None,
+                    artwork_base64: None,
+                    duration: timeline
+                        .as_ref()
+                        .and_then(|t| t.EndTime().ok())
+                        .map(|t| t.Duration as f64 / 10_000_000.0),
+                    elapsed_time: timeline
+                        .as_ref()
+                        .and_then(|t| t.Position().ok())
+                        .map(|t| t.Duration as f64 / 10_000_000.0),
+                    is_playing: true,
+                    audio_levels: None,
+                    app_name: session.SourceAppUserModelId().ok().map(|id| id.to_string()),
+                    content_type: Some(content_type),
+                });
+            }
+        }
+
+        // Stable sort keeps the session order otherwise
+        playing.sort_by_key(|data| data.app_name != current_id);
+        playing
+    }
+}
+
+/// List media-capable apps with their running and playing state, for a source picker
+#[tauri::command]
+pub async fn get_available_media_apps(app_handle: tauri::AppHandle) -> Vec<MediaAppInfo> {
+    #[cfg(target_os = "macos")]
+    {
+        const APPS: [&str; 4] = ["Spotify", "Music", "Safari", "Google Chrome"];
+        let running = processes_running(APPS);
+
+        // Browsers have no player state, so rely on the last now-playing result
+        let last_playing_app = if IS_PLAYING.load(Ordering::Relaxed) {
+            LAST_PLAYED
+                .get()
+                .and_then(|m| m.lock().ok())
+                .and_then(|guard| guard.as_ref().and_then(|d| d.app_name.clone()))
+        } else {
+            None
+        };
+
+        APPS.iter()
+            .zip(running)
+            .map(|(name, is_running)| {
+                let is_playing = is_running
+                    && match *name {
+                        "Spotify" | "Music" => run_osascript(
+                            &app_handle,
+                            &format!(r#"tell application "{}" to player state as string"#, name),
+                        )
+                        .map(|state| state == "playing")
+                        .unwrap_or(false),
+                        _ => last_playing_app.as_deref() == Some(*name),
+                    };
+
+                MediaAppInfo {
+                    name: name.to_string(),
+                    is_running,
+                    is_playing,
+                }
+            })
+            .collect()
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let _ = &app_handle;
+        use windows::Media::Control::{
+            GlobalSystemMediaTransportControlsSessionManager,
+            GlobalSystemMediaTransportControlsSessionPlaybackStatus,
+        };
+
+        let mut apps = Vec::new();
+
+        if let Ok(manager) = GlobalSystemMediaTransportControlsSessionManager::RequestAsync() {
+            if let Ok(manager) = manager.await {
+                if let Ok(sessions) = manager.GetSessions() {
+                    for session in sessions {
+                        let name = match session.SourceAppUserModelId() {
+                            Ok(id) => id.to_string(),
+                            Err(_) => continue,
+                        };
+                        let is_playing = session
+                            .GetPlaybackInfo()
+                            .and_then(|info| info.PlaybackStatus())
+                            .map(|status| {
+                                status
+                                    == GlobalSystemMediaTransportControlsSessionPlaybackStatus::Playing
+                            })
+                            .unwrap_or(false);
+
+                        apps.push(MediaAppInfo {
+                            name,
+                            is_running: true,
+                            is_playing,
+                        });
+                    }
+                }
+            }
+        }
+
+        apps
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let _ = &app_handle;
+        use zbus::Connection;
+
+        let mut apps = Vec::new();
+
+        if let Ok(conn) = Connection::session().await {
+            if let Ok(proxy) = zbus::fdo::DBusProxy::new(&conn).await {
+                if let Ok(names) = proxy.list_names().await {
+                    for name in names {
+                        let name = name.to_string();
+                        if !name.starts_with("org.mpris.MediaPlayer2.") {
+                            continue;
+                        }
+
+                        let status: Option<String> = match zbus::Proxy::new(
+                            &conn,
+                            name.as_str(),
+                            "/org/mpris/MediaPlayer2",
+                            "org.mpris.MediaPlayer2.Player",
+                        )
+                        .await
+                        {
+                            Ok(player) => player.get_property("PlaybackStatus").await.ok(),
+                            Err(_) => None,
+                        };
+
+                        apps.push(MediaAppInfo {
+                            name: name.replace("org.mpris.MediaPlayer2.", ""),
+                            is_running: true,
+                            is_playing: status.as_deref() == Some("Playing"),
+                        });
+                    }
+                }
+            }
+        }
+
+        apps
+    }
+}
+
+/// Apps `automation-denied` has been emitted for, so polling doesn't repeat it every time
+#[cfg(target_os = "macos")]
+static AUTOMATION_DENIED_APPS: std::sync::Mutex<Vec<String>> = std::sync::Mutex::new(Vec::new());
+
+/// Let `automation-denied` fire again for an app once its Automation permission was granted
+#[cfg(target_os = "macos")]
+pub fn clear_automation_denied(app: &str) {
+    if let Ok(mut denied) = AUTOMATION_DENIED_APPS.lock() {
+        denied.retain(|denied_app| denied_app != app);
+    }
 }
 
-/// Get artwork from Music.app using AppleScript to write to temp file
+/// Run an AppleScript through osascript and return its trimmed stdout.
+/// If macOS denies Automation access (-1743), emits `automation-denied` (once per app until
+/// the permission is granted) and returns a distinct error.
 #[cfg(target_os = "macos")]
-fn get_music_app_artwork() -> Option<String> {
-    use std::fs;
+fn run_osascript(app_handle: &tauri::AppHandle, script: &str) -> Result<String, String> {
     use std::process::Command;
 
-    // Use a unique path to avoid conflicts
-    let temp_path = "/tmp/overdone_music_art_v3.data";
-    // Ensure cleanup
-    let _ = fs::remove_file(temp_path);
+    let output = Command::new("osascript")
+        .arg("-e")
+        .arg(script)
+        .output()
+        .map_err(|e| e.to_string())?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if let Some(app) = crate::utils::automation_denied_app(&stderr) {
+            let first_denial = AUTOMATION_DENIED_APPS
+                .lock()
+                .map(|mut denied| {
+                    let first = !denied.contains(&app);
+                    if first {
+                        denied.push(app.clone());
+                    }
+                    first
+                })
+                .unwrap_or(true);
+            if first_denial {
+                log::debug!("Automation permission denied for {}", app);
+                let _ = app_handle.emit("automation-denied", AutomationDenied { app: app.clone() });
+            }
+            return Err(format!("AutomationDenied: {}", app));
+        }
+    }
 
-    // AppleScript to extract artwork to a file
-    // Tries 'data' first, then 'raw data' as fallback
-    let script = format!(
-        r#"
-        tell application "Music"
-            try
-                if (count of artworks of current track) < 1 then return "no_artwork"
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Counter giving every artwork extraction its own temp file
+#[cfg(target_os = "macos")]
+static ARTWORK_FILE_COUNTER: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// Temp file that is removed when dropped, so every return path cleans up
+#[cfg(target_os = "macos")]
+struct TempArtworkFile(std::path::PathBuf);
+
+#[cfg(target_os = "macos")]
+impl TempArtworkFile {
+    /// Unique per invocation (PID + counter) so concurrent fetches never share a file
+    fn new() -> Self {
+        let n = ARTWORK_FILE_COUNTER.fetch_add(1, Ordering::Relaxed);
+        Self(std::env::temp_dir().join(format!(
+            "overdone_music_art_{}_{}.data",
+            std::process::id(),
+            n
+        )))
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl Drop for TempArtworkFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Music.app can take seconds to hand over artwork when it's busy; give up after this
+#[cfg(target_os = "macos")]
+const MUSIC_ARTWORK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// Run an artwork osascript and return its stdout. Kills the script after
+/// MUSIC_ARTWORK_TIMEOUT and emits `artwork-fetch-slow` (the timeout in ms).
+#[cfg(target_os = "macos")]
+fn run_artwork_script(app_handle: &tauri::AppHandle, script: &str) -> Option<String> {
+    use std::process::{Command, Stdio};
+
+    let mut child = Command::new("osascript")
+        .arg("-e")
+        .arg(script)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+
+    let started = std::time::Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) if started.elapsed() < MUSIC_ARTWORK_TIMEOUT => {
+                std::thread::sleep(std::time::Duration::from_millis(25));
+            }
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                log::debug!("Music.app artwork fetch timed out");
+                let _ = app_handle.emit(
+                    "artwork-fetch-slow",
+                    MUSIC_ARTWORK_TIMEOUT.as_millis() as u64,
+                );
+                return None;
+            }
+        }
+    }
+
+    let output = child.wait_with_output().ok()?;
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
 
-                set artData to missing value
+/// Give `extract` a fresh temp file to write artwork to (it returns the script's stdout) and
+/// read the file back when that is "success". The file is removed on every path, including
+/// timeouts and script errors, and concurrent fetches each get their own file.
+#[cfg(target_os = "macos")]
+fn read_artwork_via_temp_file(
+    extract: impl FnOnce(&std::path::Path) -> Option<String>,
+) -> Option<Vec<u8>> {
+    let temp_file = TempArtworkFile::new();
+
+    // A killed script may leave a partial file behind, TempArtworkFile removes it on return
+    if extract(&temp_file.0)? != "success" {
+        return None;
+    }
+    std::fs::read(&temp_file.0)
+        .ok()
+        .filter(|data| !data.is_empty())
+}
+
+/// Get the raw artwork data from Music.app using AppleScript to write to temp file.
+/// Returns None if Music.app doesn't answer within MUSIC_ARTWORK_TIMEOUT.
+#[cfg(target_os = "macos")]
+fn get_music_app_artwork(app_handle: &tauri::AppHandle) -> Option<Vec<u8>> {
+    read_artwork_via_temp_file(|temp_path| {
+        let temp_path = temp_path.to_string_lossy();
 
-                -- Try getting 'data' (image object/data) first
+        // AppleScript to extract artwork to a file
+        // Tries 'data' first, then 'raw data' as fallback
+        let script = format!(
+            r#"
+            tell application "Music"
                 try
-                    set artData to data of artwork 1 of current track
-                end try
+                    if (count of artworks of current track) < 1 then return "no_artwork"
 
-                -- Fallback to 'raw data' if 'data' failed or is missing
-                if artData is missing value then
+                    set artData to missing value
+
+                    -- Try getting 'data' (image object/data) first
                     try
-                        set artData to raw data of artwork 1 of current track
+                        set artData to data of artwork 1 of current track
                     end try
-                end if
 
-                if artData is missing value then return "no_data_found"
+                    -- Fallback to 'raw data' if 'data' failed or is missing
+                    if artData is missing value then
+                        try
+                            set artData to raw data of artwork 1 of current track
+                        end try
+                    end if
 
-                set dest to POSIX file "{}"
-                set f to open for access dest with write permission
-                set eof f to 0
-                write artData to f
-                close access f
-                return "success"
-            on error errStr
-                try
-                    close access (POSIX file "{}")
+                    if artData is missing value then return "no_data_found"
+
+                    set dest to POSIX file "{}"
+                    set f to open for access dest with write permission
+                    set eof f to 0
+                    write artData to f
+                    close access f
+                    return "success"
+                on error errStr
+                    try
+                        close access (POSIX file "{}")
+                    end try
+                    return "error: " & errStr
                 end try
-                return "error: " & errStr
+            end tell
+        "#,
+            temp_path, temp_path
+        );
+
+        run_artwork_script(app_handle, &script)
+    })
+}
+
+/// Spotify album image id prefix for the largest (640x640) size.
+/// Ids start with a size-specific prefix: ...4851 = 64px, ...1e02 = 300px, ...b273 = 640px.
+const SPOTIFY_LARGE_IMAGE_PREFIX: &str = "ab67616d0000b273";
+
+/// Rewrite a Spotify album art URL (i.scdn.co or open.spotify.com) to the largest size
+fn spotify_high_res_url(url: &str) -> Option<String> {
+    let id = url
+        .split_once("i.scdn.co/image/")
+        .or_else(|| url.split_once("open.spotify.com/image/"))?
+        .1;
+
+    if !id.starts_with("ab67616d0000") {
+        return None;
+    }
+
+    let hash = id.get(SPOTIFY_LARGE_IMAGE_PREFIX.len()..)?;
+    if hash.is_empty() {
+        return None;
+    }
+
+    Some(format!(
+        "https://i.scdn.co/image/{}{}",
+        SPOTIFY_LARGE_IMAGE_PREFIX, hash
+    ))
+}
+
+/// Whether Music.app's current track matches the given artist and album (macOS)
+#[cfg(target_os = "macos")]
+fn music_app_track_matches(artist: &str, album: &str) -> bool {
+    use std::process::Command;
+
+    let [music_running] = processes_running(["Music"]);
+    if !music_running {
+        return false;
+    }
+
+    let script = r#"
+        tell application "Music"
+            try
+                return (artist of current track) & linefeed & (album of current track)
             end try
         end tell
-    "#,
-        temp_path, temp_path
-    );
+    "#;
+
+    Command::new("osascript")
+        .arg("-e")
+        .arg(script)
+        .output()
+        .map(|output| {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let mut lines = stdout.trim().lines();
+            lines.next() == Some(artist) && lines.next() == Some(album)
+        })
+        .unwrap_or(false)
+}
 
-    if let Ok(result) = Command::new("osascript").arg("-e").arg(&script).output() {
-        let stdout = String::from_utf8_lossy(&result.stdout).trim().to_string();
+/// Get the largest available artwork (base64) for an expanded view, falling back to the
+/// current art. Spotify URLs are rewritten to 640px, Music.app returns the full artwork.
+#[tauri::command]
+pub async fn get_high_res_artwork(
+    app_handle: tauri::AppHandle,
+    artist: String,
+    album: String,
+    current_url: Option<String>,
+) -> Option<String> {
+    // Probed (up to 3s) only once a URL is about to be fetched; offline, only artwork stored
+    // locally (Music.app) can be returned
+    let mut online = None;
+
+    if let Some(url) = current_url.as_deref().and_then(spotify_high_res_url) {
+        if probe_online(&mut online).await {
+            let artwork =
+                tauri::async_runtime::spawn_blocking(move || fetch_artwork_from_url(&url))
+                    .await
+                    .ok()
+                    .flatten();
+            if artwork.is_some() {
+                return artwork;
+            }
+        }
+    }
 
-        // println!("Artwork fetch result: {}", stdout); // Uncomment for debugging
+    // Music.app artwork data is stored at full resolution
+    #[cfg(target_os = "macos")]
+    {
+        let artwork = tauri::async_runtime::spawn_blocking(move || {
+            if !music_app_track_matches(&artist, &album) {
+                return None;
+            }
+            get_music_app_artwork(&app_handle).map(|artwork| base64_encode(&artwork))
+        })
+        .await
+        .ok()
+        .flatten();
+        if artwork.is_some() {
+            return artwork;
+        }
+    }
 
-        if stdout == "success" {
-            if let Ok(data) = fs::read(temp_path) {
-                let _ = fs::remove_file(temp_path);
-                if !data.is_empty() {
-                    return Some(base64_encode(&data));
-                }
+    #[cfg(not(target_os = "macos"))]
+    let _ = (&app_handle, &artist, &album);
+
+    let url = current_url?;
+    if !probe_online(&mut online).await {
+        return None;
+    }
+    tauri::async_runtime::spawn_blocking(move || fetch_artwork_from_url(&url))
+        .await
+        .ok()
+        .flatten()
+}
+
+/// Connectivity for get_high_res_artwork, probed on first use and reused after that
+async fn probe_online(online: &mut Option<bool>) -> bool {
+    if let Some(online) = *online {
+        return online;
+    }
+    let status = crate::connectivity::connectivity_status().await.online;
+    *online = Some(status);
+    status
+}
+
+/// Minimum time between two dispatched media presses, so the player registers each one
+const MEDIA_PRESS_GAP: std::time::Duration = std::time::Duration::from_millis(150);
+
+/// Media presses waiting for their turn, oldest first
+static PENDING_MEDIA_PRESSES: std::sync::Mutex<std::collections::VecDeque<(u64, MediaCommand)>> =
+    std::sync::Mutex::new(std::collections::VecDeque::new());
+
+static MEDIA_PRESS_COUNTER: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// Held while a media press is dispatched, with the time of the last dispatch
+static MEDIA_DISPATCH: tokio::sync::Mutex<Option<std::time::Instant>> =
+    tokio::sync::Mutex::const_new(None);
+
+/// Queue a press and wait for its turn. Presses that undo each other while both are still
+/// waiting (play/pause twice, next then previous) are dropped, and a newer seek replaces a
+/// waiting one. Returns None when this press was dropped.
+async fn queue_media_press(
+    command: MediaCommand,
+) -> Option<tokio::sync::MutexGuard<'static, Option<std::time::Instant>>> {
+    let id = MEDIA_PRESS_COUNTER.fetch_add(1, Ordering::Relaxed);
+    {
+        let mut pending = PENDING_MEDIA_PRESSES.lock().ok()?;
+        let last = pending.back().map(|(_, waiting)| *waiting);
+        match (last, command) {
+            (Some(MediaCommand::PlayPause), MediaCommand::PlayPause)
+            | (Some(MediaCommand::Next), MediaCommand::Previous)
+            | (Some(MediaCommand::Previous), MediaCommand::Next) => {
+                pending.pop_back();
+                return None;
+            }
+            (Some(MediaCommand::Seek(_)), MediaCommand::Seek(_)) => {
+                pending.pop_back();
+                pending.push_back((id, command));
             }
+            _ => pending.push_back((id, command)),
         }
     }
 
-    // Ensure cleanup on failure
-    let _ = fs::remove_file(temp_path);
-    None
+    // Tokio's mutex is fair, so presses are dispatched in the order they arrived
+    let last_dispatch = MEDIA_DISPATCH.lock().await;
+    {
+        let mut pending = PENDING_MEDIA_PRESSES.lock().ok()?;
+        let index = pending.iter().position(|(waiting, _)| *waiting == id)?;
+        pending.remove(index);
+    }
+
+    if let Some(last) = *last_dispatch {
+        let wait = MEDIA_PRESS_GAP.saturating_sub(last.elapsed());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+    Some(last_dispatch)
+}
+
+/// Run `dispatch` once it's this press's turn, so rapid presses don't spawn a pile of
+/// osascript processes that finish out of order. Returns Ok without dispatching when the
+/// press was cancelled out by a later one.
+async fn run_media_press(
+    command: MediaCommand,
+    dispatch: impl std::future::Future<Output = Result<(), String>>,
+) -> Result<(), String> {
+    let mut last_dispatch = match queue_media_press(command).await {
+        Some(guard) => guard,
+        None => {
+            log::debug!("Media press {:?} cancelled out by a later press", command);
+            return Ok(());
+        }
+    };
+
+    let result = dispatch.await;
+    *last_dispatch = Some(std::time::Instant::now());
+    result
 }
 
 /// Toggle play/pause for the currently playing media
 #[tauri::command]
-pub async fn media_play_pause() -> Result<(), String> {
+pub async fn media_play_pause(app_handle: tauri::AppHandle) -> Result<(), String> {
+    run_media_press(MediaCommand::PlayPause, send_play_pause(app_handle)).await
+}
+
+async fn send_play_pause(app_handle: tauri::AppHandle) -> Result<(), String> {
     #[cfg(target_os = "macos")]
     {
-        use std::process::Command;
-
         // Script that controls whichever app is playing
         let script = r#"
             tell application "System Events"
@@ -645,17 +2086,14 @@ pub async fn media_play_pause() -> Result<(), String> {
             end if
         "#;
 
-        Command::new("osascript")
-            .arg("-e")
-            .arg(script)
-            .output()
-            .map_err(|e| e.to_string())?;
+        run_osascript(&app_handle, script)?;
 
         Ok(())
     }
 
     #[cfg(target_os = "windows")]
     {
+        let _ = &app_handle;
         use windows::Media::Control::GlobalSystemMediaTransportControlsSessionManager;
         if let Ok(manager) = GlobalSystemMediaTransportControlsSessionManager::RequestAsync() {
             if let Ok(manager) = manager.await {
@@ -669,6 +2107,7 @@ pub async fn media_play_pause() -> Result<(), String> {
 
     #[cfg(target_os = "linux")]
     {
+        let _ = &app_handle;
         use zbus::{proxy, Connection};
         #[proxy(
             interface = "org.mpris.MediaPlayer2.Player",
@@ -699,11 +2138,13 @@ pub async fn media_play_pause() -> Result<(), String> {
 
 /// Skip to the next track
 #[tauri::command]
-pub async fn media_next_track() -> Result<(), String> {
+pub async fn media_next_track(app_handle: tauri::AppHandle) -> Result<(), String> {
+    run_media_press(MediaCommand::Next, send_next_track(app_handle)).await
+}
+
+async fn send_next_track(app_handle: tauri::AppHandle) -> Result<(), String> {
     #[cfg(target_os = "macos")]
     {
-        use std::process::Command;
-
         let script = r#"
             tell application "System Events"
                 set spotifyRunning to (name of processes) contains "Spotify"
@@ -773,29 +2214,157 @@ pub async fn media_next_track() -> Result<(), String> {
                 tell application "Spotify" to next track
                 return "spotify"
             else if musicRunning then
-                tell application "Music" to next track
+                tell application "Music" to next track
+                return "music"
+            else
+                return "no_app"
+            end if
+        "#;
+
+        run_osascript(&app_handle, script)?;
+
+        Ok(())
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let _ = &app_handle;
+        use windows::Media::Control::GlobalSystemMediaTransportControlsSessionManager;
+        if let Ok(manager) = GlobalSystemMediaTransportControlsSessionManager::RequestAsync() {
+            if let Ok(manager) = manager.await {
+                if let Ok(session) = manager.GetCurrentSession() {
+                    let _ = session.TrySkipNextAsync().unwrap().await;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let _ = &app_handle;
+        use zbus::{proxy, Connection};
+        #[proxy(
+            interface = "org.mpris.MediaPlayer2.Player",
+            default_path = "/org/mpris/MediaPlayer2"
+        )]
+        trait Player {
+            fn next(&self) -> zbus::Result<()>;
+        }
+        if let Ok(conn) = Connection::session().await {
+            let proxy = zbus::fdo::DBusProxy::new(&conn).await.unwrap();
+            let names = proxy.list_names().await.unwrap();
+            for name in names {
+                if name.starts_with("org.mpris.MediaPlayer2.") {
+                    let player = PlayerProxy::builder(&conn)
+                        .destination(name)
+                        .unwrap()
+                        .build()
+                        .await
+                        .unwrap();
+                    let _ = player.next().await;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Go to the previous track
+#[tauri::command]
+pub async fn media_previous_track(app_handle: tauri::AppHandle) -> Result<(), String> {
+    run_media_press(MediaCommand::Previous, send_previous_track(app_handle)).await
+}
+
+async fn send_previous_track(app_handle: tauri::AppHandle) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        let script = r#"
+            tell application "System Events"
+                set spotifyRunning to (name of processes) contains "Spotify"
+                set musicRunning to (name of processes) contains "Music"
+                set safariRunning to (name of processes) contains "Safari"
+            end tell
+
+            if spotifyRunning then
+                tell application "Spotify"
+                    if player state is playing then
+                        previous track
+                        return "spotify"
+                    end if
+                end tell
+            end if
+
+            if musicRunning then
+                tell application "Music"
+                    if player state is playing then
+                        back track
+                        return "music"
+                    end if
+                end tell
+            end if
+
+            if safariRunning then
+                tell application "Safari"
+                    try
+                        repeat with w in windows
+                            repeat with t in tabs of w
+                                try
+                                    set tabURL to URL of t
+                                    if tabURL contains "youtube.com" or tabURL contains "music.youtube.com" then
+                                        do JavaScript "
+                                            (function() {
+                                                // Try to go back ~10s or restart video usually, or strictly prev button if playlist
+                                                // For now, let's try history back or restart
+                                                window.history.back();
+                                                return 'success';
+                                            })();
+                                        " in t
+                                        return "safari"
+                                    else if tabURL contains "open.spotify.com" then
+                                        do JavaScript "
+                                            (function() {
+                                                var prevBtn = document.querySelector('[data-testid=\"control-button-skip-back\"]');
+                                                if (prevBtn) {
+                                                    prevBtn.click();
+                                                    return 'success';
+                                                }
+                                                return 'no_btn';
+                                            })();
+                                        " in t
+                                        return "safari"
+                                    end if
+                                end try
+                            end repeat
+                        end repeat
+                    end try
+                end tell
+            end if
+
+            if spotifyRunning then
+                tell application "Spotify" to previous track
+                return "spotify"
+            else if musicRunning then
+                tell application "Music" to back track
                 return "music"
             else
                 return "no_app"
             end if
         "#;
 
-        Command::new("osascript")
-            .arg("-e")
-            .arg(script)
-            .output()
-            .map_err(|e| e.to_string())?;
+        run_osascript(&app_handle, script)?;
 
         Ok(())
     }
 
     #[cfg(target_os = "windows")]
     {
+        let _ = &app_handle;
         use windows::Media::Control::GlobalSystemMediaTransportControlsSessionManager;
         if let Ok(manager) = GlobalSystemMediaTransportControlsSessionManager::RequestAsync() {
             if let Ok(manager) = manager.await {
                 if let Ok(session) = manager.GetCurrentSession() {
-                    let _ = session.TrySkipNextAsync().unwrap().await;
+                    let _ = session.TrySkipPreviousAsync().unwrap().await;
                 }
             }
         }
@@ -804,13 +2373,14 @@ pub async fn media_next_track() -> Result<(), String> {
 
     #[cfg(target_os = "linux")]
     {
+        let _ = &app_handle;
         use zbus::{proxy, Connection};
         #[proxy(
             interface = "org.mpris.MediaPlayer2.Player",
             default_path = "/org/mpris/MediaPlayer2"
         )]
         trait Player {
-            fn next(&self) -> zbus::Result<()>;
+            fn previous(&self) -> zbus::Result<()>;
         }
         if let Ok(conn) = Connection::session().await {
             let proxy = zbus::fdo::DBusProxy::new(&conn).await.unwrap();
@@ -823,7 +2393,7 @@ pub async fn media_next_track() -> Result<(), String> {
                         .build()
                         .await
                         .unwrap();
-                    let _ = player.next().await;
+                    let _ = player.previous().await;
                 }
             }
         }
@@ -831,14 +2401,182 @@ pub async fn media_next_track() -> Result<(), String> {
     }
 }
 
-/// Go to the previous track
+/// Seek to a specific position in the track (in seconds)
+#[tauri::command]
+pub async fn media_seek(app_handle: tauri::AppHandle, position: f64) -> Result<(), String> {
+    run_media_press(
+        MediaCommand::Seek(position),
+        send_seek(app_handle, position),
+    )
+    .await
+}
+
+/// Custom (forward, backward) skip seconds, None for content-aware defaults (persisted)
+static SKIP_SECONDS: std::sync::RwLock<Option<(f64, f64)>> = std::sync::RwLock::new(None);
+
+/// Longest allowed skip, in seconds
+const MAX_SKIP_SECONDS: f64 = 600.0;
+
+/// Skip lengths media_skip_forward/backward use
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+pub struct SkipSeconds {
+    pub forward: f64,
+    pub backward: f64,
+    /// Whether these are the user's values rather than the content-aware defaults
+    pub custom: bool,
+}
+
+/// The custom skip lengths, or ±30s for podcasts and audiobooks and ±15s for music
+fn skip_seconds_for(content_type: Option<&str>) -> SkipSeconds {
+    if let Some((forward, backward)) = SKIP_SECONDS.read().ok().and_then(|custom| *custom) {
+        return SkipSeconds {
+            forward,
+            backward,
+            custom: true,
+        };
+    }
+
+    let seconds = match content_type {
+        Some("podcast") | Some("audiobook") => 30.0,
+        _ => 15.0,
+    };
+    SkipSeconds {
+        forward: seconds,
+        backward: seconds,
+        custom: false,
+    }
+}
+
+fn load_skip_seconds(app_handle: &tauri::AppHandle) {
+    let stored = crate::database::get_connection(app_handle)
+        .ok()
+        .and_then(|conn| {
+            let sql = "SELECT value FROM settings WHERE key = 'skip_seconds'";
+            crate::database::log_sql(sql);
+            conn.query_row(sql, [], |row| row.get::<_, String>(0)).ok()
+        })
+        .and_then(|value| {
+            let (forward, backward) = value.split_once(',')?;
+            Some((forward.parse::<f64>().ok()?, backward.parse::<f64>().ok()?))
+        })
+        .filter(|(forward, backward)| {
+            (0.0..=MAX_SKIP_SECONDS).contains(forward)
+                && (0.0..=MAX_SKIP_SECONDS).contains(backward)
+        });
+
+    if let Some(seconds) = stored {
+        if let Ok(mut current) = SKIP_SECONDS.write() {
+            *current = Some(seconds);
+        }
+    }
+}
+
+/// Set how far media_skip_forward/backward jump, in seconds. 0 for one direction makes that
+/// skip change track instead, 0 for both restores the defaults (±30s for podcasts and
+/// audiobooks, ±15s for music).
+#[tauri::command]
+pub fn set_skip_seconds(
+    app_handle: tauri::AppHandle,
+    forward: f64,
+    backward: f64,
+) -> Result<(), String> {
+    let valid = |seconds: f64| seconds.is_finite() && (0.0..=MAX_SKIP_SECONDS).contains(&seconds);
+    if !valid(forward) || !valid(backward) {
+        return Err(format!(
+            "Skip seconds must be between 0 and {}",
+            MAX_SKIP_SECONDS
+        ));
+    }
+    let custom = (forward > 0.0 || backward > 0.0).then_some((forward, backward));
+
+    let conn = crate::database::get_connection(&app_handle).map_err(|e| e.to_string())?;
+    match custom {
+        Some((forward, backward)) => {
+            let sql = "INSERT OR REPLACE INTO settings (key, value) VALUES ('skip_seconds', ?1)";
+            crate::database::log_sql(sql);
+            conn.execute(sql, [format!("{},{}", forward, backward)])
+                .map_err(|e| e.to_string())?;
+        }
+        None => {
+            let sql = "DELETE FROM settings WHERE key = 'skip_seconds'";
+            crate::database::log_sql(sql);
+            conn.execute(sql, []).map_err(|e| e.to_string())?;
+        }
+    }
+
+    if let Ok(mut current) = SKIP_SECONDS.write() {
+        *current = custom;
+    }
+    Ok(())
+}
+
+/// Skip lengths for what's playing now (or last played)
+#[tauri::command]
+pub fn get_skip_seconds() -> SkipSeconds {
+    let content_type = LAST_PLAYED
+        .get()
+        .and_then(|m| m.lock().ok())
+        .and_then(|guard| guard.as_ref().and_then(|data| data.content_type.clone()));
+    skip_seconds_for(content_type.as_deref())
+}
+
+/// Move `seconds` forward (or back, when negative) from the current position, clamped to the
+/// track. Seeks through media_seek, so it is queued with the other media presses.
+async fn seek_relative(
+    app_handle: tauri::AppHandle,
+    data: &NowPlayingData,
+    seconds: f64,
+) -> Result<(), String> {
+    let elapsed = data
+        .elapsed_time
+        .ok_or("Unsupported: the current source doesn't report a position")?;
+
+    let mut position = (elapsed + seconds).max(0.0);
+    if let Some(duration) = data.duration.filter(|duration| *duration > 0.0) {
+        position = position.min(duration);
+    }
+    media_seek(app_handle, position).await
+}
+
+/// Jump ahead by the skip length for the current content (30s for podcasts, 15s for music).
+/// Goes to the next track when the forward skip length is 0 or the source can't seek.
+#[tauri::command]
+pub async fn media_skip_forward(app_handle: tauri::AppHandle) -> Result<(), String> {
+    let data = get_now_playing(app_handle.clone()).await;
+    let skip = skip_seconds_for(data.content_type.as_deref());
+    if skip.forward > 0.0 {
+        match seek_relative(app_handle.clone(), &data, skip.forward).await {
+            Err(e) if e.starts_with("Unsupported") => {
+                log::debug!("Can't skip forward ({}), going to the next track", e);
+            }
+            result => return result,
+        }
+    }
+    media_next_track(app_handle).await
+}
+
+/// Jump back by the skip length for the current content (30s for podcasts, 15s for music).
+/// Goes to the previous track when the backward skip length is 0 or the source can't seek.
 #[tauri::command]
-pub async fn media_previous_track() -> Result<(), String> {
+pub async fn media_skip_backward(app_handle: tauri::AppHandle) -> Result<(), String> {
+    let data = get_now_playing(app_handle.clone()).await;
+    let skip = skip_seconds_for(data.content_type.as_deref());
+    if skip.backward > 0.0 {
+        match seek_relative(app_handle.clone(), &data, -skip.backward).await {
+            Err(e) if e.starts_with("Unsupported") => {
+                log::debug!("Can't skip backward ({}), going to the previous track", e);
+            }
+            result => return result,
+        }
+    }
+    media_previous_track(app_handle).await
+}
+
+async fn send_seek(app_handle: tauri::AppHandle, position: f64) -> Result<(), String> {
     #[cfg(target_os = "macos")]
     {
-        use std::process::Command;
-
-        let script = r#"
+        let script = format!(
+            r#"
             tell application "System Events"
                 set spotifyRunning to (name of processes) contains "Spotify"
                 set musicRunning to (name of processes) contains "Music"
@@ -847,8 +2585,8 @@ pub async fn media_previous_track() -> Result<(), String> {
 
             if spotifyRunning then
                 tell application "Spotify"
-                    if player state is playing then
-                        previous track
+                    if player state is not stopped then
+                        set player position to {}
                         return "spotify"
                     end if
                 end tell
@@ -856,8 +2594,8 @@ pub async fn media_previous_track() -> Result<(), String> {
 
             if musicRunning then
                 tell application "Music"
-                    if player state is playing then
-                        back track
+                    if player state is not stopped then
+                        set player position to {}
                         return "music"
                     end if
                 end tell
@@ -870,26 +2608,21 @@ pub async fn media_previous_track() -> Result<(), String> {
                             repeat with t in tabs of w
                                 try
                                     set tabURL to URL of t
-                                    if tabURL contains "youtube.com" or tabURL contains "music.youtube.com" then
-                                        do JavaScript "
-                                            (function() {
-                                                // Try to go back ~10s or restart video usually, or strictly prev button if playlist
-                                                // For now, let's try history back or restart
-                                                window.history.back();
-                                                return 'success';
-                                            })();
-                                        " in t
-                                        return "safari"
-                                    else if tabURL contains "open.spotify.com" then
+                                    if tabURL contains "youtube.com" or tabURL contains "music.youtube.com" or tabURL contains "open.spotify.com" or tabURL contains "soundcloud.com" then
                                         do JavaScript "
-                                            (function() {
-                                                var prevBtn = document.querySelector('[data-testid=\"control-button-skip-back\"]');
-                                                if (prevBtn) {
-                                                    prevBtn.click();
+                                            (function() {{
+                                                var video = document.querySelector('video');
+                                                var audio = document.querySelector('audio');
+                                                var activeMedia = (video && !video.paused && !video.ended) ? video : (audio && !audio.paused && !audio.ended) ? audio : null;
+                                                // Fallback if paused but visible
+                                                if (!activeMedia) activeMedia = video || audio;
+
+                                                if (activeMedia) {{
+                                                    activeMedia.currentTime = {};
                                                     return 'success';
-                                                }
-                                                return 'no_btn';
-                                            })();
+                                                }}
+                                                return 'no_media';
+                                            }})();
                                         " in t
                                         return "safari"
                                     end if
@@ -900,87 +2633,577 @@ pub async fn media_previous_track() -> Result<(), String> {
                 end tell
             end if
 
-            if spotifyRunning then
-                tell application "Spotify" to previous track
-                return "spotify"
-            else if musicRunning then
-                tell application "Music" to back track
-                return "music"
-            else
-                return "no_app"
-            end if
-        "#;
+            return "no_app"
+            "#,
+            position, position, position
+        );
 
-        Command::new("osascript")
-            .arg("-e")
-            .arg(script)
-            .output()
+        match run_osascript(&app_handle, &script)?.as_str() {
+            "no_app" => Err("Unsupported: nothing playing can seek".to_string()),
+            _ => Ok(()),
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let _ = &app_handle;
+        use windows::Media::Control::GlobalSystemMediaTransportControlsSessionManager;
+
+        let manager = GlobalSystemMediaTransportControlsSessionManager::RequestAsync()
+            .map_err(|e| e.to_string())?
+            .await
+            .map_err(|e| e.to_string())?;
+        let session = manager
+            .GetCurrentSession()
+            .map_err(|_| "Unsupported: no media session is active".to_string())?;
+
+        // Position is in 100ns ticks
+        let accepted = session
+            .TryChangePlaybackPositionAsync((position * 10_000_000.0) as i64)
+            .map_err(|e| e.to_string())?
+            .await
+            .map_err(|e| e.to_string())?;
+        if accepted {
+            Ok(())
+        } else {
+            Err("Unsupported: the current media session can't seek".to_string())
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let _ = &app_handle;
+        use zbus::Connection;
+
+        let conn = Connection::session().await.map_err(|e| e.to_string())?;
+        let dbus = zbus::fdo::DBusProxy::new(&conn)
+            .await
             .map_err(|e| e.to_string())?;
+        let names = dbus.list_names().await.map_err(|e| e.to_string())?;
 
+        // Seek the playing player, or a paused one when nothing is playing
+        let mut target = None;
+        for name in names {
+            let name = name.to_string();
+            if !name.starts_with("org.mpris.MediaPlayer2.") {
+                continue;
+            }
+            let player = match zbus::Proxy::new(
+                &conn,
+                name.as_str(),
+                "/org/mpris/MediaPlayer2",
+                "org.mpris.MediaPlayer2.Player",
+            )
+            .await
+            {
+                Ok(player) => player,
+                Err(_) => continue,
+            };
+            if !player
+                .get_property::<bool>("CanSeek")
+                .await
+                .unwrap_or(false)
+            {
+                continue;
+            }
+            let status: String = player
+                .get_property("PlaybackStatus")
+                .await
+                .unwrap_or_default();
+            if status == "Playing" {
+                target = Some(name);
+                break;
+            }
+            if status == "Paused" && target.is_none() {
+                target = Some(name);
+            }
+        }
+        let name = target.ok_or("Unsupported: no MPRIS player can seek")?;
+
+        let player = zbus::Proxy::new(
+            &conn,
+            name.as_str(),
+            "/org/mpris/MediaPlayer2",
+            "org.mpris.MediaPlayer2.Player",
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+        // MPRIS seeks relative to the current position, in microseconds
+        let current: i64 = player.get_property("Position").await.unwrap_or(0);
+        let offset = (position * 1_000_000.0) as i64 - current;
+        player
+            .call_method("Seek", &offset)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// A transport command sent to one specific media app
+#[derive(Debug, Clone, Copy)]
+enum MediaCommand {
+    PlayPause,
+    Next,
+    Previous,
+    Seek(f64),
+}
+
+impl MediaCommand {
+    fn parse(command: &str, position: Option<f64>) -> Result<Self, String> {
+        match command.to_lowercase().as_str() {
+            "play_pause" | "playpause" => Ok(Self::PlayPause),
+            "next" => Ok(Self::Next),
+            "previous" => Ok(Self::Previous),
+            "seek" => match position {
+                Some(p) if p.is_finite() && p >= 0.0 => Ok(Self::Seek(p)),
+                _ => Err("Seek needs a non-negative position".to_string()),
+            },
+            other => Err(format!("Unknown media command: {}", other)),
+        }
+    }
+}
+
+/// Build the AppleScript that sends `command` to a single app (macOS)
+#[cfg(target_os = "macos")]
+fn directed_media_script(app: &str, command: MediaCommand) -> Result<String, String> {
+    let player_command = |previous: &str| match command {
+        MediaCommand::PlayPause => "playpause".to_string(),
+        MediaCommand::Next => "next track".to_string(),
+        MediaCommand::Previous => previous.to_string(),
+        MediaCommand::Seek(position) => format!("set player position to {}", position),
+    };
+
+    let script = match app {
+        "Spotify" => format!(
+            r#"tell application "Spotify" to {}
+            return "handled""#,
+            player_command("previous track")
+        ),
+        "Music" => format!(
+            r#"tell application "Music" to {}
+            return "handled""#,
+            player_command("back track")
+        ),
+        "Safari" | "Google Chrome" => {
+            let action = match command {
+                MediaCommand::PlayPause => {
+                    "if (activeMedia.paused) { activeMedia.play(); } else { activeMedia.pause(); }"
+                        .to_string()
+                }
+                MediaCommand::Seek(position) => format!("activeMedia.currentTime = {};", position),
+                MediaCommand::Next | MediaCommand::Previous => {
+                    return Err(format!(
+                        "Unsupported: {:?} is not available for browser tabs",
+                        command
+                    ))
+                }
+            };
+            let js = format!(
+                "(function() {{ var video = document.querySelector('video'); var audio = document.querySelector('audio'); var activeMedia = (video && !video.paused && !video.ended) ? video : (audio && !audio.paused && !audio.ended) ? audio : null; if (!activeMedia) activeMedia = video || audio; if (activeMedia) {{ {} return 'success'; }} return 'no_media'; }})();",
+                action
+            );
+            let run_js = if app == "Safari" {
+                format!(r#"do JavaScript "{}" in t"#, js)
+            } else {
+                format!(r#"execute t javascript "{}""#, js)
+            };
+            format!(
+                r#"
+                tell application "{}"
+                    repeat with w in windows
+                        repeat with t in tabs of w
+                            try
+                                set tabURL to URL of t
+                                if tabURL contains "youtube.com" or tabURL contains "music.youtube.com" or tabURL contains "open.spotify.com" or tabURL contains "soundcloud.com" then
+                                    set res to {}
+                                    if res is "success" then return "handled"
+                                end if
+                            end try
+                        end repeat
+                    end repeat
+                end tell
+                return "no_media"
+            "#,
+                app, run_js
+            )
+        }
+        _ => return Err(format!("Unsupported media app: {}", app)),
+    };
+
+    Ok(script)
+}
+
+/// Send play_pause/next/previous/seek to the named app, skipping the auto-detection order.
+/// `position` (seconds) is required for seek. Returns the app that handled the command.
+#[tauri::command]
+pub async fn media_command_to(
+    app_handle: tauri::AppHandle,
+    app: String,
+    command: String,
+    position: Option<f64>,
+) -> Result<String, String> {
+    let command = MediaCommand::parse(&command, position)?;
+
+    #[cfg(target_os = "macos")]
+    {
+        let app_name = match app.to_lowercase().as_str() {
+            "spotify" => "Spotify",
+            "music" => "Music",
+            "safari" => "Safari",
+            "chrome" | "google chrome" => "Google Chrome",
+            _ => return Err(format!("Unsupported media app: {}", app)),
+        };
+
+        if !processes_running([app_name])[0] {
+            return Err(format!("{} is not running", app_name));
+        }
+
+        let script = directed_media_script(app_name, command)?;
+        match run_osascript(&app_handle, &script)?.as_str() {
+            "handled" => {
+                log::debug!("{:?} handled by {}", command, app_name);
+                Ok(app_name.to_string())
+            }
+            "no_media" => Err(format!("No media tab found in {}", app_name)),
+            other => Err(format!(
+                "{} did not handle {:?}: {}",
+                app_name, command, other
+            )),
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let _ = &app_handle;
+        use windows::Media::Control::GlobalSystemMediaTransportControlsSessionManager;
+
+        let manager = GlobalSystemMediaTransportControlsSessionManager::RequestAsync()
+            .map_err(|e| e.to_string())?
+            .await
+            .map_err(|e| e.to_string())?;
+        let sessions = manager.GetSessions().map_err(|e| e.to_string())?;
+        let needle = app.to_lowercase();
+
+        for session in sessions {
+            let id = match session.SourceAppUserModelId() {
+                Ok(id) => id.to_string(),
+                Err(_) => continue,
+            };
+            if !id.to_lowercase().contains(&needle) {
+                continue;
+            }
+
+            let operation = match command {
+                MediaCommand::PlayPause => session.TryTogglePlayPauseAsync(),
+                MediaCommand::Next => session.TrySkipNextAsync(),
+                MediaCommand::Previous => session.TrySkipPreviousAsync(),
+                // Position is in 100ns ticks
+                MediaCommand::Seek(position) => {
+                    session.TryChangePlaybackPositionAsync((position * 10_000_000.0) as i64)
+                }
+            };
+            let accepted = operation
+                .map_err(|e| e.to_string())?
+                .await
+                .map_err(|e| e.to_string())?;
+
+            return if accepted {
+                Ok(id)
+            } else {
+                Err(format!("{} rejected {:?}", id, command))
+            };
+        }
+
+        Err(format!("No media session found for {}", app))
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let _ = &app_handle;
+        use zbus::Connection;
+
+        let conn = Connection::session().await.map_err(|e| e.to_string())?;
+        let dbus = zbus::fdo::DBusProxy::new(&conn)
+            .await
+            .map_err(|e| e.to_string())?;
+        let needle = app.to_lowercase();
+
+        let name = dbus
+            .list_names()
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|name| name.to_string())
+            .find(|name| {
+                name.strip_prefix("org.mpris.MediaPlayer2.")
+                    .map(|player| player.to_lowercase().contains(&needle))
+                    .unwrap_or(false)
+            })
+            .ok_or(format!("No MPRIS player found for {}", app))?;
+
+        let player = zbus::Proxy::new(
+            &conn,
+            name.as_str(),
+            "/org/mpris/MediaPlayer2",
+            "org.mpris.MediaPlayer2.Player",
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
+        match command {
+            MediaCommand::PlayPause => player.call_method("PlayPause", &()).await,
+            MediaCommand::Next => player.call_method("Next", &()).await,
+            MediaCommand::Previous => player.call_method("Previous", &()).await,
+            // MPRIS seeks relative to the current position, in microseconds
+            MediaCommand::Seek(position) => {
+                let current: i64 = player.get_property("Position").await.unwrap_or(0);
+                let target = (position * 1_000_000.0) as i64;
+                player.call_method("Seek", &(target - current)).await
+            }
+        }
+        .map_err(|e| e.to_string())?;
+
+        Ok(name.replace("org.mpris.MediaPlayer2.", ""))
+    }
+}
+
+/// App name of the most recent now-playing source (as reported in NowPlayingData.app_name)
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn last_played_app() -> Option<String> {
+    LAST_PLAYED
+        .get()
+        .and_then(|m| m.lock().ok())
+        .and_then(|guard| guard.as_ref().and_then(|data| data.app_name.clone()))
+}
+
+/// Jump to the track at `index` (0-based) in the current source's queue.
+/// Works for Music.app (its current playlist) and MPRIS players implementing TrackList;
+/// Spotify, web players and Windows sessions can't jump to arbitrary queue items.
+#[tauri::command]
+pub async fn play_queue_item(app_handle: tauri::AppHandle, index: usize) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        match last_played_app().as_deref() {
+            Some("Music") => {
+                let script = format!(
+                    r#"tell application "Music" to play track {} of current playlist"#,
+                    index + 1
+                );
+                run_osascript(&app_handle, &script).map(|_| ())
+            }
+            Some(app) => Err(format!("Unsupported: {} has no scriptable queue", app)),
+            None => Err("Nothing is playing".to_string()),
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let _ = (&app_handle, index);
+        Err("Unsupported: media sessions don't expose their queue".to_string())
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let _ = &app_handle;
+        use zbus::zvariant::OwnedObjectPath;
+        use zbus::Connection;
+
+        let app = last_played_app().ok_or("Nothing is playing")?;
+        let name = format!("org.mpris.MediaPlayer2.{}", app);
+
+        let conn = Connection::session().await.map_err(|e| e.to_string())?;
+        let track_list = zbus::Proxy::new(
+            &conn,
+            name.as_str(),
+            "/org/mpris/MediaPlayer2",
+            "org.mpris.MediaPlayer2.TrackList",
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let tracks: Vec<OwnedObjectPath> = track_list
+            .get_property("Tracks")
+            .await
+            .map_err(|_| format!("Unsupported: {} doesn't implement TrackList", app))?;
+        let track = tracks.get(index).ok_or(format!(
+            "Queue has {} items, no item {}",
+            tracks.len(),
+            index
+        ))?;
+
+        track_list
+            .call_method("GoTo", &(track,))
+            .await
+            .map_err(|e| e.to_string())?;
         Ok(())
     }
+}
+
+/// An AirPlay device playback can be routed to
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PlaybackTarget {
+    /// Persistent id, as passed to `set_playback_target`
+    pub id: String,
+    pub name: String,
+    /// Device kind as Music reports it, e.g. "computer", "HomePod", "Apple TV"
+    pub kind: String,
+    /// Playback is currently routed to this device
+    pub selected: bool,
+    pub available: bool,
+}
+
+/// Parse one `id|name|kind|selected|available` line (macOS)
+#[cfg(target_os = "macos")]
+fn parse_playback_target(line: &str) -> Option<PlaybackTarget> {
+    let parts: Vec<&str> = line.split('|').collect();
+    if parts.len() < 5 || parts[0].is_empty() {
+        return None;
+    }
+
+    Some(PlaybackTarget {
+        id: parts[0].to_string(),
+        name: parts[1].to_string(),
+        kind: parts[2].to_string(),
+        selected: parts[3] == "true",
+        available: parts[4] == "true",
+    })
+}
+
+/// List the AirPlay devices Music.app can play to (including this computer).
+/// Only Music.app exposes its output devices; elsewhere this returns an Unsupported error.
+#[tauri::command]
+pub async fn list_playback_targets(
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<PlaybackTarget>, String> {
+    #[cfg(target_os = "macos")]
+    {
+        let [music_running] = processes_running(["Music"]);
+        if !music_running {
+            return Err("Music is not running".to_string());
+        }
+
+        let script = r#"
+            tell application "Music"
+                set targets to {}
+                repeat with d in AirPlay devices
+                    set end of targets to (persistent ID of d) & "|" & (name of d) & "|" & (kind of d as text) & "|" & (selected of d as text) & "|" & (available of d as text)
+                end repeat
+            end tell
+            set AppleScript's text item delimiters to linefeed
+            return targets as text
+        "#;
+        let stdout = run_osascript(&app_handle, script)?;
+        Ok(stdout.lines().filter_map(parse_playback_target).collect())
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = &app_handle;
+        Err("Unsupported: playback targets are only available for Music.app".to_string())
+    }
+}
+
+/// Move Music.app playback to the AirPlay device with the given id (from `list_playback_targets`)
+#[tauri::command]
+pub async fn set_playback_target(app_handle: tauri::AppHandle, id: String) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        let [music_running] = processes_running(["Music"]);
+        if !music_running {
+            return Err("Music is not running".to_string());
+        }
+
+        // Persistent ids are hex, anything else can't match and mustn't reach the script
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("Invalid playback target: {}", id));
+        }
+
+        let script = format!(
+            r#"
+            tell application "Music"
+                set matches to (every AirPlay device whose persistent ID is "{}")
+                if matches is {{}} then return "missing"
+                set current AirPlay devices to matches
+            end tell
+            return "ok"
+        "#,
+            id
+        );
+        match run_osascript(&app_handle, &script)?.as_str() {
+            "ok" => Ok(()),
+            "missing" => Err(format!("No AirPlay device with id {}", id)),
+            other => Err(format!("Failed to switch playback target: {}", other)),
+        }
+    }
 
-    #[cfg(target_os = "windows")]
+    #[cfg(not(target_os = "macos"))]
     {
-        use windows::Media::Control::GlobalSystemMediaTransportControlsSessionManager;
-        if let Ok(manager) = GlobalSystemMediaTransportControlsSessionManager::RequestAsync() {
-            if let Ok(manager) = manager.await {
-                if let Ok(session) = manager.GetCurrentSession() {
-                    let _ = session.TrySkipPreviousAsync().unwrap().await;
-                }
-            }
-        }
-        Ok(())
+        let _ = (&app_handle, &id);
+        Err("Unsupported: playback targets are only available for Music.app".to_string())
     }
+}
 
-    #[cfg(target_os = "linux")]
-    {
-        use zbus::{proxy, Connection};
-        #[proxy(
-            interface = "org.mpris.MediaPlayer2.Player",
-            default_path = "/org/mpris/MediaPlayer2"
-        )]
-        trait Player {
-            fn previous(&self) -> zbus::Result<()>;
-        }
-        if let Ok(conn) = Connection::session().await {
-            let proxy = zbus::fdo::DBusProxy::new(&conn).await.unwrap();
-            let names = proxy.list_names().await.unwrap();
-            for name in names {
-                if name.starts_with("org.mpris.MediaPlayer2.") {
-                    let player = PlayerProxy::builder(&conn)
-                        .destination(name)
-                        .unwrap()
-                        .build()
-                        .await
-                        .unwrap();
-                    let _ = player.previous().await;
-                }
-            }
-        }
-        Ok(())
+fn load_suppress_volume_hud(app_handle: &tauri::AppHandle) {
+    let stored = crate::database::get_connection(app_handle)
+        .ok()
+        .and_then(|conn| {
+            let sql = "SELECT value FROM settings WHERE key = 'suppress_volume_hud'";
+            crate::database::log_sql(sql);
+            conn.query_row(sql, [], |row| row.get::<_, String>(0)).ok()
+        })
+        .and_then(|value| value.parse::<bool>().ok());
+
+    if let Some(suppress) = stored {
+        SUPPRESS_VOLUME_HUD.store(suppress, Ordering::Relaxed);
     }
 }
 
-/// Seek to a specific position in the track (in seconds)
+/// Set whether volume changes should suppress the system volume HUD (persisted).
+/// macOS has no public API to hide the HUD, so when enabled the volume is changed via the
+/// playing app's own AppleScript `sound volume` (which never shows the HUD) instead of system volume.
+#[tauri::command]
+pub fn set_suppress_volume_hud(app_handle: tauri::AppHandle, suppress: bool) -> Result<(), String> {
+    let conn = crate::database::get_connection(&app_handle).map_err(|e| e.to_string())?;
+    let sql = "INSERT OR REPLACE INTO settings (key, value) VALUES ('suppress_volume_hud', ?1)";
+    crate::database::log_sql(sql);
+    conn.execute(sql, [suppress.to_string()])
+        .map_err(|e| e.to_string())?;
+
+    SUPPRESS_VOLUME_HUD.store(suppress, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Get whether volume HUD suppression is enabled
+#[tauri::command]
+pub fn get_suppress_volume_hud() -> bool {
+    SUPPRESS_VOLUME_HUD.load(Ordering::Relaxed)
+}
+
+/// Set the playback volume (0-100, macOS only)
+/// Honors the HUD suppression setting: per-app volume when suppressed, system volume otherwise
 #[tauri::command]
-pub async fn media_seek(position: f64) -> Result<(), String> {
+pub async fn media_set_volume(app_handle: tauri::AppHandle, volume: u8) -> Result<(), String> {
+    let volume = volume.min(100);
+
     #[cfg(target_os = "macos")]
     {
-        use std::process::Command;
+        if !SUPPRESS_VOLUME_HUD.load(Ordering::Relaxed) {
+            let script = format!("set volume output volume {}", volume);
+            run_osascript(&app_handle, &script)?;
+            return Ok(());
+        }
 
         let script = format!(
             r#"
             tell application "System Events"
                 set spotifyRunning to (name of processes) contains "Spotify"
                 set musicRunning to (name of processes) contains "Music"
-                set safariRunning to (name of processes) contains "Safari"
             end tell
 
             if spotifyRunning then
                 tell application "Spotify"
                     if player state is playing then
-                        set player position to {}
+                        set sound volume to {}
                         return "spotify"
                     end if
                 end tell
@@ -989,63 +3212,133 @@ pub async fn media_seek(position: f64) -> Result<(), String> {
             if musicRunning then
                 tell application "Music"
                     if player state is playing then
-                        set player position to {}
+                        set sound volume to {}
                         return "music"
                     end if
                 end tell
             end if
 
-            if safariRunning then
-                tell application "Safari"
-                    try
-                        repeat with w in windows
-                            repeat with t in tabs of w
-                                try
-                                    set tabURL to URL of t
-                                    if tabURL contains "youtube.com" or tabURL contains "music.youtube.com" or tabURL contains "open.spotify.com" or tabURL contains "soundcloud.com" then
-                                        do JavaScript "
-                                            (function() {{
-                                                var video = document.querySelector('video');
-                                                var audio = document.querySelector('audio');
-                                                var activeMedia = (video && !video.paused && !video.ended) ? video : (audio && !audio.paused && !audio.ended) ? audio : null;
-                                                // Fallback if paused but visible
-                                                if (!activeMedia) activeMedia = video || audio;
-
-                                                if (activeMedia) {{
-                                                    activeMedia.currentTime = {};
-                                                    return 'success';
-                                                }}
-                                                return 'no_media';
-                                            }})();
-                                        " in t
-                                        return "safari"
-                                    end if
-                                end try
-                            end repeat
-                        end repeat
-                    end try
-                end tell
-            end if
-
             return "no_app"
             "#,
-            position, position, position
+            volume, volume
         );
 
-        Command::new("osascript")
-            .arg("-e")
-            .arg(script)
-            .output()
-            .map_err(|e| e.to_string())?;
+        // Don't fall back to system volume here, that would show the HUD
+        if run_osascript(&app_handle, &script)? == "no_app" {
+            return Err("No playing app supports per-app volume".to_string());
+        }
 
         Ok(())
     }
 
     #[cfg(not(target_os = "macos"))]
     {
-        // Seeking not easily supported in global transport controls universally
-        Ok(())
+        let _ = (&app_handle, volume);
+        Err("Unsupported: setting the playback volume is only available on macOS".to_string())
+    }
+}
+
+/// Default render endpoint's volume control (Windows)
+#[cfg(target_os = "windows")]
+fn default_endpoint_volume(
+) -> Result<windows::Win32::Media::Audio::Endpoints::IAudioEndpointVolume, String> {
+    use windows::Win32::Media::Audio::{
+        eConsole, eRender, IMMDeviceEnumerator, MMDeviceEnumerator,
+    };
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CLSCTX_ALL, COINIT_MULTITHREADED,
+    };
+
+    unsafe {
+        // Fails harmlessly if COM is already initialized on this thread
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+
+        let enumerator: IMMDeviceEnumerator =
+            CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL).map_err(|e| e.to_string())?;
+        let device = enumerator
+            .GetDefaultAudioEndpoint(eRender, eConsole)
+            .map_err(|e| e.to_string())?;
+        device.Activate(CLSCTX_ALL, None).map_err(|e| e.to_string())
+    }
+}
+
+/// Run a PulseAudio/PipeWire CLI tool and return its stdout (Linux)
+#[cfg(target_os = "linux")]
+fn run_audio_cli(program: &str, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new(program)
+        .args(args)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Get whether the system output device is muted
+#[tauri::command]
+pub fn get_mute_state(app_handle: tauri::AppHandle) -> Result<bool, String> {
+    #[cfg(target_os = "macos")]
+    {
+        let muted = run_osascript(&app_handle, "output muted of (get volume settings)")?;
+        Ok(muted == "true")
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let _ = &app_handle;
+        let volume = default_endpoint_volume()?;
+        unsafe { volume.GetMute() }
+            .map(|muted| muted.as_bool())
+            .map_err(|e| e.to_string())
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let _ = &app_handle;
+        // pactl works on PulseAudio and pipewire-pulse, wpctl on plain PipeWire
+        if let Some(output) = run_audio_cli("pactl", &["get-sink-mute", "@DEFAULT_SINK@"]) {
+            return Ok(output.contains("yes"));
+        }
+        run_audio_cli("wpctl", &["get-volume", "@DEFAULT_AUDIO_SINK@"])
+            .map(|output| output.contains("[MUTED]"))
+            .ok_or_else(|| "Unsupported: neither pactl nor wpctl is available".to_string())
+    }
+}
+
+/// Toggle mute on the system output device and return the resulting state
+#[tauri::command]
+pub fn toggle_mute(app_handle: tauri::AppHandle) -> Result<bool, String> {
+    let muted = !get_mute_state(app_handle.clone())?;
+
+    #[cfg(target_os = "macos")]
+    {
+        let script = if muted {
+            "set volume with output muted"
+        } else {
+            "set volume without output muted"
+        };
+        run_osascript(&app_handle, script)?;
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::Foundation::BOOL;
+
+        let volume = default_endpoint_volume()?;
+        unsafe { volume.SetMute(BOOL::from(muted), std::ptr::null()) }
+            .map_err(|e| e.to_string())?;
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let value = if muted { "1" } else { "0" };
+        run_audio_cli("pactl", &["set-sink-mute", "@DEFAULT_SINK@", value])
+            .or_else(|| run_audio_cli("wpctl", &["set-mute", "@DEFAULT_AUDIO_SINK@", value]))
+            .ok_or("Failed to change mute state")?;
     }
+
+    get_mute_state(app_handle)
 }
 
 /// Activate the media application
@@ -1081,13 +3374,254 @@ pub fn activate_media_app(app_name: String) -> Result<(), String> {
     }
 }
 
+/// Site icon of the now-playing source when it's a browser tab
+static CURRENT_SOURCE_FAVICON: std::sync::RwLock<Option<String>> = std::sync::RwLock::new(None);
+
+/// Encoded source icons by lowercased app name (favicon URL for browser tabs). Misses aren't
+/// cached, so an icon that failed to load (e.g. while offline) is tried again next time
+static SOURCE_ICON_CACHE: std::sync::OnceLock<
+    std::sync::Mutex<std::collections::HashMap<String, String>>,
+> = std::sync::OnceLock::new();
+
+/// Forget the cached source icons, returns the number of entries dropped
+pub fn clear_source_icon_cache() -> usize {
+    SOURCE_ICON_CACHE
+        .get()
+        .and_then(|cache| cache.lock().ok())
+        .map(|mut cache| {
+            let entries = cache.len();
+            cache.clear();
+            entries
+        })
+        .unwrap_or(0)
+}
+
+/// Source icons are shown as a small badge
+const SOURCE_ICON_MAX_DIMENSION: u32 = 64;
+
+/// PNG icon of an installed app, looked up by name (macOS)
+#[cfg(target_os = "macos")]
+fn app_icon_png(app_name: &str) -> Option<Vec<u8>> {
+    use objc2::runtime::AnyObject;
+    use objc2::*;
+    use objc2_foundation::NSString;
+
+    unsafe {
+        let workspace: *mut AnyObject = msg_send![class!(NSWorkspace), sharedWorkspace];
+        let name = NSString::from_str(app_name);
+        let path: *mut AnyObject = msg_send![workspace, fullPathForApplication: &*name];
+        if path.is_null() {
+            return None;
+        }
+
+        let icon: *mut AnyObject = msg_send![workspace, iconForFile: path];
+        let tiff: *mut AnyObject = msg_send![icon, TIFFRepresentation];
+        if tiff.is_null() {
+            return None;
+        }
+        let bitmap: *mut AnyObject = msg_send![class!(NSBitmapImageRep), imageRepWithData: tiff];
+        if bitmap.is_null() {
+            return None;
+        }
+
+        // NSBitmapImageFileTypePNG = 4
+        let properties: *mut AnyObject = msg_send![class!(NSDictionary), dictionary];
+        let png: *mut AnyObject =
+            msg_send![bitmap, representationUsingType: 4_u64, properties: properties];
+        if png.is_null() {
+            return None;
+        }
+        let length: usize = msg_send![png, length];
+        let bytes: *const u8 = msg_send![png, bytes];
+        if bytes.is_null() {
+            return None;
+        }
+        Some(std::slice::from_raw_parts(bytes, length).to_vec())
+    }
+}
+
+/// PNG icon of an MPRIS player from the hicolor theme or pixmaps (Linux)
+#[cfg(target_os = "linux")]
+fn app_icon_png(app_name: &str) -> Option<Vec<u8>> {
+    // MPRIS names can carry an instance suffix, e.g. firefox.instance_1_42
+    let name = app_name
+        .split('.')
+        .next()
+        .unwrap_or(app_name)
+        .to_lowercase();
+
+    [
+        "/usr/share/icons/hicolor/128x128/apps",
+        "/usr/share/icons/hicolor/256x256/apps",
+        "/usr/share/icons/hicolor/64x64/apps",
+        "/usr/share/icons/hicolor/48x48/apps",
+        "/usr/share/pixmaps",
+    ]
+    .iter()
+    .find_map(|dir| std::fs::read(std::path::Path::new(dir).join(format!("{}.png", name))).ok())
+}
+
+/// Icons aren't looked up on Windows yet
+#[cfg(target_os = "windows")]
+fn app_icon_png(_app_name: &str) -> Option<Vec<u8>> {
+    None
+}
+
+fn source_icon(app_name: &str) -> Option<String> {
+    // A browser tab is badged with its site's icon rather than the browser's
+    let favicon = if app_name.eq_ignore_ascii_case("safari") {
+        CURRENT_SOURCE_FAVICON
+            .read()
+            .ok()
+            .and_then(|favicon| favicon.clone())
+    } else {
+        None
+    };
+    let key = favicon.clone().unwrap_or_else(|| app_name.to_lowercase());
+
+    let cache =
+        SOURCE_ICON_CACHE.get_or_init(|| std::sync::Mutex::new(std::collections::HashMap::new()));
+    if let Some(cached) = cache.lock().ok().and_then(|cache| cache.get(&key).cloned()) {
+        return Some(cached);
+    }
+
+    let data = match &favicon {
+        Some(url) => crate::utils::fetch_artwork_bytes(url),
+        None => app_icon_png(app_name),
+    }?;
+    let icon = match crate::utils::downscale_image(&data, SOURCE_ICON_MAX_DIMENSION) {
+        Some(resized) => resized,
+        // Favicons are often .ico, which is small enough to be kept as is but not displayable
+        None if image::guess_format(&data).ok() == Some(image::ImageFormat::Ico) => {
+            let mut png = std::io::Cursor::new(Vec::new());
+            image::load_from_memory(&data)
+                .ok()?
+                .write_to(&mut png, image::ImageFormat::Png)
+                .ok()?;
+            png.into_inner()
+        }
+        None => data,
+    };
+    let icon = base64_encode(&icon);
+
+    if let Ok(mut cache) = cache.lock() {
+        cache.insert(key, icon.clone());
+    }
+    Some(icon)
+}
+
+/// Base64 icon badge for a now-playing source, cached per source: the app's own icon, or the
+/// site's favicon when the source is the browser tab currently playing. None if unavailable.
+#[tauri::command]
+pub async fn get_media_source_icon(app_name: String) -> Option<String> {
+    tauri::async_runtime::spawn_blocking(move || source_icon(&app_name))
+        .await
+        .ok()
+        .flatten()
+}
+
+/// Bring the active media app forward and navigate to the now-playing track where possible
+/// (Spotify: open the track URI, Music: reveal the current track, Safari: select the playing tab)
+#[tauri::command]
+pub fn focus_current_track(app_handle: tauri::AppHandle) -> Result<(), String> {
+    let last = LAST_PLAYED
+        .get()
+        .and_then(|m| m.lock().ok())
+        .and_then(|guard| guard.clone())
+        .ok_or("Nothing has been played yet")?;
+    let app_name = last.app_name.clone().ok_or("Unknown media app")?;
+
+    #[cfg(target_os = "macos")]
+    {
+        use crate::utils::applescript_escape;
+        use std::process::Command;
+
+        match app_name.as_str() {
+            "Spotify" => {
+                let uri = run_osascript(
+                    &app_handle,
+                    r#"tell application "Spotify" to id of current track"#,
+                )?;
+                if uri.starts_with("spotify:") {
+                    Command::new("open")
+                        .arg(&uri)
+                        .output()
+                        .map_err(|e| e.to_string())?;
+                    return Ok(());
+                }
+            }
+            "Music" => {
+                run_osascript(
+                    &app_handle,
+                    r#"
+                    tell application "Music"
+                        reveal current track
+                        activate
+                    end tell
+                "#,
+                )?;
+                return Ok(());
+            }
+            "Safari" => {
+                // For Safari the tab URL is stored in the album field
+                if let Some(url) = last.album.as_deref().filter(|u| !u.is_empty()) {
+                    let script = format!(
+                        r#"
+                        tell application "Safari"
+                            repeat with w in windows
+                                repeat with t in tabs of w
+                                    try
+                                        if URL of t is "{}" then
+                                            set current tab of w to t
+                                            set index of w to 1
+                                            activate
+                                            return "found"
+                                        end if
+                                    end try
+                                end repeat
+                            end repeat
+                        end tell
+                        return "not_found"
+                    "#,
+                        applescript_escape(url)
+                    );
+
+                    if run_osascript(&app_handle, &script)? == "found" {
+                        return Ok(());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    let _ = &app_handle;
+
+    // Fall back to just bringing the app forward
+    activate_media_app(app_name)
+}
+
 use std::thread;
 
-/// Setup audio level monitoring using simulated audio visualization
+/// Setup audio level monitoring for the configured visualizer source.
+/// Real capture is not implemented yet, so every source currently runs the simulation.
 pub fn setup_audio_monitoring(app_handle: tauri::AppHandle) {
     // Initialize the audio levels storage if not already done
     if AUDIO_LEVELS.get().is_none() {
-        let _ = AUDIO_LEVELS.set(std::sync::Mutex::new(vec![0.15; 6]));
+        let _ = AUDIO_LEVELS.set(std::sync::Mutex::new(vec![0.15; VISUALIZER_BANDS]));
+    }
+
+    load_visualizer_source(&app_handle);
+    load_artwork_max_dimension(&app_handle);
+    load_visualizer_crossfade(&app_handle);
+    load_skip_seconds(&app_handle);
+    load_suppress_volume_hud(&app_handle);
+    load_track_ending_threshold(&app_handle);
+    if get_visualizer_source() == VisualizerSource::Real
+        && effective_visualizer_source() != VisualizerSource::Real
+    {
+        log::warn!("Real audio capture is unavailable, falling back to simulation");
     }
 
     // Spawn simulation thread
@@ -1095,22 +3629,71 @@ pub fn setup_audio_monitoring(app_handle: tauri::AppHandle) {
         log::info!("🎭 Starting audio visualization simulation");
 
         let mut t = 0.0f64;
-        let mut prev_levels = vec![0.15; 6];
+        let mut prev_levels = vec![0.15; VISUALIZER_BANDS];
         let mut beat_phase = 0.0f64;
         let mut energy = 0.5f64;
+        let floor_levels = vec![VISUALIZER_FLOOR; VISUALIZER_BANDS];
+        // Last emitted levels, where a cross-fade starts from
+        let mut shown = prev_levels.clone();
+        // Start levels and time of the running track-change dip or stop fade, if any
+        let mut track_fade: Option<(Vec<f64>, std::time::Instant)> = None;
+        let mut stop_fade: Option<(Vec<f64>, std::time::Instant)> = None;
+        // Whether the bars have settled at the floor since playback stopped
+        let mut settled = false;
 
         // Reduce to 30fps to save IPC overhead and make transitions smoother
-        let frame_duration = std::time::Duration::from_micros(33333); // ~30fps
+        let frame_duration = std::time::Duration::from_secs(1) / VISUALIZER_FPS;
         let mut next_frame = std::time::Instant::now();
 
         loop {
-            if !IS_PLAYING.load(Ordering::Relaxed) {
-                std::thread::sleep(std::time::Duration::from_millis(200));
+            // Nothing renders the visualizer while the window is hidden
+            if crate::power::is_monitoring_paused() {
+                crate::power::idle_while_paused(std::time::Duration::from_secs(1));
                 next_frame = std::time::Instant::now();
                 continue;
             }
+            let fade_duration = std::time::Duration::from_millis(
+                VISUALIZER_CROSSFADE_MS.load(Ordering::Relaxed) as u64,
+            );
+
+            if !IS_PLAYING.load(Ordering::Relaxed) || crate::power::is_battery_saver_active() {
+                track_fade = None;
+                if settled || fade_duration.is_zero() {
+                    stop_fade = None;
+                    settled = true;
+                    std::thread::sleep(std::time::Duration::from_millis(200));
+                    next_frame = std::time::Instant::now();
+                    continue;
+                }
+
+                // Let the bars fall to the floor instead of freezing mid-bar
+                let (from, started) =
+                    stop_fade.get_or_insert_with(|| (shown.clone(), std::time::Instant::now()));
+                let progress = started.elapsed().as_secs_f64() / fade_duration.as_secs_f64();
+                let levels = crossfade_levels(from, &floor_levels, progress);
+                if progress >= 1.0 {
+                    settled = true;
+                    stop_fade = None;
+                }
+
+                // Resuming rises from wherever the bars were left
+                prev_levels = levels.clone();
+                shown = levels.clone();
+                set_audio_levels(levels.clone());
+                let _ = app_handle.emit("audio-levels-update", levels);
+
+                wait_for_next_frame(&mut next_frame, frame_duration);
+                continue;
+            }
 
-            t += 0.0333; // Time increment per frame (30fps)
+            settled = false;
+            stop_fade = None;
+            // A new track dips to the floor and back up
+            if VISUALIZER_TRACK_CHANGED.swap(false, Ordering::Relaxed) && !fade_duration.is_zero() {
+                track_fade = Some((shown.clone(), std::time::Instant::now()));
+            }
+
+            t += 1.0 / VISUALIZER_FPS as f64; // Time increment per frame
 
             // Simulate varying energy levels (like quiet vs loud parts of a song)
             let energy_wave = (t * 0.15).sin() * 0.3 + 0.9;
@@ -1129,7 +3712,7 @@ pub fn setup_audio_monitoring(app_handle: tauri::AppHandle) {
                 (hasher.finish() % 1000) as f64 / 1000.0 - 0.5
             };
 
-            let mut levels = vec![0.0; 6];
+            let mut levels = vec![0.0; VISUALIZER_BANDS];
 
             // Bass (20-150 Hz) - strongest on beat
             levels[0] = energy * (0.4 + beat * 0.5 + noise() * 0.1);
@@ -1153,7 +3736,7 @@ pub fn setup_audio_monitoring(app_handle: tauri::AppHandle) {
                 energy * (0.18 + (t * 11.2).sin() * 0.1 + (t * 9.7).cos() * 0.08 + noise() * 0.06);
 
             // Smooth transitions (exponential moving average)
-            for i in 0..6 {
+            for i in 0..VISUALIZER_BANDS {
                 // Adjusted smoothing for 30fps (needs to be slightly higher to match speed of 60fps)
                 let smoothing = if levels[i] > prev_levels[i] {
                     0.5 // faster attack
@@ -1162,23 +3745,283 @@ pub fn setup_audio_monitoring(app_handle: tauri::AppHandle) {
                 };
                 levels[i] = prev_levels[i] + (levels[i] - prev_levels[i]) * smoothing;
                 // Clamp to valid range
-                levels[i] = levels[i].clamp(0.08, 0.92);
+                levels[i] = levels[i].clamp(VISUALIZER_FLOOR, VISUALIZER_CEILING);
             }
 
+            // Smoothed levels keep evolving underneath so the fade lands on live values
             prev_levels = levels.clone();
 
+            if let Some((from, started)) = &track_fade {
+                let progress = started.elapsed().as_secs_f64() / fade_duration.as_secs_f64();
+                levels = if progress < 0.5 {
+                    crossfade_levels(from, &floor_levels, progress * 2.0)
+                } else {
+                    crossfade_levels(&floor_levels, &levels, progress * 2.0 - 1.0)
+                };
+                if progress >= 1.0 {
+                    track_fade = None;
+                }
+            }
+
+            shown = levels.clone();
             set_audio_levels(levels.clone());
             let _ = app_handle.emit("audio-levels-update", levels);
 
-            // Precise timing for consistent 60fps
-            next_frame += frame_duration;
-            let now = std::time::Instant::now();
-            if next_frame > now {
-                std::thread::sleep(next_frame - now);
-            } else {
-                // If we're behind, reset timing
-                next_frame = now + frame_duration;
+            wait_for_next_frame(&mut next_frame, frame_duration);
+        }
+    });
+}
+
+/// Sleep until `next_frame` and advance it, for consistent frame pacing
+fn wait_for_next_frame(next_frame: &mut std::time::Instant, frame_duration: std::time::Duration) {
+    *next_frame += frame_duration;
+    let now = std::time::Instant::now();
+    if *next_frame > now {
+        std::thread::sleep(*next_frame - now);
+    } else {
+        // If we're behind, reset timing
+        *next_frame = now + frame_duration;
+    }
+}
+
+/// Watch MPRIS `PropertiesChanged` signals and push `now-playing-changed` updates (Linux)
+/// Falls back to polling `get_now_playing` if the signal subscription cannot be set up
+#[cfg(target_os = "linux")]
+pub fn setup_mpris_monitoring(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = watch_mpris_signals(&app_handle).await {
+            log::warn!(
+                "MPRIS signal subscription failed, falling back to polling: {}",
+                e
+            );
+
+            let mut last_key = None;
+            loop {
+                emit_now_playing_if_changed(&app_handle, &mut last_key).await;
+                tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+            }
+        }
+    });
+}
+
+/// Subscribe to Player property changes from every MPRIS player on the session bus.
+/// Each signal triggers a fresh query, which picks whichever player is currently playing.
+#[cfg(target_os = "linux")]
+async fn watch_mpris_signals(app_handle: &tauri::AppHandle) -> zbus::Result<()> {
+    use futures_util::StreamExt;
+    use zbus::{Connection, MatchRule, MessageStream};
+
+    let conn = Connection::session().await?;
+
+    let rule = MatchRule::builder()
+        .msg_type(zbus::message::Type::Signal)
+        .interface("org.freedesktop.DBus.Properties")?
+        .member("PropertiesChanged")?
+        .path("/org/mpris/MediaPlayer2")?
+        .arg(0, "org.mpris.MediaPlayer2.Player")?
+        .build();
+
+    let mut stream = MessageStream::for_match_rule(rule, &conn, Some(16)).await?;
+    log::info!("Subscribed to MPRIS PropertiesChanged signals");
+
+    // Emit the initial state so the frontend doesn't wait for the first change
+    let mut last_key = None;
+    emit_now_playing_if_changed(app_handle, &mut last_key).await;
+
+    while let Some(msg) = stream.next().await {
+        if msg.is_err() {
+            continue;
+        }
+        emit_now_playing_if_changed(app_handle, &mut last_key).await;
+    }
+
+    Err(zbus::Error::Failure(
+        "MPRIS signal stream ended".to_string(),
+    ))
+}
+
+/// Query now playing and emit `now-playing-changed` if the track or play state differs
+#[cfg(target_os = "linux")]
+async fn emit_now_playing_if_changed(
+    app_handle: &tauri::AppHandle,
+    last_key: &mut Option<(Option<String>, Option<String>, bool)>,
+) {
+    let data = get_now_playing(app_handle.clone()).await;
+    let key = (data.title.clone(), data.artist.clone(), data.is_playing);
+
+    if last_key.as_ref() != Some(&key) {
+        *last_key = Some(key);
+        let _ = app_handle.emit("now-playing-changed", data);
+    }
+}
+
+/// Bumped by subscribe/unsubscribe so only the latest now-playing poller keeps running
+static NOW_PLAYING_SUBSCRIPTION: std::sync::atomic::AtomicU64 =
+    std::sync::atomic::AtomicU64::new(0);
+
+/// Whether two snapshots differ in track or playback state (elapsed time and levels are ignored)
+fn now_playing_changed(previous: &NowPlayingData, current: &NowPlayingData) -> bool {
+    previous.title != current.title
+        || previous.artist != current.artist
+        || previous.album != current.album
+        || previous.app_name != current.app_name
+        || previous.is_playing != current.is_playing
+        || previous.duration != current.duration
+        || previous.artwork_base64 != current.artwork_base64
+}
+
+/// Start a single backend poller that emits `now-playing-update` only when the track or
+/// playback state changes. Replaces any existing subscription.
+#[tauri::command]
+pub fn subscribe_now_playing(app_handle: tauri::AppHandle, interval_ms: u64) {
+    let interval = std::time::Duration::from_millis(interval_ms.clamp(250, 10_000));
+    let subscription = NOW_PLAYING_SUBSCRIPTION.fetch_add(1, Ordering::SeqCst) + 1;
+
+    log::debug!("Now playing subscription started ({:?})", interval);
+
+    tauri::async_runtime::spawn(async move {
+        let mut last: Option<NowPlayingData> = None;
+
+        while NOW_PLAYING_SUBSCRIPTION.load(Ordering::SeqCst) == subscription {
+            if !crate::power::is_monitoring_paused() {
+                let data = get_now_playing(app_handle.clone()).await;
+                let changed = match &last {
+                    Some(prev) => now_playing_changed(prev, &data),
+                    None => true,
+                };
+                if changed {
+                    let _ = app_handle.emit("now-playing-update", &data);
+                    last = Some(data);
+                }
             }
+
+            tokio::time::sleep(interval).await;
         }
+
+        log::debug!("Now playing subscription stopped");
     });
 }
+
+/// Stop the poller started by subscribe_now_playing
+#[tauri::command]
+pub fn unsubscribe_now_playing() {
+    NOW_PLAYING_SUBSCRIPTION.fetch_add(1, Ordering::SeqCst);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_artists_keeps_names_with_commas_and_ampersands() {
+        assert_eq!(split_artists("Tyler, The Creator"), ["Tyler, The Creator"]);
+        assert_eq!(split_artists("Earth, Wind & Fire"), ["Earth, Wind & Fire"]);
+        assert_eq!(split_artists("Simon & Garfunkel"), ["Simon & Garfunkel"]);
+    }
+
+    #[test]
+    fn split_artists_splits_on_featuring() {
+        assert_eq!(
+            split_artists("Tyler, The Creator feat. Kali Uchis"),
+            ["Tyler, The Creator", "Kali Uchis"]
+        );
+        assert_eq!(split_artists("A ft. B featuring C"), ["A", "B", "C"]);
+        assert_eq!(split_artists("A Feat. a"), ["A"]);
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn temp_artwork_file_is_removed_on_drop() {
+        let temp_file = TempArtworkFile::new();
+        let path = temp_file.0.clone();
+        std::fs::write(&path, b"artwork").unwrap();
+        assert!(path.exists());
+
+        drop(temp_file);
+        assert!(!path.exists());
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn artwork_temp_file_is_read_back_and_removed() {
+        let mut written = None;
+        let data = read_artwork_via_temp_file(|path| {
+            std::fs::write(path, b"artwork").unwrap();
+            written = Some(path.to_path_buf());
+            Some("success".to_string())
+        });
+
+        assert_eq!(data.as_deref(), Some(&b"artwork"[..]));
+        assert!(!written.unwrap().exists());
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn artwork_temp_file_is_removed_on_timeout_and_error() {
+        // Timed out: the killed script left a partial file
+        let mut written = None;
+        let data = read_artwork_via_temp_file(|path| {
+            std::fs::write(path, b"partial").unwrap();
+            written = Some(path.to_path_buf());
+            None
+        });
+        assert!(data.is_none());
+        assert!(!written.unwrap().exists());
+
+        // The script reported an error after writing
+        let mut written = None;
+        let data = read_artwork_via_temp_file(|path| {
+            std::fs::write(path, b"partial").unwrap();
+            written = Some(path.to_path_buf());
+            Some("error: disk full".to_string())
+        });
+        assert!(data.is_none());
+        assert!(!written.unwrap().exists());
+
+        // Success without any data written
+        assert!(read_artwork_via_temp_file(|_| Some("success".to_string())).is_none());
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn overlapping_artwork_fetches_use_separate_temp_files() {
+        let barrier = std::sync::Arc::new(std::sync::Barrier::new(2));
+
+        let fetches: Vec<_> = [b"first".to_vec(), b"second".to_vec()]
+            .into_iter()
+            .map(|artwork| {
+                let barrier = barrier.clone();
+                std::thread::spawn(move || {
+                    let mut written = None;
+                    let data = read_artwork_via_temp_file(|path| {
+                        std::fs::write(path, &artwork).unwrap();
+                        written = Some(path.to_path_buf());
+                        // Both fetches have written their file before either reads it back
+                        barrier.wait();
+                        Some("success".to_string())
+                    });
+                    (artwork, data, written.unwrap())
+                })
+            })
+            .collect();
+        let results: Vec<_> = fetches.into_iter().map(|f| f.join().unwrap()).collect();
+
+        assert_ne!(results[0].2, results[1].2);
+        for (artwork, data, path) in results {
+            assert_eq!(data, Some(artwork));
+            assert!(!path.exists());
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn temp_artwork_files_are_unique_and_drop_without_a_file() {
+        let first = TempArtworkFile::new();
+        let second = TempArtworkFile::new();
+        assert_ne!(first.0, second.0);
+
+        // Nothing was ever written; dropping must not panic
+        drop(first);
+        drop(second);
+    }
+}
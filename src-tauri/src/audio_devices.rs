@@ -0,0 +1,447 @@
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+/// How often the output device list is checked while subscribed
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Bumped on every (un)subscribe, a watcher stops once it no longer matches
+static DEVICE_SUBSCRIPTION: AtomicU64 = AtomicU64::new(0);
+
+/// Payload for `audio-device-connected` / `audio-device-disconnected`
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioDevice {
+    /// Stable identifier (CoreAudio UID, endpoint ID or sink name)
+    pub id: String,
+    pub name: String,
+    /// "bluetooth", "headphones", "usb", "hdmi", "airplay", "builtin" or "other"
+    pub kind: String,
+    /// Battery percentage, when the device reports one (e.g. AirPods)
+    pub battery: Option<u8>,
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use super::AudioDevice;
+    use core_foundation::base::TCFType;
+    use core_foundation::string::CFString;
+    use coreaudio_sys::*;
+    use std::mem::size_of;
+
+    fn address(selector: u32, scope: u32) -> AudioObjectPropertyAddress {
+        AudioObjectPropertyAddress {
+            mSelector: selector,
+            mScope: scope,
+            // kAudioObjectPropertyElementMain
+            mElement: 0,
+        }
+    }
+
+    unsafe fn get_u32(object: AudioObjectID, selector: u32, scope: u32) -> Option<u32> {
+        let address = address(selector, scope);
+        let mut value: u32 = 0;
+        let mut size = size_of::<u32>() as u32;
+        let status = AudioObjectGetPropertyData(
+            object,
+            &address,
+            0,
+            std::ptr::null(),
+            &mut size,
+            &mut value as *mut u32 as *mut std::ffi::c_void,
+        );
+        (status == 0).then_some(value)
+    }
+
+    unsafe fn get_string(object: AudioObjectID, selector: u32) -> Option<String> {
+        let address = address(selector, kAudioObjectPropertyScopeGlobal as u32);
+        let mut value: CFStringRef = std::ptr::null();
+        let mut size = size_of::<CFStringRef>() as u32;
+        let status = AudioObjectGetPropertyData(
+            object,
+            &address,
+            0,
+            std::ptr::null(),
+            &mut size,
+            &mut value as *mut CFStringRef as *mut std::ffi::c_void,
+        );
+        if status != 0 || value.is_null() {
+            return None;
+        }
+        Some(CFString::wrap_under_create_rule(value as _).to_string())
+    }
+
+    /// Whether the device can play audio (AirPods show up as separate input and output devices)
+    unsafe fn has_output(object: AudioObjectID) -> bool {
+        let address = address(
+            kAudioDevicePropertyStreams as u32,
+            kAudioObjectPropertyScopeOutput as u32,
+        );
+        let mut size = 0u32;
+        AudioObjectGetPropertyDataSize(object, &address, 0, std::ptr::null(), &mut size) == 0
+            && size > 0
+    }
+
+    pub fn output_devices() -> Vec<AudioDevice> {
+        unsafe {
+            let system = kAudioObjectSystemObject as AudioObjectID;
+            let address = address(
+                kAudioHardwarePropertyDevices as u32,
+                kAudioObjectPropertyScopeGlobal as u32,
+            );
+
+            let mut size = 0u32;
+            if AudioObjectGetPropertyDataSize(system, &address, 0, std::ptr::null(), &mut size) != 0
+            {
+                return Vec::new();
+            }
+            let mut ids = vec![0 as AudioObjectID; size as usize / size_of::<AudioObjectID>()];
+            if AudioObjectGetPropertyData(
+                system,
+                &address,
+                0,
+                std::ptr::null(),
+                &mut size,
+                ids.as_mut_ptr() as *mut std::ffi::c_void,
+            ) != 0
+            {
+                return Vec::new();
+            }
+            ids.truncate(size as usize / size_of::<AudioObjectID>());
+
+            ids.into_iter()
+                .filter(|&id| has_output(id))
+                .filter_map(|id| {
+                    let uid = get_string(id, kAudioDevicePropertyDeviceUID as u32)?;
+                    let name = get_string(id, kAudioObjectPropertyName as u32).unwrap_or_default();
+                    let transport = get_u32(
+                        id,
+                        kAudioDevicePropertyTransportType as u32,
+                        kAudioObjectPropertyScopeGlobal as u32,
+                    )
+                    .unwrap_or(0);
+
+                    let kind = match transport {
+                        t if t == kAudioDeviceTransportTypeBluetooth as u32
+                            || t == kAudioDeviceTransportTypeBluetoothLE as u32 =>
+                        {
+                            "bluetooth"
+                        }
+                        t if t == kAudioDeviceTransportTypeUSB as u32 => "usb",
+                        t if t == kAudioDeviceTransportTypeHDMI as u32
+                            || t == kAudioDeviceTransportTypeDisplayPort as u32 =>
+                        {
+                            "hdmi"
+                        }
+                        t if t == kAudioDeviceTransportTypeAirPlay as u32 => "airplay",
+                        t if t == kAudioDeviceTransportTypeBuiltIn as u32 => "builtin",
+                        // Aggregate and virtual devices (e.g. capture taps) aren't hardware
+                        t if t == kAudioDeviceTransportTypeAggregate as u32
+                            || t == kAudioDeviceTransportTypeVirtual as u32 =>
+                        {
+                            return None;
+                        }
+                        _ => "other",
+                    };
+
+                    // Wired headphones don't add a device, the built-in output switches its
+                    // data source to 'hdpn', so report them as a device of their own
+                    let data_source = get_u32(
+                        id,
+                        kAudioDevicePropertyDataSource as u32,
+                        kAudioObjectPropertyScopeOutput as u32,
+                    );
+                    if kind == "builtin" && data_source == Some(u32::from_be_bytes(*b"hdpn")) {
+                        return Some(AudioDevice {
+                            id: format!("{}#headphones", uid),
+                            name: "Headphones".to_string(),
+                            kind: "headphones".to_string(),
+                            battery: None,
+                        });
+                    }
+
+                    Some(AudioDevice {
+                        id: uid,
+                        name,
+                        kind: kind.to_string(),
+                        battery: None,
+                    })
+                })
+                .collect()
+        }
+    }
+
+    fn parse_percent(value: &serde_json::Value) -> Option<u8> {
+        value.as_str()?.trim().trim_end_matches('%').parse().ok()
+    }
+
+    /// Battery of a connected Bluetooth device from system_profiler. AirPods report each bud,
+    /// the lower of the two is used.
+    pub fn bluetooth_battery(name: &str) -> Option<u8> {
+        let output = std::process::Command::new("system_profiler")
+            .args(["SPBluetoothDataType", "-json"])
+            .output()
+            .ok()?;
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
+
+        json["SPBluetoothDataType"]
+            .as_array()?
+            .iter()
+            .filter_map(|controller| controller["device_connected"].as_array())
+            .flatten()
+            .filter_map(|entry| entry.get(name))
+            .find_map(|device| {
+                parse_percent(&device["device_batteryLevelMain"]).or_else(|| {
+                    let left = parse_percent(&device["device_batteryLevelLeft"]);
+                    let right = parse_percent(&device["device_batteryLevelRight"]);
+                    match (left, right) {
+                        (Some(left), Some(right)) => Some(left.min(right)),
+                        (left, right) => left.or(right),
+                    }
+                })
+            })
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod windows_devices {
+    use super::AudioDevice;
+    use windows::Win32::Devices::FunctionDiscovery::PKEY_Device_FriendlyName;
+    use windows::Win32::Media::Audio::{
+        eRender, DigitalAudioDisplayDevice, Headphones, Headset, IMMDeviceEnumerator,
+        MMDeviceEnumerator, PKEY_AudioEndpoint_FormFactor, Speakers, DEVICE_STATE_ACTIVE,
+    };
+    use windows::Win32::System::Com::StructuredStorage::{
+        PropVariantClear, PropVariantToStringAlloc, PropVariantToUInt32,
+    };
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CoTaskMemFree, CLSCTX_ALL, COINIT_MULTITHREADED,
+        STGM_READ,
+    };
+    use windows::Win32::UI::Shell::PropertiesSystem::{IPropertyStore, PROPERTYKEY};
+
+    /// Take ownership of a COM-allocated string
+    unsafe fn take_string(value: windows::core::PWSTR) -> String {
+        let text = value.to_string().unwrap_or_default();
+        CoTaskMemFree(Some(value.0 as *const std::ffi::c_void));
+        text
+    }
+
+    unsafe fn read_string(store: &IPropertyStore, key: &PROPERTYKEY) -> Option<String> {
+        let mut value = store.GetValue(key).ok()?;
+        let text = PropVariantToStringAlloc(&value)
+            .ok()
+            .map(|s| take_string(s));
+        let _ = PropVariantClear(&mut value);
+        text
+    }
+
+    unsafe fn read_u32(store: &IPropertyStore, key: &PROPERTYKEY) -> Option<u32> {
+        let mut value = store.GetValue(key).ok()?;
+        let number = PropVariantToUInt32(&value).ok();
+        let _ = PropVariantClear(&mut value);
+        number
+    }
+
+    fn kind(form_factor: Option<u32>) -> &'static str {
+        match form_factor.map(|f| f as i32) {
+            Some(f) if f == Headphones.0 || f == Headset.0 => "headphones",
+            Some(f) if f == DigitalAudioDisplayDevice.0 => "hdmi",
+            Some(f) if f == Speakers.0 => "builtin",
+            _ => "other",
+        }
+    }
+
+    pub fn output_devices() -> Vec<AudioDevice> {
+        let list = || -> windows::core::Result<Vec<AudioDevice>> {
+            unsafe {
+                // Fails harmlessly if COM is already initialized on this thread
+                let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+
+                let enumerator: IMMDeviceEnumerator =
+                    CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)?;
+                let collection = enumerator.EnumAudioEndpoints(eRender, DEVICE_STATE_ACTIVE)?;
+
+                let mut devices = Vec::new();
+                for index in 0..collection.GetCount()? {
+                    let device = collection.Item(index)?;
+                    let id = take_string(device.GetId()?);
+                    let store = device.OpenPropertyStore(STGM_READ)?;
+
+                    devices.push(AudioDevice {
+                        id,
+                        name: read_string(&store, &PKEY_Device_FriendlyName).unwrap_or_default(),
+                        kind: kind(read_u32(&store, &PKEY_AudioEndpoint_FormFactor)).to_string(),
+                        battery: None,
+                    });
+                }
+                Ok(devices)
+            }
+        };
+
+        list().unwrap_or_else(|e| {
+            log::debug!("Failed to list audio endpoints: {}", e);
+            Vec::new()
+        })
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::AudioDevice;
+
+    fn run(program: &str, args: &[&str]) -> Option<String> {
+        let output = std::process::Command::new(program)
+            .args(args)
+            .output()
+            .ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// Value of a `key = "value"` line in a pactl property list
+    fn property<'a>(block: &'a str, key: &str) -> Option<&'a str> {
+        block.lines().find_map(|line| {
+            let (name, value) = line.trim().split_once(" = ")?;
+            (name == key).then(|| value.trim_matches('"'))
+        })
+    }
+
+    /// Sinks from pactl (PulseAudio or pipewire-pulse)
+    pub fn output_devices() -> Vec<AudioDevice> {
+        let output = match run("pactl", &["list", "sinks"]) {
+            Some(output) => output,
+            None => return Vec::new(),
+        };
+
+        output
+            .split("Sink #")
+            .skip(1)
+            .filter_map(|block| {
+                let field = |label: &str| {
+                    block
+                        .lines()
+                        .find_map(|line| line.trim().strip_prefix(label))
+                        .map(|value| value.trim().to_string())
+                };
+                let id = field("Name:")?;
+                let name = field("Description:").unwrap_or_else(|| id.clone());
+
+                let kind = match (
+                    property(block, "device.bus"),
+                    property(block, "device.form_factor"),
+                ) {
+                    (Some("bluetooth"), _) => "bluetooth",
+                    (_, Some("headphones" | "headset")) => "headphones",
+                    (Some("usb"), _) => "usb",
+                    _ if id.contains("hdmi") => "hdmi",
+                    (_, Some("internal")) => "builtin",
+                    _ => "other",
+                };
+
+                Some(AudioDevice {
+                    id,
+                    name,
+                    kind: kind.to_string(),
+                    battery: None,
+                })
+            })
+            .collect()
+    }
+
+    /// Battery from BlueZ, the sink name embeds the MAC (bluez_output.AA_BB_CC_DD_EE_FF.1)
+    pub fn bluetooth_battery(sink: &str) -> Option<u8> {
+        let mac = sink.split('.').nth(1)?.replace('_', ":");
+        let info = run("bluetoothctl", &["info", &mac])?;
+
+        // "Battery Percentage: 0x55 (85)"
+        info.lines()
+            .find_map(|line| line.trim().strip_prefix("Battery Percentage:"))
+            .and_then(|value| value.split('(').nth(1))
+            .and_then(|value| value.trim_end_matches(')').trim().parse().ok())
+    }
+}
+
+fn output_devices() -> Vec<AudioDevice> {
+    #[cfg(target_os = "macos")]
+    {
+        macos::output_devices()
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        windows_devices::output_devices()
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        linux::output_devices()
+    }
+}
+
+/// Battery level where the platform exposes it (Bluetooth devices on macOS and Linux)
+fn battery_level(device: &AudioDevice) -> Option<u8> {
+    if device.kind != "bluetooth" {
+        return None;
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        macos::bluetooth_battery(&device.name)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        None
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        linux::bluetooth_battery(&device.id)
+    }
+}
+
+/// Start emitting `audio-device-connected` and `audio-device-disconnected` (an AudioDevice
+/// with id, name, kind and battery when known) as output devices come and go, so the notch
+/// can show e.g. "AirPods connected • 85%". Devices present when subscribing aren't reported.
+#[tauri::command]
+pub fn subscribe_device_events(app_handle: AppHandle) {
+    let subscription = DEVICE_SUBSCRIPTION.fetch_add(1, Ordering::SeqCst) + 1;
+
+    std::thread::spawn(move || {
+        let mut known = output_devices();
+
+        while DEVICE_SUBSCRIPTION.load(Ordering::SeqCst) == subscription {
+            std::thread::sleep(POLL_INTERVAL);
+            if crate::power::is_monitoring_paused() {
+                continue;
+            }
+
+            let current = output_devices();
+            for device in &known {
+                if !current.iter().any(|d| d.id == device.id) {
+                    log::info!("Audio device disconnected: {}", device.name);
+                    let _ = app_handle.emit("audio-device-disconnected", device);
+                }
+            }
+            for device in &current {
+                if !known.iter().any(|d| d.id == device.id) {
+                    log::info!("Audio device connected: {}", device.name);
+                    let device = AudioDevice {
+                        battery: battery_level(device),
+                        ..device.clone()
+                    };
+                    let _ = app_handle.emit("audio-device-connected", device);
+                }
+            }
+            known = current;
+        }
+    });
+}
+
+#[tauri::command]
+pub fn unsubscribe_device_events() {
+    DEVICE_SUBSCRIPTION.fetch_add(1, Ordering::SeqCst);
+}
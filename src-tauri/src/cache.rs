@@ -0,0 +1,114 @@
+use crate::models::{CacheClearReport, ClearedCache};
+use log;
+use std::fs;
+use std::path::Path;
+use tauri::{AppHandle, Manager};
+
+/// Prefix of temp files written while extracting Music.app artwork
+const ARTWORK_TEMP_PREFIX: &str = "overdone_music_art_";
+
+/// Remove a file or directory tree, returning (files removed, bytes freed)
+fn remove_path(path: &Path) -> (usize, u64) {
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(_) => return (0, 0),
+    };
+
+    if metadata.is_dir() {
+        let mut totals = (0, 0);
+        if let Ok(entries) = fs::read_dir(path) {
+            for entry in entries.flatten() {
+                let (files, bytes) = remove_path(&entry.path());
+                totals.0 += files;
+                totals.1 += bytes;
+            }
+        }
+        let _ = fs::remove_dir(path);
+        totals
+    } else if fs::remove_file(path).is_ok() {
+        (1, metadata.len())
+    } else {
+        (0, 0)
+    }
+}
+
+/// Remove everything inside `dir` (but keep the directory itself)
+fn clear_dir_contents(dir: &Path) -> (usize, u64) {
+    let mut totals = (0, 0);
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let (files, bytes) = remove_path(&entry.path());
+            totals.0 += files;
+            totals.1 += bytes;
+        }
+    }
+    totals
+}
+
+/// Remove artwork temp files left behind by a crash mid-extraction
+fn clear_artwork_temp_files() -> (usize, u64) {
+    let mut totals = (0, 0);
+    if let Ok(entries) = fs::read_dir(std::env::temp_dir()) {
+        for entry in entries.flatten() {
+            if entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with(ARTWORK_TEMP_PREFIX)
+            {
+                let (files, bytes) = remove_path(&entry.path());
+                totals.0 += files;
+                totals.1 += bytes;
+            }
+        }
+    }
+    totals
+}
+
+/// Empty every in-memory cache and delete on-disk cache files, for troubleshooting
+/// stale artwork, events or screen geometry
+#[tauri::command]
+pub fn clear_all_caches(app_handle: AppHandle) -> CacheClearReport {
+    let mut report = CacheClearReport::default();
+    let mut add = |name: &str, entries: usize, bytes: u64| {
+        report.caches.push(ClearedCache {
+            name: name.to_string(),
+            entries,
+            bytes,
+        });
+        report.total_bytes += bytes;
+    };
+
+    add("track", crate::audio::clear_track_cache(), 0);
+    add("last_played", crate::audio::clear_last_played(), 0);
+    add("source_icons", crate::audio::clear_source_icon_cache(), 0);
+
+    let (events, reminders) = crate::calendar::clear_calendar_caches();
+    add("events", events, 0);
+    add("reminders", reminders, 0);
+
+    add("waveforms", crate::waveform::clear_waveform_cache(), 0);
+    add(
+        "wallpaper_colors",
+        crate::wallpaper::clear_wallpaper_colors(),
+        0,
+    );
+    add(
+        "screen_info",
+        usize::from(crate::window::invalidate_screen_info()),
+        0,
+    );
+
+    let (files, bytes) = clear_artwork_temp_files();
+    add("artwork_temp", files, bytes);
+
+    if let Ok(cache_dir) = app_handle.path().app_cache_dir() {
+        let (files, bytes) = clear_dir_contents(&cache_dir);
+        add("disk", files, bytes);
+    }
+
+    log::info!(
+        "Cleared all caches ({} bytes freed on disk)",
+        report.total_bytes
+    );
+    report
+}
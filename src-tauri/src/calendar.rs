@@ -1,7 +1,8 @@
+use crate::permissions::PermissionStatus;
 use log;
 use serde::Serialize;
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Clone, PartialEq)]
 pub struct CalendarEvent {
     pub id: String,
     pub title: String,
@@ -10,24 +11,99 @@ pub struct CalendarEvent {
     pub location: Option<String>,
     pub is_all_day: bool,
     pub color: String,
+    /// Identifier of the calendar the event belongs to
+    pub calendar_id: String,
 }
 
+/// An event calendar, as listed for ordering
 #[derive(Serialize, Clone)]
+pub struct CalendarInfo {
+    pub id: String,
+    pub title: String,
+    pub color: String,
+}
+
+#[derive(Serialize, Clone, PartialEq)]
 pub struct Reminder {
     pub id: String,
     pub title: String,
     pub due_date: Option<f64>,
     pub priority: i32,
     pub is_completed: bool,
+    pub list_id: String,
     pub list_name: String,
     pub list_color: String,
+    pub is_flagged: bool,
+    pub has_notes: bool,
+    /// Link attached to the reminder
+    pub url: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ReminderList {
+    pub id: String,
+    pub name: String,
+    pub color: String,
+    pub incomplete_count: usize,
+}
+
+/// Items that changed between two fetches, matched by key `K`
+#[derive(Serialize, Clone)]
+pub struct ItemDiff<T, K = String> {
+    pub added: Vec<T>,
+    /// New versions of items whose fields changed
+    pub updated: Vec<T>,
+    /// Keys that are no longer present
+    pub removed: Vec<K>,
+}
+
+impl<T, K> ItemDiff<T, K> {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Identifies one occurrence of an event; occurrences of a recurring event share the id
+#[derive(Serialize, Clone, PartialEq)]
+pub struct EventOccurrence {
+    pub id: String,
+    pub start_date: f64,
+}
+
+impl From<&CalendarEvent> for EventOccurrence {
+    fn from(event: &CalendarEvent) -> Self {
+        EventOccurrence {
+            id: event.id.clone(),
+            start_date: event.start_date,
+        }
+    }
+}
+
+/// Payload of `calendar-diff`; a side is None when it had never been fetched, so there was
+/// nothing to compare against
+#[derive(Serialize, Clone)]
+pub struct CalendarDiff {
+    pub events: Option<ItemDiff<CalendarEvent, EventOccurrence>>,
+    pub reminders: Option<ItemDiff<Reminder>>,
+}
+
+/// Incomplete reminders bucketed by due date relative to today (local time)
+#[derive(Serialize, Clone, Default)]
+pub struct RemindersGrouped {
+    pub overdue: Vec<Reminder>,
+    pub today: Vec<Reminder>,
+    pub tomorrow: Vec<Reminder>,
+    /// Due within the next 7 days, after tomorrow
+    pub this_week: Vec<Reminder>,
+    pub later: Vec<Reminder>,
+    pub no_date: Vec<Reminder>,
 }
 
 #[cfg(target_os = "macos")]
 mod macos {
     use super::*;
     use objc2::rc::Retained;
-    use objc2_event_kit::{EKAuthorizationStatus, EKEntityType, EKEventStore};
+    use objc2_event_kit::{EKAuthorizationStatus, EKEntityType, EKEvent, EKEventStore};
     use objc2_foundation::{MainThreadMarker, NSCalendar, NSCalendarUnit, NSDate};
     use std::sync::atomic::{AtomicBool, Ordering};
     use std::sync::OnceLock;
@@ -67,6 +143,11 @@ mod macos {
                 .map(|d| d < duration)
                 .unwrap_or(false)
         }
+
+        /// Force the next read to refetch
+        fn invalidate(&mut self) {
+            self.last_fetched = SystemTime::UNIX_EPOCH;
+        }
     }
 
     // Static caches
@@ -88,6 +169,176 @@ mod macos {
         EVENT_STORE.get()
     }
 
+    /// Bumped on every EKEventStoreChanged so bursts collapse into one `calendar-changed` event
+    static CHANGE_GENERATION: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+    /// Quiet period before `calendar-changed` is emitted after the last store change
+    const CHANGE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+    /// Range of the cached events, so a change refetches the same window
+    static CACHED_EVENT_DAYS: std::sync::atomic::AtomicI64 = std::sync::atomic::AtomicI64::new(7);
+
+    /// Cached data of a cache that has been filled at least once
+    fn cached_snapshot<T: Clone>(cache: &OnceLock<Mutex<Cache<Vec<T>>>>) -> Option<Vec<T>> {
+        cache
+            .get()
+            .and_then(|m| m.lock().ok())
+            .map(|cache| cache.data.clone())
+    }
+
+    /// Cached events and reminders, None for a cache that was never filled
+    type CacheSnapshot = (Option<Vec<CalendarEvent>>, Option<Vec<Reminder>>);
+
+    /// Cache contents from the first change notification of a burst, until the burst is diffed
+    static PENDING_SNAPSHOT: Mutex<Option<CacheSnapshot>> = Mutex::new(None);
+
+    fn diff_by_key<T: Clone + PartialEq, K: PartialEq>(
+        old: &[T],
+        new: &[T],
+        key: impl Fn(&T) -> K,
+    ) -> ItemDiff<T, K> {
+        let mut added = Vec::new();
+        let mut updated = Vec::new();
+        for item in new {
+            match old.iter().find(|o| key(o) == key(item)) {
+                None => added.push(item.clone()),
+                Some(previous) if previous != item => updated.push(item.clone()),
+                Some(_) => {}
+            }
+        }
+        let removed = old
+            .iter()
+            .filter(|o| !new.iter().any(|n| key(n) == key(o)))
+            .map(&key)
+            .collect();
+
+        ItemDiff {
+            added,
+            updated,
+            removed,
+        }
+    }
+
+    /// Refetch whatever was cached before the change and emit `calendar-diff` against it
+    async fn emit_calendar_diff(
+        handle: tauri::AppHandle,
+        old_events: Option<Vec<CalendarEvent>>,
+        old_reminders: Option<Vec<Reminder>>,
+    ) {
+        use tauri::Emitter;
+
+        let events = old_events.map(|old| {
+            let new = get_events(CACHED_EVENT_DAYS.load(Ordering::Relaxed), true);
+            diff_by_key(&old, &new, |e: &CalendarEvent| EventOccurrence::from(e))
+        });
+        let reminders = match old_reminders {
+            Some(old) => {
+                let new = get_reminders(true).await;
+                Some(diff_by_key(&old, &new, |r: &Reminder| r.id.clone()))
+            }
+            None => None,
+        };
+
+        let events_changed = events.as_ref().is_some_and(|diff| !diff.is_empty());
+        let reminders_changed = reminders.as_ref().is_some_and(|diff| !diff.is_empty());
+        if !events_changed && !reminders_changed {
+            return;
+        }
+
+        let _ = handle.emit("calendar-diff", CalendarDiff { events, reminders });
+    }
+
+    fn invalidate_caches() {
+        if let Some(cache_mutex) = EVENTS_CACHE.get() {
+            if let Ok(mut cache) = cache_mutex.lock() {
+                cache.invalidate();
+            }
+        }
+        if let Some(cache_mutex) = REMINDERS_CACHE.get() {
+            if let Ok(mut cache) = cache_mutex.lock() {
+                cache.invalidate();
+            }
+        }
+    }
+
+    /// Empty both caches, returns the number of (events, reminders) dropped
+    pub fn clear_caches() -> (usize, usize) {
+        fn drain<T>(cache: &OnceLock<Mutex<Cache<Vec<T>>>>) -> usize {
+            cache
+                .get()
+                .and_then(|m| m.lock().ok())
+                .map(|mut cache| {
+                    let count = cache.data.len();
+                    cache.data.clear();
+                    cache.invalidate();
+                    count
+                })
+                .unwrap_or(0)
+        }
+
+        (drain(&EVENTS_CACHE), drain(&REMINDERS_CACHE))
+    }
+
+    /// Invalidate the caches and notify the frontend when events/reminders change externally
+    pub fn setup_change_monitoring(app_handle: &tauri::AppHandle) {
+        use objc2::runtime::AnyObject;
+        use objc2::*;
+        use tauri::Emitter;
+
+        // EventKit only posts changes for stores that exist
+        if get_store().is_none() {
+            return;
+        }
+
+        unsafe {
+            let center: *mut AnyObject = msg_send![class!(NSNotificationCenter), defaultCenter];
+            crate::utils::add_notification_observer(
+                center,
+                "EKEventStoreChangedNotification",
+                app_handle,
+                |handle| {
+                    // Later notifications in the burst may race with refetches that refill
+                    // the caches, so only the first one records what to diff against
+                    if let Ok(mut pending) = PENDING_SNAPSHOT.lock() {
+                        if pending.is_none() {
+                            *pending = Some((
+                                cached_snapshot(&EVENTS_CACHE),
+                                cached_snapshot(&REMINDERS_CACHE),
+                            ));
+                        }
+                    }
+                    invalidate_caches();
+
+                    // A single edit in Calendar.app often posts several notifications
+                    let generation = CHANGE_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+                    let handle = handle.clone();
+                    std::thread::spawn(move || {
+                        std::thread::sleep(CHANGE_DEBOUNCE);
+                        if CHANGE_GENERATION.load(Ordering::SeqCst) == generation {
+                            log::debug!("Event store changed, notifying frontend");
+                            let (old_events, old_reminders) = PENDING_SNAPSHOT
+                                .lock()
+                                .ok()
+                                .and_then(|mut pending| pending.take())
+                                .unwrap_or((None, None));
+                            let _ = handle.emit("calendar-changed", ());
+                            tauri::async_runtime::spawn(emit_calendar_diff(
+                                handle.clone(),
+                                old_events,
+                                old_reminders,
+                            ));
+                            // Drop the pin if its event was deleted or moved into the past
+                            super::resolve_pinned_event(&handle);
+                            tauri::async_runtime::spawn(super::reschedule_reminder_notifications(
+                                handle.clone(),
+                            ));
+                        }
+                    });
+                },
+            );
+        }
+    }
+
     pub async fn request_access() -> Result<bool, String> {
         // If already requested, return immediately
         if ACCESS_ALREADY_REQUESTED.load(Ordering::SeqCst) {
@@ -111,6 +362,34 @@ mod macos {
         result
     }
 
+    fn map_authorization_status(status: EKAuthorizationStatus) -> PermissionStatus {
+        if status == EKAuthorizationStatus::FullAccess || status == EKAuthorizationStatus::WriteOnly
+        {
+            PermissionStatus::Granted
+        } else if status == EKAuthorizationStatus::Denied {
+            PermissionStatus::Denied
+        } else if status == EKAuthorizationStatus::Restricted {
+            PermissionStatus::Restricted
+        } else if status == EKAuthorizationStatus::NotDetermined {
+            PermissionStatus::NotDetermined
+        } else {
+            PermissionStatus::Unknown
+        }
+    }
+
+    /// Current (calendar, reminders) authorization status without prompting
+    pub fn permission_status() -> (PermissionStatus, PermissionStatus) {
+        let status_events =
+            unsafe { EKEventStore::authorizationStatusForEntityType(EKEntityType::Event) };
+        let status_reminders =
+            unsafe { EKEventStore::authorizationStatusForEntityType(EKEntityType::Reminder) };
+
+        (
+            map_authorization_status(status_events),
+            map_authorization_status(status_reminders),
+        )
+    }
+
     async fn request_access_inner() -> Result<bool, String> {
         let store = get_store().ok_or("Failed to initialize EventStore")?;
 
@@ -201,6 +480,67 @@ mod macos {
         Ok(true)
     }
 
+    /// EventKit has no public flagged property. Use the private `isFlagged` when the
+    /// runtime responds to it, otherwise treat high priority (1-4) as flagged.
+    fn reminder_is_flagged(reminder: &objc2_event_kit::EKReminder, priority: i32) -> bool {
+        use objc2::runtime::Bool;
+        use objc2::{msg_send, sel};
+
+        let responds: Bool = unsafe { msg_send![reminder, respondsToSelector: sel!(isFlagged)] };
+        if responds.as_bool() {
+            let flagged: Bool = unsafe { msg_send![reminder, isFlagged] };
+            return flagged.as_bool();
+        }
+
+        (1..=4).contains(&priority)
+    }
+
+    /// Extract a calendar's color as a hex string using the Core Graphics C API
+    fn calendar_color_hex(cal: &objc2_event_kit::EKCalendar) -> String {
+        unsafe {
+            use objc2::msg_send;
+            use std::ffi::c_void;
+
+            // CGColorRef is a C type, not an Objective-C object
+            type CGColorRef = *const c_void;
+
+            // External C functions from Core Graphics
+            extern "C" {
+                fn CGColorGetNumberOfComponents(color: CGColorRef) -> usize;
+                fn CGColorGetComponents(color: CGColorRef) -> *const f64;
+            }
+
+            // Get CGColor from calendar (this returns a CGColorRef)
+            let cg_color: CGColorRef = msg_send![cal, CGColor];
+
+            if !cg_color.is_null() {
+                // Use Core Graphics C functions
+                let num_components = CGColorGetNumberOfComponents(cg_color);
+
+                if num_components >= 3 {
+                    let components_ptr = CGColorGetComponents(cg_color);
+
+                    if !components_ptr.is_null() {
+                        let components = std::slice::from_raw_parts(components_ptr, num_components);
+
+                        // Convert RGB components (0.0-1.0) to hex
+                        let r = (components[0] * 255.0) as u8;
+                        let g = (components[1] * 255.0) as u8;
+                        let b = (components[2] * 255.0) as u8;
+
+                        format!("#{:02x}{:02x}{:02x}", r, g, b)
+                    } else {
+                        "#0a84ff".to_string() // Default blue
+                    }
+                } else {
+                    "#0a84ff".to_string()
+                }
+            } else {
+                "#0a84ff".to_string()
+            }
+        }
+    }
+
     pub fn get_events(days_ahead: i64, force_refresh: bool) -> Vec<CalendarEvent> {
         // Check cache first
         if !force_refresh {
@@ -216,6 +556,101 @@ mod macos {
 
         log::debug!("Fetching fresh calendar events...");
 
+        let events_list = fetch_events(days_ahead);
+        CACHED_EVENT_DAYS.store(days_ahead, Ordering::Relaxed);
+
+        // Update cache
+        let cache_mutex = EVENTS_CACHE.get_or_init(|| Mutex::new(Cache::new(Vec::new())));
+        if let Ok(mut cache) = cache_mutex.lock() {
+            *cache = Cache::new(events_list.clone());
+        }
+
+        events_list
+    }
+
+    fn to_calendar_event(event: &EKEvent) -> CalendarEvent {
+        // title() returns Retained<NSString> or Option<Retained<NSString>>
+        // We'll handle both cases
+        let title: String = {
+            let title_ns = unsafe { event.title() };
+            title_ns.to_string()
+        };
+
+        // startDate() returns Retained<NSDate>
+        let start_ts: f64 = {
+            let date = unsafe { event.startDate() };
+            date.timeIntervalSince1970()
+        };
+
+        // endDate() returns Retained<NSDate>
+        let end_ts: f64 = {
+            let date = unsafe { event.endDate() };
+            date.timeIntervalSince1970()
+        };
+
+        // location() returns Option<Retained<NSString>>
+        let location: Option<String> = {
+            let loc = unsafe { event.location() };
+            loc.map(|s| s.to_string())
+        };
+
+        // eventIdentifier() returns Option<Retained<NSString>>
+        let id: String = {
+            unsafe { event.eventIdentifier() }
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| "unknown".to_string())
+        };
+
+        let is_all_day = unsafe { event.isAllDay() };
+
+        // Use default color for now
+        let color = "#34c759".to_string();
+
+        let calendar_id = unsafe { event.calendar() }
+            .map(|cal| unsafe { cal.calendarIdentifier() }.to_string())
+            .unwrap_or_default();
+
+        CalendarEvent {
+            id,
+            title,
+            start_date: start_ts,
+            end_date: end_ts,
+            location,
+            is_all_day,
+            color,
+            calendar_id,
+        }
+    }
+
+    /// Event calendars in EventKit's order
+    pub fn list_calendars() -> Vec<CalendarInfo> {
+        let store = match get_store() {
+            Some(s) => &s.0,
+            None => return Vec::new(),
+        };
+
+        let calendars = unsafe { store.calendarsForEntityType(EKEntityType::Event) };
+
+        calendars
+            .iter()
+            .map(|cal| CalendarInfo {
+                id: unsafe { cal.calendarIdentifier() }.to_string(),
+                title: unsafe { cal.title() }.to_string(),
+                color: calendar_color_hex(&cal),
+            })
+            .collect()
+    }
+
+    /// Look up a single event by id, including events outside the cached range
+    pub fn get_event(id: &str) -> Option<CalendarEvent> {
+        let store = &get_store()?.0;
+        let identifier = objc2_foundation::NSString::from_str(id);
+        let event = unsafe { store.eventWithIdentifier(&identifier) }?;
+        Some(to_calendar_event(&event))
+    }
+
+    /// Fetch events from EventKit without touching the cache
+    pub fn fetch_events(days_ahead: i64) -> Vec<CalendarEvent> {
         let mut events_list = Vec::new();
         let store = match get_store() {
             Some(s) => &s.0,
@@ -234,66 +669,49 @@ mod macos {
 
         // Convert each EKEvent to our CalendarEvent struct
         for event in events.iter() {
-            // title() returns Retained<NSString> or Option<Retained<NSString>>
-            // We'll handle both cases
-            let title: String = {
-                let title_ns = unsafe { event.title() };
-                title_ns.to_string()
-            };
-
-            // startDate() returns Retained<NSDate>
-            let start_ts: f64 = {
-                let date = unsafe { event.startDate() };
-                date.timeIntervalSince1970()
-            };
-
-            // endDate() returns Retained<NSDate>
-            let end_ts: f64 = {
-                let date = unsafe { event.endDate() };
-                date.timeIntervalSince1970()
-            };
-
-            // location() returns Option<Retained<NSString>>
-            let location: Option<String> = {
-                let loc = unsafe { event.location() };
-                loc.map(|s| s.to_string())
-            };
-
-            // eventIdentifier() returns Option<Retained<NSString>>
-            let id: String = {
-                unsafe { event.eventIdentifier() }
-                    .map(|s| s.to_string())
-                    .unwrap_or_else(|| "unknown".to_string())
-            };
-
-            let is_all_day = unsafe { event.isAllDay() };
-
-            // Use default color for now
-            let color = "#34c759".to_string();
-
-            events_list.push(CalendarEvent {
-                id,
-                title,
-                start_date: start_ts,
-                end_date: end_ts,
-                location,
-                is_all_day,
-                color,
-            });
+            events_list.push(to_calendar_event(&event));
         }
 
         // Sort by start date
         events_list.sort_by(|a, b| a.start_date.partial_cmp(&b.start_date).unwrap());
 
-        // Update cache
-        let cache_mutex = EVENTS_CACHE.get_or_init(|| Mutex::new(Cache::new(Vec::new())));
-        if let Ok(mut cache) = cache_mutex.lock() {
-            *cache = Cache::new(events_list.clone());
-        }
-
         events_list
     }
 
+    /// Local calendar date (year, month, day) of a timestamp
+    pub fn local_date(timestamp: f64) -> (i64, u32, u32) {
+        let ns_date = NSDate::dateWithTimeIntervalSince1970(timestamp);
+        let calendar = NSCalendar::currentCalendar();
+        let unit_flags = NSCalendarUnit::Year | NSCalendarUnit::Month | NSCalendarUnit::Day;
+        let components = calendar.components_fromDate(unit_flags, &ns_date);
+
+        (
+            components.year() as i64,
+            components.month() as u32,
+            components.day() as u32,
+        )
+    }
+
+    /// Local (year, month, day, hour, minute) of a timestamp
+    pub fn local_datetime(timestamp: f64) -> (i64, u32, u32, u32, u32) {
+        let ns_date = NSDate::dateWithTimeIntervalSince1970(timestamp);
+        let calendar = NSCalendar::currentCalendar();
+        let unit_flags = NSCalendarUnit::Year
+            | NSCalendarUnit::Month
+            | NSCalendarUnit::Day
+            | NSCalendarUnit::Hour
+            | NSCalendarUnit::Minute;
+        let components = calendar.components_fromDate(unit_flags, &ns_date);
+
+        (
+            components.year() as i64,
+            components.month() as u32,
+            components.day() as u32,
+            components.hour() as u32,
+            components.minute() as u32,
+        )
+    }
+
     pub async fn get_reminders(force_refresh: bool) -> Vec<Reminder> {
         // Check cache first
         if !force_refresh {
@@ -367,78 +785,49 @@ mod macos {
                             };
 
                             // Get calendar info
-                            let (list_name, list_color) = {
+                            let (list_id, list_name, list_color) = {
                                 match unsafe { reminder.calendar() } {
                                     Some(cal) => {
+                                        let id = unsafe { cal.calendarIdentifier() }.to_string();
                                         let name = unsafe { cal.title() }.to_string();
 
-                                        // Extract color from calendar using Core Graphics C API
-                                        let color = unsafe {
-                                            use objc2::msg_send;
-                                            use std::ffi::c_void;
-
-                                            // CGColorRef is a C type, not an Objective-C object
-                                            type CGColorRef = *const c_void;
-
-                                            // External C functions from Core Graphics
-                                            extern "C" {
-                                                fn CGColorGetNumberOfComponents(
-                                                    color: CGColorRef,
-                                                ) -> usize;
-                                                fn CGColorGetComponents(
-                                                    color: CGColorRef,
-                                                ) -> *const f64;
-                                            }
-
-                                            // Get CGColor from calendar (this returns a CGColorRef)
-                                            let cg_color: CGColorRef = msg_send![&cal, CGColor];
-
-                                            if !cg_color.is_null() {
-                                                // Use Core Graphics C functions
-                                                let num_components =
-                                                    CGColorGetNumberOfComponents(cg_color);
-
-                                                if num_components >= 3 {
-                                                    let components_ptr =
-                                                        CGColorGetComponents(cg_color);
-
-                                                    if !components_ptr.is_null() {
-                                                        let components = std::slice::from_raw_parts(
-                                                            components_ptr,
-                                                            num_components,
-                                                        );
-
-                                                        // Convert RGB components (0.0-1.0) to hex
-                                                        let r = (components[0] * 255.0) as u8;
-                                                        let g = (components[1] * 255.0) as u8;
-                                                        let b = (components[2] * 255.0) as u8;
-
-                                                        format!("#{:02x}{:02x}{:02x}", r, g, b)
-                                                    } else {
-                                                        "#0a84ff".to_string() // Default blue
-                                                    }
-                                                } else {
-                                                    "#0a84ff".to_string()
-                                                }
-                                            } else {
-                                                "#0a84ff".to_string()
-                                            }
-                                        };
-
-                                        (name, color)
+                                        let color = calendar_color_hex(&cal);
+
+                                        (id, name, color)
                                     }
-                                    None => ("Unknown".to_string(), "#0a84ff".to_string()),
+                                    None => (
+                                        String::new(),
+                                        "Unknown".to_string(),
+                                        "#0a84ff".to_string(),
+                                    ),
                                 }
                             };
 
+                            let has_notes = unsafe { reminder.notes() }
+                                .map(|notes| !notes.to_string().trim().is_empty())
+                                .unwrap_or(false);
+
+                            let url: Option<String> = unsafe {
+                                reminder
+                                    .URL()
+                                    .and_then(|url| url.absoluteString())
+                                    .map(|url| url.to_string())
+                            };
+
+                            let is_flagged = reminder_is_flagged(&reminder, priority);
+
                             results.push(Reminder {
                                 id,
                                 title,
                                 due_date,
                                 priority,
                                 is_completed,
+                                list_id,
                                 list_name,
                                 list_color,
+                                is_flagged,
+                                has_notes,
+                                url,
                             });
                         }
                     }
@@ -476,6 +865,33 @@ mod macos {
         }
     }
 
+    pub async fn get_reminder_lists() -> Vec<ReminderList> {
+        // Counts come from the (possibly cached) incomplete reminders
+        let reminders = get_reminders(false).await;
+
+        let store = match get_store() {
+            Some(s) => &s.0,
+            None => return Vec::new(),
+        };
+
+        let calendars = unsafe { store.calendarsForEntityType(EKEntityType::Reminder) };
+
+        calendars
+            .iter()
+            .map(|cal| {
+                let id = unsafe { cal.calendarIdentifier() }.to_string();
+                let incomplete_count = reminders.iter().filter(|r| r.list_id == id).count();
+
+                ReminderList {
+                    name: unsafe { cal.title() }.to_string(),
+                    color: calendar_color_hex(&cal),
+                    id,
+                    incomplete_count,
+                }
+            })
+            .collect()
+    }
+
     pub async fn complete_reminder(id: String) -> Result<bool, String> {
         let store = match get_store() {
             Some(s) => &s.0,
@@ -633,6 +1049,23 @@ mod macos {
     }
 }
 
+/// Watch for calendar/reminder edits made outside the app (macOS)
+#[allow(unused_variables)]
+pub fn setup_calendar_change_monitoring(app_handle: &tauri::AppHandle) {
+    #[cfg(target_os = "macos")]
+    macos::setup_change_monitoring(app_handle);
+}
+
+/// Empty the events and reminders caches, returns the number of (events, reminders) dropped
+pub fn clear_calendar_caches() -> (usize, usize) {
+    #[cfg(target_os = "macos")]
+    {
+        macos::clear_caches()
+    }
+    #[cfg(not(target_os = "macos"))]
+    (0, 0)
+}
+
 // Public commands
 
 #[tauri::command]
@@ -645,23 +1078,150 @@ pub async fn request_calendar_access() -> Result<bool, String> {
     Ok(true)
 }
 
+/// Get the (calendar, reminders) permission status without prompting
+pub fn get_calendar_permission_status() -> (PermissionStatus, PermissionStatus) {
+    #[cfg(target_os = "macos")]
+    {
+        macos::permission_status()
+    }
+    #[cfg(not(target_os = "macos"))]
+    (PermissionStatus::Granted, PermissionStatus::Granted)
+}
+
 #[tauri::command]
 pub async fn get_upcoming_events(
     force_refresh: Option<bool>,
 ) -> Result<Vec<CalendarEvent>, String> {
     #[cfg(target_os = "macos")]
     {
-        Ok(macos::get_events(7, force_refresh.unwrap_or(false)))
+        let mut events = macos::get_events(7, force_refresh.unwrap_or(false));
+        // Events starting together are listed in the user's calendar order
+        let order = calendar_order();
+        events.sort_by(|a, b| {
+            a.start_date.total_cmp(&b.start_date).then(
+                calendar_rank(&order, &a.calendar_id).cmp(&calendar_rank(&order, &b.calendar_id)),
+            )
+        });
+        Ok(events)
+    }
+    #[cfg(not(target_os = "macos"))]
+    Ok(vec![])
+}
+
+/// Calendar ids in the user's preferred order (persisted)
+static CALENDAR_ORDER: std::sync::RwLock<Vec<String>> = std::sync::RwLock::new(Vec::new());
+
+#[cfg(target_os = "macos")]
+fn calendar_order() -> Vec<String> {
+    CALENDAR_ORDER
+        .read()
+        .map(|order| order.clone())
+        .unwrap_or_default()
+}
+
+/// Position of a calendar in the preferred order, calendars not in it sort last
+#[cfg(target_os = "macos")]
+fn calendar_rank(order: &[String], id: &str) -> usize {
+    order
+        .iter()
+        .position(|ordered| ordered == id)
+        .unwrap_or(usize::MAX)
+}
+
+/// Load the persisted calendar order
+pub fn initialize_calendar_order(app_handle: &tauri::AppHandle) {
+    let order: Option<Vec<String>> = crate::database::get_connection(app_handle)
+        .ok()
+        .and_then(|conn| {
+            let sql = "SELECT value FROM settings WHERE key = 'calendar_order'";
+            crate::database::log_sql(sql);
+            conn.query_row(sql, [], |row| row.get::<_, String>(0)).ok()
+        })
+        .and_then(|json| serde_json::from_str(&json).ok());
+
+    if let (Some(order), Ok(mut current)) = (order, CALENDAR_ORDER.write()) {
+        *current = order;
+    }
+}
+
+/// List event calendars in the user's order. Calendars added since the order was
+/// saved come last, in EventKit's order.
+#[tauri::command]
+pub async fn list_calendars() -> Result<Vec<CalendarInfo>, String> {
+    #[cfg(target_os = "macos")]
+    {
+        let order = calendar_order();
+        let mut calendars = macos::list_calendars();
+        // Stable, so unordered calendars keep their relative order
+        calendars.sort_by_key(|cal| calendar_rank(&order, &cal.id));
+        Ok(calendars)
     }
     #[cfg(not(target_os = "macos"))]
     Ok(vec![])
 }
 
+/// Save the preferred calendar order. Every id must be a known event calendar.
 #[tauri::command]
-pub async fn get_reminders(force_refresh: Option<bool>) -> Result<Vec<Reminder>, String> {
+pub async fn set_calendar_order(
+    app_handle: tauri::AppHandle,
+    ids: Vec<String>,
+) -> Result<(), String> {
     #[cfg(target_os = "macos")]
     {
-        Ok(macos::get_reminders(force_refresh.unwrap_or(false)).await)
+        let known = macos::list_calendars();
+        if let Some(unknown) = ids
+            .iter()
+            .find(|id| !known.iter().any(|cal| &cal.id == *id))
+        {
+            return Err(format!("Unknown calendar: {}", unknown));
+        }
+    }
+
+    let mut order: Vec<String> = Vec::with_capacity(ids.len());
+    for id in ids {
+        if !order.contains(&id) {
+            order.push(id);
+        }
+    }
+
+    let json = serde_json::to_string(&order).map_err(|e| e.to_string())?;
+    let conn = crate::database::get_connection(&app_handle).map_err(|e| e.to_string())?;
+    let sql = "INSERT OR REPLACE INTO settings (key, value) VALUES ('calendar_order', ?1)";
+    crate::database::log_sql(sql);
+    conn.execute(sql, [json]).map_err(|e| e.to_string())?;
+
+    if let Ok(mut current) = CALENDAR_ORDER.write() {
+        *current = order;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_reminders(
+    force_refresh: Option<bool>,
+    list_id: Option<String>,
+) -> Result<Vec<Reminder>, String> {
+    #[cfg(target_os = "macos")]
+    {
+        let reminders = macos::get_reminders(force_refresh.unwrap_or(false)).await;
+        Ok(match list_id {
+            Some(list_id) => reminders
+                .into_iter()
+                .filter(|r| r.list_id == list_id)
+                .collect(),
+            None => reminders,
+        })
+    }
+    #[cfg(not(target_os = "macos"))]
+    Ok(vec![])
+}
+
+/// Get all reminder lists with their incomplete reminder counts
+#[tauri::command]
+pub async fn get_reminder_lists() -> Result<Vec<ReminderList>, String> {
+    #[cfg(target_os = "macos")]
+    {
+        Ok(macos::get_reminder_lists().await)
     }
     #[cfg(not(target_os = "macos"))]
     Ok(vec![])
@@ -678,10 +1238,25 @@ pub async fn complete_reminder(id: String) -> Result<bool, String> {
 }
 
 #[tauri::command]
-pub async fn create_reminder(title: String, due_date: Option<f64>) -> Result<bool, String> {
+#[allow(unused_variables)]
+pub async fn create_reminder(
+    app_handle: tauri::AppHandle,
+    title: String,
+    due_date: Option<f64>,
+) -> Result<bool, String> {
     #[cfg(target_os = "macos")]
     {
-        macos::create_reminder(title, due_date).await
+        let message = format!("Reminder added: {}", title);
+        let created = macos::create_reminder(title, due_date).await?;
+        if created {
+            crate::toast::show(
+                &app_handle,
+                &message,
+                crate::toast::DEFAULT_DURATION_MS,
+                crate::toast::ToastLevel::Success,
+            );
+        }
+        Ok(created)
     }
     #[cfg(not(target_os = "macos"))]
     Ok(true)
@@ -793,6 +1368,638 @@ pub async fn create_calendar_event(
     Ok(true)
 }
 
+/// Minutes before its start at which an event counts as starting soon (persisted)
+static STARTING_SOON_MINUTES: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(10);
+
+/// Where an event stands relative to now
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum EventStatus {
+    Upcoming,
+    StartingSoon,
+    InProgress,
+    Ended,
+}
+
+/// Status of an event at `now` (Unix seconds), using the starting-soon threshold
+pub fn event_status(event: &CalendarEvent, now: f64) -> EventStatus {
+    let threshold = STARTING_SOON_MINUTES.load(std::sync::atomic::Ordering::Relaxed) as f64 * 60.0;
+
+    if now >= event.end_date {
+        EventStatus::Ended
+    } else if now >= event.start_date {
+        EventStatus::InProgress
+    } else if event.start_date - now <= threshold {
+        EventStatus::StartingSoon
+    } else {
+        EventStatus::Upcoming
+    }
+}
+
+/// Load the persisted starting-soon threshold (defaults to 10 minutes)
+pub fn initialize_event_status_thresholds(app_handle: &tauri::AppHandle) {
+    let minutes = crate::database::get_connection(app_handle)
+        .ok()
+        .and_then(|conn| {
+            let sql = "SELECT value FROM settings WHERE key = 'starting_soon_minutes'";
+            crate::database::log_sql(sql);
+            conn.query_row(sql, [], |row| row.get::<_, String>(0)).ok()
+        })
+        .and_then(|value| value.parse::<u32>().ok());
+
+    if let Some(minutes) = minutes {
+        STARTING_SOON_MINUTES.store(minutes, std::sync::atomic::Ordering::Relaxed);
+    }
+}
+
+/// Set how many minutes before its start an event is reported as "starting_soon"
+#[tauri::command]
+pub fn set_starting_soon_minutes(app_handle: tauri::AppHandle, minutes: u32) -> Result<(), String> {
+    STARTING_SOON_MINUTES.store(minutes, std::sync::atomic::Ordering::Relaxed);
+
+    let conn = crate::database::get_connection(&app_handle).map_err(|e| e.to_string())?;
+    let sql = "INSERT OR REPLACE INTO settings (key, value) VALUES ('starting_soon_minutes', ?1)";
+    crate::database::log_sql(sql);
+    conn.execute(sql, rusqlite::params![minutes.to_string()])
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_starting_soon_minutes() -> u32 {
+    STARTING_SOON_MINUTES.load(std::sync::atomic::Ordering::Relaxed)
+}
+
+/// Settings key holding the id of the event pinned to the collapsed notch
+const PINNED_EVENT_KEY: &str = "pinned_event_id";
+
+/// The pinned event with a countdown that is recomputed on every read
+#[derive(Serialize, Clone)]
+pub struct PinnedEvent {
+    #[serde(flatten)]
+    pub event: CalendarEvent,
+    /// Whole minutes until the event starts, negative once it has started
+    pub minutes_until_start: i64,
+    /// `minutes_until_start` phrased by `format_time_until`, e.g. "in 5 min"
+    pub starts_in: String,
+    pub status: EventStatus,
+}
+
+#[cfg(target_os = "macos")]
+fn pinned_event_id(app_handle: &tauri::AppHandle) -> Option<String> {
+    let conn = crate::database::get_connection(app_handle).ok()?;
+    let sql = "SELECT value FROM settings WHERE key = ?1";
+    crate::database::log_sql(sql);
+    conn.query_row(sql, [PINNED_EVENT_KEY], |row| row.get::<_, String>(0))
+        .ok()
+}
+
+fn clear_pinned_event(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    let conn = crate::database::get_connection(app_handle).map_err(|e| e.to_string())?;
+    let sql = "DELETE FROM settings WHERE key = ?1";
+    crate::database::log_sql(sql);
+    conn.execute(sql, [PINNED_EVENT_KEY])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Resolve the pinned event, clearing the pin (and emitting `pinned-event-cleared` with
+/// its id) once the event has ended or no longer exists
+#[cfg(target_os = "macos")]
+fn resolve_pinned_event(app_handle: &tauri::AppHandle) -> Option<PinnedEvent> {
+    let id = pinned_event_id(app_handle)?;
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0);
+
+    let event = macos::get_events(7, false)
+        .into_iter()
+        // Occurrences of a recurring event share an id, use the next one
+        .find(|event| event.id == id && event.end_date > now)
+        .or_else(|| macos::get_event(&id));
+
+    match event {
+        Some(event) if event.end_date > now => Some(PinnedEvent {
+            minutes_until_start: ((event.start_date - now) / 60.0).ceil() as i64,
+            starts_in: format_relative(event.start_date, now, local_datetime),
+            status: event_status(&event, now),
+            event,
+        }),
+        _ => {
+            use tauri::Emitter;
+
+            log::info!("Pinned event {} has passed or was deleted, unpinning", id);
+            if let Err(e) = clear_pinned_event(app_handle) {
+                log::warn!("Failed to clear pinned event: {}", e);
+            }
+            let _ = app_handle.emit("pinned-event-cleared", id);
+            None
+        }
+    }
+}
+
+/// Pin an event to the collapsed notch, replacing any previously pinned event
+#[tauri::command]
+pub fn pin_event(app_handle: tauri::AppHandle, id: String) -> Result<(), String> {
+    let conn = crate::database::get_connection(&app_handle).map_err(|e| e.to_string())?;
+    let sql = "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)";
+    crate::database::log_sql(sql);
+    conn.execute(sql, [PINNED_EVENT_KEY, id.as_str()])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn unpin_event(app_handle: tauri::AppHandle) -> Result<(), String> {
+    clear_pinned_event(&app_handle)
+}
+
+/// Get the pinned event with a live `minutes_until_start`, None if nothing is pinned.
+/// The pin is cleared automatically once the event ends or is deleted.
+#[tauri::command]
+#[allow(unused_variables)]
+pub async fn get_pinned_event(app_handle: tauri::AppHandle) -> Option<PinnedEvent> {
+    #[cfg(target_os = "macos")]
+    {
+        resolve_pinned_event(&app_handle)
+    }
+    #[cfg(not(target_os = "macos"))]
+    None
+}
+
+/// Phrase `target` relative to `now`: "now", "in 5 min", "in 2 hr", "tomorrow at 9:00",
+/// "in 3 days", "2 min ago". `local_datetime` resolves local (year, month, day, hour, minute).
+fn format_relative(
+    target: f64,
+    now: f64,
+    local_datetime: impl Fn(f64) -> (i64, u32, u32, u32, u32),
+) -> String {
+    let delta = target - now;
+    let minutes = (delta.abs() / 60.0).round() as i64;
+
+    if minutes == 0 {
+        return "now".to_string();
+    }
+    if delta < 0.0 {
+        return match minutes {
+            m if m < 60 => format!("{} min ago", m),
+            m if m < 24 * 60 => format!("{} hr ago", m / 60),
+            m => format!("{} days ago", m / (24 * 60)),
+        };
+    }
+    if minutes < 60 {
+        return format!("in {} min", minutes);
+    }
+
+    let (ty, tm, td, hour, minute) = local_datetime(target);
+    let (ny, nm, nd, _, _) = local_datetime(now);
+    match days_from_civil(ty, tm, td) - days_from_civil(ny, nm, nd) {
+        0 => format!("in {} hr", (minutes as f64 / 60.0).round() as i64),
+        1 => format!("tomorrow at {}:{:02}", hour, minute),
+        days => format!("in {} days", days),
+    }
+}
+
+/// Local date and time of a timestamp (UTC where there is no calendar API to ask)
+fn local_datetime(timestamp: f64) -> (i64, u32, u32, u32, u32) {
+    #[cfg(target_os = "macos")]
+    {
+        macos::local_datetime(timestamp)
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        let secs = timestamp.floor() as i64;
+        let (year, month, day) = civil_from_days(secs.div_euclid(86_400));
+        let time = secs.rem_euclid(86_400);
+        (
+            year,
+            month,
+            day,
+            (time / 3600) as u32,
+            ((time % 3600) / 60) as u32,
+        )
+    }
+}
+
+/// Format a timestamp relative to now in the local timezone ("in 5 min", "tomorrow at 9:00",
+/// "2 min ago"), so every surface phrases times the same way
+#[tauri::command]
+pub fn format_time_until(target_ts: f64) -> String {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0);
+    format_relative(target_ts, now, local_datetime)
+}
+
+/// Reminder notifications set up by `schedule_reminder_notifications`
+#[cfg(target_os = "macos")]
+#[derive(Default)]
+struct ReminderSchedule {
+    /// None until scheduling is enabled, so `calendar-changed` doesn't start it
+    horizon_hours: Option<u32>,
+    lead_minutes: u32,
+    /// Bumped on every reschedule, timers from older generations do nothing
+    generation: u64,
+    /// The single task firing this generation's notifications, aborted on reschedule
+    timer: Option<tauri::async_runtime::JoinHandle<()>>,
+    /// Due date of each reminder already notified, so each fires once (again if its due date
+    /// moves). Entries are dropped once their due date has passed.
+    fired: std::collections::HashMap<String, f64>,
+}
+
+#[cfg(target_os = "macos")]
+static REMINDER_SCHEDULE: std::sync::Mutex<Option<ReminderSchedule>> = std::sync::Mutex::new(None);
+
+/// The window is re-read this often, so reminders entering the horizon get scheduled
+#[cfg(target_os = "macos")]
+const REMINDER_RESCHEDULE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3600);
+
+#[cfg(target_os = "macos")]
+static REMINDER_RESCHEDULER_STARTED: std::sync::atomic::AtomicBool =
+    std::sync::atomic::AtomicBool::new(false);
+
+/// Fire the notification for a reminder unless it was superseded or already fired
+#[cfg(target_os = "macos")]
+fn fire_reminder_notification(app_handle: &tauri::AppHandle, generation: u64, reminder: Reminder) {
+    use tauri::Emitter;
+
+    let due = match reminder.due_date {
+        Some(due) => due,
+        None => return,
+    };
+
+    {
+        let mut guard = match REMINDER_SCHEDULE.lock() {
+            Ok(guard) => guard,
+            Err(_) => return,
+        };
+        let schedule = match guard.as_mut() {
+            Some(schedule) if schedule.generation == generation => schedule,
+            _ => return,
+        };
+        if schedule.fired.get(&reminder.id) == Some(&due) {
+            return;
+        }
+        schedule.fired.insert(reminder.id.clone(), due);
+    }
+
+    // Counted as fired, so it doesn't go off late once quiet hours end
+    if crate::quiet_hours::suppressed("reminder", &reminder.title) {
+        return;
+    }
+
+    // Reminders bypass the toast rate limit, several can come due at the same minute
+    crate::toast::show_unthrottled(
+        app_handle,
+        &format!("{} · {}", reminder.title, format_time_until(due)),
+        6000,
+        crate::toast::ToastLevel::Info,
+    );
+    let _ = app_handle.emit("reminder-due", reminder);
+}
+
+/// Re-read reminders and start one timer task that fires each one due inside the horizon in
+/// turn, replacing the previous task. Returns how many were scheduled (0 when scheduling isn't
+/// enabled).
+#[cfg(target_os = "macos")]
+async fn reschedule_reminder_notifications(app_handle: tauri::AppHandle) -> usize {
+    let (generation, horizon_hours, lead_minutes) = {
+        let mut guard = match REMINDER_SCHEDULE.lock() {
+            Ok(guard) => guard,
+            Err(_) => return 0,
+        };
+        match guard.as_mut() {
+            Some(schedule) => match schedule.horizon_hours {
+                Some(horizon_hours) => {
+                    schedule.generation += 1;
+                    (schedule.generation, horizon_hours, schedule.lead_minutes)
+                }
+                None => return 0,
+            },
+            None => return 0,
+        }
+    };
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0);
+    let horizon_end = now + horizon_hours as f64 * 3600.0;
+
+    let due: Vec<(f64, Reminder)> = macos::get_reminders(true)
+        .await
+        .into_iter()
+        .filter(|r| !r.is_completed)
+        .filter_map(|r| r.due_date.map(|due| (due, r)))
+        .filter(|(due, _)| *due > now && *due <= horizon_end)
+        .collect();
+    let count = due.len();
+
+    let mut queue: Vec<(std::time::Instant, Reminder)> = due
+        .into_iter()
+        .map(|(due, reminder)| {
+            // Already inside the lead time, notify right away
+            let delay = (due - lead_minutes as f64 * 60.0 - now).max(0.0);
+            (
+                std::time::Instant::now() + std::time::Duration::from_secs_f64(delay),
+                reminder,
+            )
+        })
+        .collect();
+    queue.sort_by_key(|(fire_at, _)| *fire_at);
+
+    let handle = app_handle.clone();
+    let timer = tauri::async_runtime::spawn(async move {
+        for (fire_at, reminder) in queue {
+            tokio::time::sleep_until(fire_at.into()).await;
+            fire_reminder_notification(&handle, generation, reminder);
+        }
+    });
+
+    if let Ok(mut guard) = REMINDER_SCHEDULE.lock() {
+        match guard.as_mut() {
+            Some(schedule) if schedule.generation == generation => {
+                if let Some(previous) = schedule.timer.replace(timer) {
+                    previous.abort();
+                }
+                // Past reminders are never scheduled again
+                schedule.fired.retain(|_, due| *due > now);
+            }
+            // Superseded while reading reminders
+            _ => timer.abort(),
+        }
+    }
+
+    log::debug!(
+        "Scheduled {} reminder notifications within {}h",
+        count,
+        horizon_hours
+    );
+    count
+}
+
+/// Show a toast and emit `reminder-due` (with the reminder) for every incomplete reminder due
+/// within `horizon_hours`, `lead_minutes` (default 0) before it's due. Each reminder fires once.
+/// Timers are rebuilt on `calendar-changed` and hourly. A horizon of 0 turns this off.
+/// Returns how many reminders were scheduled.
+#[tauri::command]
+#[allow(unused_variables)]
+pub async fn schedule_reminder_notifications(
+    app_handle: tauri::AppHandle,
+    horizon_hours: u32,
+    lead_minutes: Option<u32>,
+) -> Result<usize, String> {
+    #[cfg(target_os = "macos")]
+    {
+        {
+            let mut guard = REMINDER_SCHEDULE.lock().map_err(|e| e.to_string())?;
+            let schedule = guard.get_or_insert_with(ReminderSchedule::default);
+            schedule.lead_minutes = lead_minutes.unwrap_or(0);
+            if horizon_hours == 0 {
+                // Invalidate pending timers
+                schedule.horizon_hours = None;
+                schedule.generation += 1;
+                if let Some(timer) = schedule.timer.take() {
+                    timer.abort();
+                }
+                return Ok(0);
+            }
+            schedule.horizon_hours = Some(horizon_hours);
+        }
+
+        // Slide the window forward, doing nothing while scheduling is off
+        if !REMINDER_RESCHEDULER_STARTED.swap(true, std::sync::atomic::Ordering::SeqCst) {
+            let handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    tokio::time::sleep(REMINDER_RESCHEDULE_INTERVAL).await;
+                    reschedule_reminder_notifications(handle.clone()).await;
+                }
+            });
+        }
+
+        Ok(reschedule_reminder_notifications(app_handle).await)
+    }
+    #[cfg(not(target_os = "macos"))]
+    Ok(0)
+}
+
+/// Convert days since the Unix epoch to a (year, month, day) civil date
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// Convert a (year, month, day) civil date to days since the Unix epoch
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let yoe = year - era * 400;
+    let month = month as i64;
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Sort key for EventKit priorities: high (1-4), medium (5), low (6-9), then none (0)
+fn priority_rank(priority: i32) -> i32 {
+    if priority == 0 {
+        10
+    } else {
+        priority
+    }
+}
+
+/// Bucket reminders by local calendar day of their due date, relative to `now`.
+/// `local_date` resolves the local calendar date of a timestamp.
+fn group_reminders(
+    mut reminders: Vec<Reminder>,
+    now: f64,
+    local_date: impl Fn(f64) -> (i64, u32, u32),
+) -> RemindersGrouped {
+    reminders.sort_by(|a, b| {
+        a.due_date
+            .partial_cmp(&b.due_date)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(priority_rank(a.priority).cmp(&priority_rank(b.priority)))
+    });
+
+    let day_number = |timestamp: f64| {
+        let (year, month, day) = local_date(timestamp);
+        days_from_civil(year, month, day)
+    };
+    let today = day_number(now);
+
+    let mut grouped = RemindersGrouped::default();
+    for reminder in reminders {
+        let bucket = match reminder.due_date {
+            None => &mut grouped.no_date,
+            Some(due) => match day_number(due) - today {
+                offset if offset < 0 => &mut grouped.overdue,
+                0 => &mut grouped.today,
+                1 => &mut grouped.tomorrow,
+                offset if offset < 7 => &mut grouped.this_week,
+                _ => &mut grouped.later,
+            },
+        };
+        bucket.push(reminder);
+    }
+
+    grouped
+}
+
+/// Get incomplete reminders grouped into overdue/today/tomorrow/this week/later/no date,
+/// each sorted by due date then priority
+#[tauri::command]
+pub async fn get_reminders_grouped(
+    force_refresh: Option<bool>,
+) -> Result<RemindersGrouped, String> {
+    #[cfg(target_os = "macos")]
+    {
+        let reminders = macos::get_reminders(force_refresh.unwrap_or(false)).await;
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs_f64())
+            .unwrap_or(0.0);
+        Ok(group_reminders(reminders, now, macos::local_date))
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = force_refresh;
+        Ok(group_reminders(Vec::new(), 0.0, |ts| {
+            civil_from_days((ts.floor() as i64).div_euclid(86_400))
+        }))
+    }
+}
+
+/// Format a timestamp as an iCalendar UTC date-time (e.g. 20240131T093000Z)
+fn ics_utc_datetime(timestamp: f64) -> String {
+    let secs = timestamp.floor() as i64;
+    let (year, month, day) = civil_from_days(secs.div_euclid(86_400));
+    let rem = secs.rem_euclid(86_400);
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        (rem % 3600) / 60,
+        rem % 60
+    )
+}
+
+/// Escape TEXT values per RFC 5545
+fn ics_escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Fold a content line to 75 octets, continuation lines start with a space
+fn ics_fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut len = 0;
+    for c in line.chars() {
+        let width = c.len_utf8();
+        if len + width > 75 {
+            folded.push_str("\r\n ");
+            len = 1;
+        }
+        folded.push(c);
+        len += width;
+    }
+    folded
+}
+
+/// Serialize events into an iCalendar document.
+/// `local_date` resolves the local calendar date of all-day event boundaries.
+fn events_to_ics(events: &[CalendarEvent], local_date: impl Fn(f64) -> (i64, u32, u32)) -> String {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0);
+    let dtstamp = ics_utc_datetime(now);
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//openNook//Calendar Export//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+
+    for event in events {
+        lines.push("BEGIN:VEVENT".to_string());
+        // Occurrences of a recurring event share their identifier and are exported as separate
+        // events, so the start makes each UID unique
+        lines.push(format!(
+            "UID:{}-{}",
+            ics_escape(&event.id),
+            ics_utc_datetime(event.start_date)
+        ));
+        lines.push(format!("DTSTAMP:{}", dtstamp));
+
+        if event.is_all_day {
+            // DTEND is exclusive; EventKit ends all-day events at 23:59:59 of the last day
+            let (sy, sm, sd) = local_date(event.start_date);
+            let (ey, em, ed) = local_date(event.end_date + 1.0);
+            lines.push(format!("DTSTART;VALUE=DATE:{:04}{:02}{:02}", sy, sm, sd));
+            lines.push(format!("DTEND;VALUE=DATE:{:04}{:02}{:02}", ey, em, ed));
+        } else {
+            lines.push(format!("DTSTART:{}", ics_utc_datetime(event.start_date)));
+            lines.push(format!("DTEND:{}", ics_utc_datetime(event.end_date)));
+        }
+
+        lines.push(format!("SUMMARY:{}", ics_escape(&event.title)));
+        if let Some(location) = &event.location {
+            if !location.is_empty() {
+                lines.push(format!("LOCATION:{}", ics_escape(location)));
+            }
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut ics = lines
+        .iter()
+        .map(|line| ics_fold(line))
+        .collect::<Vec<_>>()
+        .join("\r\n");
+    ics.push_str("\r\n");
+    ics
+}
+
+/// Export upcoming events as an iCalendar (.ics) document
+#[tauri::command]
+pub async fn export_events_ics(days_ahead: i64) -> Result<String, String> {
+    #[cfg(target_os = "macos")]
+    {
+        let events = macos::fetch_events(days_ahead);
+        Ok(events_to_ics(&events, macos::local_date))
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = days_ahead;
+        Ok(events_to_ics(&[], |ts| {
+            civil_from_days((ts.floor() as i64).div_euclid(86_400))
+        }))
+    }
+}
+
 #[tauri::command]
 pub async fn open_calendar_event(_id: String, date: f64) -> Result<(), String> {
     #[cfg(target_os = "macos")]
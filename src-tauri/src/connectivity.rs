@@ -0,0 +1,111 @@
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+/// Small, highly available endpoint; any HTTP response counts as online
+const PROBE_URL: &str = "https://cloudflare.com/cdn-cgi/trace";
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// How long a probe result is reused before probing again
+const CACHE_TTL: Duration = Duration::from_secs(5);
+
+/// How often the watcher probes for `connectivity-changed`
+const WATCH_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ConnectivityStatus {
+    pub online: bool,
+    /// Round trip of the probe request, None when offline
+    pub latency_ms: Option<f64>,
+}
+
+/// Most recent probe result and when it was taken
+static LAST_STATUS: Mutex<Option<(Instant, ConnectivityStatus)>> = Mutex::new(None);
+
+async fn probe() -> ConnectivityStatus {
+    let client = match reqwest::Client::builder().timeout(PROBE_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => {
+            log::warn!("Failed to build connectivity client: {}", e);
+            return ConnectivityStatus {
+                online: false,
+                latency_ms: None,
+            };
+        }
+    };
+
+    let start = Instant::now();
+    let status = match client.head(PROBE_URL).send().await {
+        Ok(_) => ConnectivityStatus {
+            online: true,
+            latency_ms: Some((start.elapsed().as_secs_f64() * 10_000.0).round() / 10.0),
+        },
+        Err(e) => {
+            log::debug!("Connectivity probe failed: {}", e);
+            ConnectivityStatus {
+                online: false,
+                latency_ms: None,
+            }
+        }
+    };
+
+    if let Ok(mut last) = LAST_STATUS.lock() {
+        *last = Some((Instant::now(), status));
+    }
+    status
+}
+
+/// Connectivity, probing only when the cached result is older than CACHE_TTL
+pub async fn connectivity_status() -> ConnectivityStatus {
+    let cached = LAST_STATUS
+        .lock()
+        .ok()
+        .and_then(|last| *last)
+        .filter(|(checked, _)| checked.elapsed() < CACHE_TTL);
+
+    match cached {
+        Some((_, status)) => status,
+        None => probe().await,
+    }
+}
+
+/// Fail fast with an "Offline" error instead of letting a network request time out
+pub async fn ensure_online() -> Result<(), String> {
+    if connectivity_status().await.online {
+        Ok(())
+    } else {
+        Err("Offline: no network connection".to_string())
+    }
+}
+
+/// Check whether the network is reachable (cached for a few seconds)
+#[tauri::command]
+pub async fn check_connectivity() -> ConnectivityStatus {
+    connectivity_status().await
+}
+
+/// Probe periodically and emit `connectivity-changed` (with the new status) when the
+/// online state flips, so the UI can disable network widgets
+pub fn setup_connectivity_monitoring(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut last_online: Option<bool> = None;
+
+        loop {
+            if !crate::power::is_monitoring_paused() {
+                let status = probe().await;
+                if last_online.is_some_and(|online| online != status.online) {
+                    log::info!(
+                        "Connectivity changed: {}",
+                        if status.online { "online" } else { "offline" }
+                    );
+                    let _ = app_handle.emit("connectivity-changed", status);
+                }
+                last_online = Some(status.online);
+            }
+
+            tokio::time::sleep(WATCH_INTERVAL).await;
+        }
+    });
+}
@@ -0,0 +1,66 @@
+use crate::calendar::{CalendarEvent, EventStatus};
+use crate::models::NowPlayingData;
+use crate::power::BatteryStatus;
+use serde::Serialize;
+use tauri::AppHandle;
+
+/// Everything the collapsed notch shows, gathered in one call
+#[derive(Serialize, Clone)]
+pub struct Dashboard {
+    /// None when nothing is playing
+    pub now_playing: Option<NowPlayingData>,
+    /// Next event that hasn't ended yet
+    pub next_event: Option<CalendarEvent>,
+    pub next_event_status: Option<EventStatus>,
+    /// Incomplete reminders, None where reminders are unsupported
+    pub reminder_count: Option<usize>,
+    /// None on machines without a battery
+    pub battery: Option<BatteryStatus>,
+    /// There is no weather provider yet, so this is always None
+    pub weather: Option<serde_json::Value>,
+}
+
+/// Get now-playing, the next event, the reminder count and battery status in a single
+/// call. Each part goes through its usual cache, and the parts are fetched concurrently.
+#[tauri::command]
+pub async fn get_dashboard(app_handle: AppHandle) -> Dashboard {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0);
+
+    let (now_playing, events, reminders) = tokio::join!(
+        crate::audio::get_now_playing(app_handle),
+        crate::calendar::get_upcoming_events(None),
+        crate::calendar::get_reminders(None, None),
+    );
+
+    let now_playing = Some(now_playing).filter(|data| data.title.is_some());
+
+    let next_event = events
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|event| event.end_date > now)
+        .min_by(|a, b| a.start_date.total_cmp(&b.start_date));
+
+    let next_event_status = next_event
+        .as_ref()
+        .map(|event| crate::calendar::event_status(event, now));
+
+    let reminder_count = if cfg!(target_os = "macos") {
+        reminders
+            .ok()
+            .map(|reminders| reminders.iter().filter(|r| !r.is_completed).count())
+    } else {
+        None
+    };
+
+    Dashboard {
+        now_playing,
+        next_event,
+        next_event_status,
+        reminder_count,
+        battery: crate::power::get_battery_status(),
+        weather: None,
+    }
+}
@@ -2,10 +2,13 @@ use base64::Engine;
 use log;
 use rusqlite::types::{ToSql, ValueRef};
 use rusqlite::{Connection, Result};
+use serde::Serialize;
 use serde_json::Value as JsonValue;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::fs;
 use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use tauri::command;
 use tauri::{AppHandle, Manager};
 
@@ -13,6 +16,57 @@ pub struct DatabaseState {
     pub db_path: PathBuf,
 }
 
+/// Frontend queries slower than this are logged at warn level and kept for diagnostics
+const SLOW_QUERY_THRESHOLD: Duration = Duration::from_millis(50);
+
+/// Number of slow queries kept, oldest are dropped first
+const SLOW_QUERY_LIMIT: usize = 50;
+
+/// A query that took longer than SLOW_QUERY_THRESHOLD
+#[derive(Serialize, Clone)]
+pub struct SlowQuery {
+    pub sql: String,
+    pub duration_ms: f64,
+    /// Unix timestamp (seconds)
+    pub executed_at: i64,
+}
+
+static SLOW_QUERIES: Mutex<VecDeque<SlowQuery>> = Mutex::new(VecDeque::new());
+
+/// Log and remember `sql` if it ran for longer than SLOW_QUERY_THRESHOLD
+fn record_query_time(sql: &str, started: Instant) {
+    let elapsed = started.elapsed();
+    if elapsed < SLOW_QUERY_THRESHOLD {
+        return;
+    }
+
+    let duration_ms = elapsed.as_secs_f64() * 1000.0;
+    log::warn!("Slow SQL ({:.1}ms): {}", duration_ms, sql);
+
+    if let Ok(mut queries) = SLOW_QUERIES.lock() {
+        if queries.len() >= SLOW_QUERY_LIMIT {
+            queries.pop_front();
+        }
+        queries.push_back(SlowQuery {
+            sql: sql.to_string(),
+            duration_ms,
+            executed_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0),
+        });
+    }
+}
+
+/// Recent db_execute/db_select queries that took longer than 50ms, newest first
+#[command]
+pub fn get_slow_queries() -> Vec<SlowQuery> {
+    SLOW_QUERIES
+        .lock()
+        .map(|queries| queries.iter().rev().cloned().collect())
+        .unwrap_or_default()
+}
+
 /// Helper to log SQL in debug mode
 pub fn log_sql(sql: &str) {
     log::debug!("SQL: {}", sql);
@@ -106,8 +160,12 @@ pub fn db_execute(
     let sql_args: Vec<Box<dyn ToSql>> = args.iter().map(json_to_sql).collect();
     let sql_args_refs: Vec<&dyn ToSql> = sql_args.iter().map(|a| a.as_ref()).collect();
 
-    conn.execute(&sql, sql_args_refs.as_slice())
-        .map_err(|e| e.to_string())
+    let started = Instant::now();
+    let result = conn
+        .execute(&sql, sql_args_refs.as_slice())
+        .map_err(|e| e.to_string());
+    record_query_time(&sql, started);
+    result
 }
 
 #[command]
@@ -122,6 +180,7 @@ pub fn db_select(
     let sql_args: Vec<Box<dyn ToSql>> = args.iter().map(json_to_sql).collect();
     let sql_args_refs: Vec<&dyn ToSql> = sql_args.iter().map(|a| a.as_ref()).collect();
 
+    let started = Instant::now();
     let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
 
     // Get column names to create the hashmap
@@ -157,6 +216,7 @@ pub fn db_select(
     for row in rows {
         results.push(row.map_err(|e| e.to_string())?);
     }
+    record_query_time(&sql, started);
 
     Ok(results)
 }
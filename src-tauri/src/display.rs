@@ -0,0 +1,627 @@
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+/// Night Shift status as filled in by CBBlueLightClient's getBlueLightStatus:
+/// Trailing reserved bytes guard against the struct growing in future macOS versions.
+#[cfg(all(target_os = "macos", feature = "night-shift"))]
+#[repr(C)]
+#[allow(dead_code)] // Fields exist to match the native layout
+struct BlueLightStatus {
+    active: u8,
+    enabled: u8,
+    sun_schedule_permitted: u8,
+    mode: i32,
+    schedule: [i32; 4],
+    disable_flags: u64,
+    available: u8,
+    _reserved: [u8; 64],
+}
+
+/// Load the private CoreBrightness framework and return the CBBlueLightClient class,
+/// or None if it isn't available on this macOS version
+#[cfg(all(target_os = "macos", feature = "night-shift"))]
+fn blue_light_client_class() -> Option<&'static objc2::runtime::AnyClass> {
+    use std::sync::OnceLock;
+
+    static LOADED: OnceLock<bool> = OnceLock::new();
+
+    let loaded = *LOADED.get_or_init(|| unsafe {
+        let handle = libc::dlopen(
+            c"/System/Library/PrivateFrameworks/CoreBrightness.framework/CoreBrightness".as_ptr(),
+            libc::RTLD_LAZY,
+        );
+        if handle.is_null() {
+            log::warn!("CoreBrightness framework unavailable, Night Shift disabled");
+        }
+        !handle.is_null()
+    });
+
+    if !loaded {
+        return None;
+    }
+
+    objc2::runtime::AnyClass::get(c"CBBlueLightClient")
+}
+
+#[cfg(all(target_os = "macos", feature = "night-shift"))]
+fn blue_light_client() -> Option<objc2::rc::Retained<objc2::runtime::AnyObject>> {
+    use objc2::*;
+
+    let class = blue_light_client_class()?;
+    unsafe { msg_send![class, new] }
+}
+
+/// Get whether Night Shift is currently on (None if unsupported)
+#[tauri::command]
+pub fn get_night_shift() -> Option<bool> {
+    #[cfg(all(target_os = "macos", feature = "night-shift"))]
+    {
+        use objc2::runtime::Bool;
+        use objc2::*;
+
+        let client = blue_light_client()?;
+        // All-zero is a valid value for this plain C struct
+        let mut status: BlueLightStatus = unsafe { std::mem::zeroed() };
+
+        let ok: Bool = unsafe {
+            msg_send![
+                &*client,
+                getBlueLightStatus: &mut status as *mut BlueLightStatus as *mut std::ffi::c_void
+            ]
+        };
+
+        if !ok.as_bool() {
+            log::debug!("getBlueLightStatus failed");
+            return None;
+        }
+
+        Some(status.enabled != 0)
+    }
+
+    #[cfg(not(all(target_os = "macos", feature = "night-shift")))]
+    {
+        None
+    }
+}
+
+/// Turn Night Shift on or off
+#[tauri::command]
+#[allow(unused_variables)]
+pub fn set_night_shift(enabled: bool) -> Result<(), String> {
+    #[cfg(all(target_os = "macos", feature = "night-shift"))]
+    {
+        use objc2::runtime::Bool;
+        use objc2::*;
+
+        let client = blue_light_client().ok_or("Unsupported: Night Shift is not available")?;
+        let ok: Bool = unsafe { msg_send![&*client, setEnabled: Bool::new(enabled)] };
+
+        if !ok.as_bool() {
+            return Err("Failed to change Night Shift state".to_string());
+        }
+
+        log::debug!("Night Shift set to {}", enabled);
+        Ok(())
+    }
+
+    // Windows has Night Light and Linux has redshift/gammastep, but neither exposes
+    // a stable API to toggle them from here
+    #[cfg(not(all(target_os = "macos", feature = "night-shift")))]
+    {
+        Err("Unsupported: Night Shift is not available on this platform".to_string())
+    }
+}
+
+/// DisplayServices brightness functions, resolved at runtime since the framework is private (macOS)
+#[cfg(target_os = "macos")]
+struct DisplayServices {
+    get_brightness: unsafe extern "C" fn(u32, *mut f32) -> i32,
+    set_brightness: unsafe extern "C" fn(u32, f32) -> i32,
+}
+
+#[cfg(target_os = "macos")]
+extern "C" {
+    fn CGMainDisplayID() -> u32;
+    fn CGDisplayCopyDisplayMode(display: u32) -> *mut libc::c_void;
+    fn CGDisplayModeGetRefreshRate(mode: *mut libc::c_void) -> f64;
+    fn CGDisplayModeGetPixelWidth(mode: *mut libc::c_void) -> usize;
+    fn CGDisplayModeGetPixelHeight(mode: *mut libc::c_void) -> usize;
+    fn CGDisplayModeRelease(mode: *mut libc::c_void);
+}
+
+#[cfg(target_os = "macos")]
+fn display_services() -> Option<&'static DisplayServices> {
+    use std::sync::OnceLock;
+
+    static SERVICES: OnceLock<Option<DisplayServices>> = OnceLock::new();
+
+    SERVICES
+        .get_or_init(|| unsafe {
+            let handle = libc::dlopen(
+                c"/System/Library/PrivateFrameworks/DisplayServices.framework/DisplayServices"
+                    .as_ptr(),
+                libc::RTLD_LAZY,
+            );
+            if handle.is_null() {
+                log::warn!("DisplayServices framework unavailable, brightness control disabled");
+                return None;
+            }
+
+            let get = libc::dlsym(handle, c"DisplayServicesGetBrightness".as_ptr());
+            let set = libc::dlsym(handle, c"DisplayServicesSetBrightness".as_ptr());
+            if get.is_null() || set.is_null() {
+                return None;
+            }
+
+            Some(DisplayServices {
+                get_brightness: std::mem::transmute::<
+                    *mut libc::c_void,
+                    unsafe extern "C" fn(u32, *mut f32) -> i32,
+                >(get),
+                set_brightness: std::mem::transmute::<
+                    *mut libc::c_void,
+                    unsafe extern "C" fn(u32, f32) -> i32,
+                >(set),
+            })
+        })
+        .as_ref()
+}
+
+/// Run `f` with the DDC/CI handle of the primary monitor (Windows)
+#[cfg(target_os = "windows")]
+fn with_primary_physical_monitor<R>(
+    f: impl FnOnce(windows::Win32::Foundation::HANDLE) -> Option<R>,
+) -> Option<R> {
+    use windows::Win32::Devices::Display::{
+        DestroyPhysicalMonitors, GetNumberOfPhysicalMonitorsFromHMONITOR,
+        GetPhysicalMonitorsFromHMONITOR, PHYSICAL_MONITOR,
+    };
+    use windows::Win32::Foundation::POINT;
+    use windows::Win32::Graphics::Gdi::{MonitorFromPoint, MONITOR_DEFAULTTOPRIMARY};
+
+    unsafe {
+        let hmonitor = MonitorFromPoint(POINT { x: 0, y: 0 }, MONITOR_DEFAULTTOPRIMARY);
+
+        let mut count = 0u32;
+        GetNumberOfPhysicalMonitorsFromHMONITOR(hmonitor, &mut count).ok()?;
+        if count == 0 {
+            return None;
+        }
+
+        let mut monitors = vec![PHYSICAL_MONITOR::default(); count as usize];
+        GetPhysicalMonitorsFromHMONITOR(hmonitor, &mut monitors).ok()?;
+
+        let result = f(monitors[0].hPhysicalMonitor);
+        let _ = DestroyPhysicalMonitors(&monitors);
+        result
+    }
+}
+
+/// First backlight device under /sys/class/backlight (Linux)
+#[cfg(target_os = "linux")]
+fn backlight_device() -> Option<std::path::PathBuf> {
+    std::fs::read_dir("/sys/class/backlight")
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .next()
+}
+
+#[cfg(target_os = "linux")]
+fn read_backlight_value(device: &std::path::Path, file: &str) -> Option<u32> {
+    std::fs::read_to_string(device.join(file))
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Get the built-in/primary display brightness (0.0-1.0), None if unsupported
+#[tauri::command]
+pub fn get_display_brightness() -> Option<f64> {
+    #[cfg(target_os = "macos")]
+    {
+        let services = display_services()?;
+        let mut level = 0.0f32;
+        let result = unsafe { (services.get_brightness)(CGMainDisplayID(), &mut level) };
+
+        // Non-zero means the display has no software brightness (e.g. most external monitors)
+        if result != 0 {
+            return None;
+        }
+        Some(level as f64)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::Devices::Display::GetMonitorBrightness;
+
+        with_primary_physical_monitor(|monitor| {
+            let (mut min, mut current, mut max) = (0u32, 0u32, 0u32);
+            let ok = unsafe { GetMonitorBrightness(monitor, &mut min, &mut current, &mut max) };
+            if ok == 0 || max <= min {
+                return None;
+            }
+            Some(current.saturating_sub(min) as f64 / (max - min) as f64)
+        })
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let device = backlight_device()?;
+        let max = read_backlight_value(&device, "max_brightness")?;
+        let current = read_backlight_value(&device, "brightness")?;
+        if max == 0 {
+            return None;
+        }
+        Some(current as f64 / max as f64)
+    }
+}
+
+/// Set the built-in/primary display brightness (0.0-1.0) and emit `brightness-changed`
+#[tauri::command]
+pub async fn set_display_brightness(app_handle: AppHandle, level: f64) -> Result<(), String> {
+    if !level.is_finite() {
+        return Err("Invalid brightness level".to_string());
+    }
+    let level = level.clamp(0.0, 1.0);
+
+    #[cfg(target_os = "macos")]
+    {
+        let services = display_services().ok_or("Unsupported: brightness control unavailable")?;
+        let result = unsafe { (services.set_brightness)(CGMainDisplayID(), level as f32) };
+        if result != 0 {
+            return Err("Unsupported: this display has no software brightness control".to_string());
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::Devices::Display::{GetMonitorBrightness, SetMonitorBrightness};
+
+        with_primary_physical_monitor(|monitor| unsafe {
+            let (mut min, mut current, mut max) = (0u32, 0u32, 0u32);
+            if GetMonitorBrightness(monitor, &mut min, &mut current, &mut max) == 0 || max <= min {
+                return None;
+            }
+            let value = min + ((max - min) as f64 * level).round() as u32;
+            (SetMonitorBrightness(monitor, value) != 0).then_some(())
+        })
+        .ok_or("Unsupported: monitor does not support DDC/CI brightness")?;
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let device = backlight_device().ok_or("Unsupported: no backlight device found")?;
+        let max = read_backlight_value(&device, "max_brightness")
+            .ok_or("Unsupported: unreadable backlight device")?;
+        let value = (max as f64 * level).round() as u32;
+
+        // Writing sysfs usually needs root, logind allows it for the active session
+        if std::fs::write(device.join("brightness"), value.to_string()).is_err() {
+            let name = device
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            set_brightness_via_logind(&name, value)
+                .await
+                .map_err(|e| format!("Failed to set brightness: {}", e))?;
+        }
+    }
+
+    let actual = get_display_brightness().unwrap_or(level);
+    let _ = app_handle.emit("brightness-changed", actual);
+    Ok(())
+}
+
+/// Set backlight brightness through logind's Session.SetBrightness (Linux)
+#[cfg(target_os = "linux")]
+async fn set_brightness_via_logind(device: &str, value: u32) -> zbus::Result<()> {
+    let conn = zbus::Connection::system().await?;
+    let proxy = zbus::Proxy::new(
+        &conn,
+        "org.freedesktop.login1",
+        "/org/freedesktop/login1/session/auto",
+        "org.freedesktop.login1.Session",
+    )
+    .await?;
+
+    proxy
+        .call::<_, _, ()>("SetBrightness", &("backlight", device, value))
+        .await
+}
+
+/// Current mode of a display, in physical pixels
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct DisplayResolution {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Refresh rate, HDR and color details of a connected display
+#[derive(Debug, Clone, Serialize)]
+pub struct DisplayCapabilities {
+    pub name: Option<String>,
+    /// Maximum refresh rate for variable refresh panels (e.g. ProMotion), None if unknown
+    pub refresh_hz: Option<f64>,
+    /// Whether the display is currently able to show HDR content
+    pub is_hdr: bool,
+    pub color_space: Option<String>,
+    pub resolution: DisplayResolution,
+}
+
+/// Capabilities of the display at `index`, in the OS's display order
+/// (NSScreen.screens on macOS, desktop-attached devices on Windows, xrandr outputs on Linux)
+#[tauri::command]
+pub fn get_display_capabilities(index: usize) -> Result<DisplayCapabilities, String> {
+    #[cfg(target_os = "macos")]
+    {
+        macos_display_capabilities(index)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        windows_display_capabilities(index)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let output = std::process::Command::new("xrandr")
+            .arg("--query")
+            .output()
+            .map_err(|e| format!("Unsupported: xrandr unavailable ({})", e))?;
+        parse_xrandr_display(&String::from_utf8_lossy(&output.stdout), index)
+            .ok_or(format!("No display at index {}", index))
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn macos_display_capabilities(index: usize) -> Result<DisplayCapabilities, String> {
+    use objc2::runtime::{AnyObject, Bool};
+    use objc2::*;
+    use objc2_foundation::NSString;
+
+    unsafe {
+        let screens: *mut AnyObject = msg_send![class!(NSScreen), screens];
+        let count: usize = if screens.is_null() {
+            0
+        } else {
+            msg_send![screens, count]
+        };
+        if index >= count {
+            return Err(format!("No display at index {}", index));
+        }
+        let screen: *mut AnyObject = msg_send![screens, objectAtIndex: index];
+
+        let description: *mut AnyObject = msg_send![screen, deviceDescription];
+        let key = NSString::from_str("NSScreenNumber");
+        let number: *mut AnyObject = msg_send![description, objectForKey: &*key];
+        if number.is_null() {
+            return Err(format!("Display {} has no display id", index));
+        }
+        let display_id: u32 = msg_send![number, unsignedIntValue];
+
+        let mode = CGDisplayCopyDisplayMode(display_id);
+        if mode.is_null() {
+            return Err(format!("Display {} has no current mode", index));
+        }
+        let mode_hz = CGDisplayModeGetRefreshRate(mode);
+        let resolution = DisplayResolution {
+            width: CGDisplayModeGetPixelWidth(mode) as u32,
+            height: CGDisplayModeGetPixelHeight(mode) as u32,
+        };
+        CGDisplayModeRelease(mode);
+
+        // Built-in panels report 0 Hz, maximumFramesPerSecond (macOS 12+) covers those and ProMotion
+        let has_max_fps: Bool = msg_send![screen, respondsToSelector: sel!(maximumFramesPerSecond)];
+        let max_fps: isize = if has_max_fps.as_bool() {
+            msg_send![screen, maximumFramesPerSecond]
+        } else {
+            0
+        };
+        let refresh_hz = if max_fps > 0 {
+            Some(max_fps as f64)
+        } else if mode_hz > 0.0 {
+            Some(mode_hz)
+        } else {
+            None
+        };
+
+        // Above 1.0 when the screen can render values brighter than SDR white (macOS 10.15+)
+        let has_edr: Bool = msg_send![
+            screen,
+            respondsToSelector: sel!(maximumPotentialExtendedDynamicRangeColorComponentValue)
+        ];
+        let is_hdr = if has_edr.as_bool() {
+            let max_edr: f64 = msg_send![
+                screen,
+                maximumPotentialExtendedDynamicRangeColorComponentValue
+            ];
+            max_edr > 1.0
+        } else {
+            false
+        };
+
+        let color_space: *mut AnyObject = msg_send![screen, colorSpace];
+        let color_space = if color_space.is_null() {
+            None
+        } else {
+            let name: Option<objc2::rc::Retained<NSString>> = msg_send![color_space, localizedName];
+            name.map(|name| name.to_string())
+        };
+
+        let name: Option<objc2::rc::Retained<NSString>> = msg_send![screen, localizedName];
+
+        Ok(DisplayCapabilities {
+            name: name.map(|name| name.to_string()),
+            refresh_hz,
+            is_hdr,
+            color_space,
+            resolution,
+        })
+    }
+}
+
+/// Read a NUL-terminated UTF-16 buffer
+#[cfg(target_os = "windows")]
+fn wide_to_string(buffer: &[u16]) -> String {
+    let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+    String::from_utf16_lossy(&buffer[..len])
+}
+
+/// DXGI description of the output driving the GDI device `device_name` (Windows)
+#[cfg(target_os = "windows")]
+fn dxgi_output_desc(
+    device_name: &[u16; 32],
+) -> Option<windows::Win32::Graphics::Dxgi::DXGI_OUTPUT_DESC1> {
+    use windows::core::ComInterface;
+    use windows::Win32::Graphics::Dxgi::{
+        CreateDXGIFactory1, IDXGIFactory1, IDXGIOutput6, DXGI_OUTPUT_DESC1,
+    };
+
+    unsafe {
+        let factory: IDXGIFactory1 = CreateDXGIFactory1().ok()?;
+        let mut adapter_index = 0;
+        while let Ok(adapter) = factory.EnumAdapters1(adapter_index) {
+            let mut output_index = 0;
+            while let Ok(output) = adapter.EnumOutputs(output_index) {
+                if let Ok(output) = output.cast::<IDXGIOutput6>() {
+                    let mut desc = DXGI_OUTPUT_DESC1::default();
+                    if output.GetDesc1(&mut desc).is_ok() && &desc.DeviceName == device_name {
+                        return Some(desc);
+                    }
+                }
+                output_index += 1;
+            }
+            adapter_index += 1;
+        }
+    }
+    None
+}
+
+#[cfg(target_os = "windows")]
+fn windows_display_capabilities(index: usize) -> Result<DisplayCapabilities, String> {
+    use windows::core::PCWSTR;
+    use windows::Win32::Graphics::Dxgi::Common::DXGI_COLOR_SPACE_RGB_FULL_G2084_NONE_P2020;
+    use windows::Win32::Graphics::Gdi::{
+        EnumDisplayDevicesW, EnumDisplaySettingsW, DEVMODEW, DISPLAY_DEVICEW,
+        DISPLAY_DEVICE_ATTACHED_TO_DESKTOP, ENUM_CURRENT_SETTINGS,
+    };
+
+    unsafe {
+        let new_device = || DISPLAY_DEVICEW {
+            cb: std::mem::size_of::<DISPLAY_DEVICEW>() as u32,
+            ..Default::default()
+        };
+
+        // Only devices attached to the desktop count, matching what the user sees
+        let mut attached = 0;
+        let mut device_index = 0;
+        let device = loop {
+            let mut device = new_device();
+            if !EnumDisplayDevicesW(PCWSTR::null(), device_index, &mut device, 0).as_bool() {
+                return Err(format!("No display at index {}", index));
+            }
+            device_index += 1;
+            if device.StateFlags & DISPLAY_DEVICE_ATTACHED_TO_DESKTOP == 0 {
+                continue;
+            }
+            if attached == index {
+                break device;
+            }
+            attached += 1;
+        };
+        let device_name = PCWSTR(device.DeviceName.as_ptr());
+
+        let mut mode = DEVMODEW {
+            dmSize: std::mem::size_of::<DEVMODEW>() as u16,
+            ..Default::default()
+        };
+        if !EnumDisplaySettingsW(device_name, ENUM_CURRENT_SETTINGS, &mut mode).as_bool() {
+            return Err(format!("Display {} has no current mode", index));
+        }
+
+        // The adapter's first child is the monitor, which has the friendly name
+        let mut monitor = new_device();
+        let name = if EnumDisplayDevicesW(device_name, 0, &mut monitor, 0).as_bool() {
+            Some(wide_to_string(&monitor.DeviceString))
+        } else {
+            None
+        };
+
+        let output = dxgi_output_desc(&device.DeviceName);
+        let is_hdr = output
+            .map(|desc| desc.ColorSpace == DXGI_COLOR_SPACE_RGB_FULL_G2084_NONE_P2020)
+            .unwrap_or(false);
+        let color_space = output.map(|_| {
+            if is_hdr {
+                "Rec. 2100 PQ".to_string()
+            } else {
+                "sRGB".to_string()
+            }
+        });
+
+        Ok(DisplayCapabilities {
+            name,
+            // 0 and 1 mean "hardware default"
+            refresh_hz: (mode.dmDisplayFrequency > 1).then_some(mode.dmDisplayFrequency as f64),
+            is_hdr,
+            color_space,
+            resolution: DisplayResolution {
+                width: mode.dmPelsWidth,
+                height: mode.dmPelsHeight,
+            },
+        })
+    }
+}
+
+/// Pick the `index`th active output from `xrandr --query`; the current mode line is the one
+/// whose rate is marked with `*` (Linux, X11/XWayland has no HDR)
+#[cfg(target_os = "linux")]
+fn parse_xrandr_display(output: &str, index: usize) -> Option<DisplayCapabilities> {
+    let mut active = Vec::new();
+    let mut current_output: Option<&str> = None;
+
+    for line in output.lines() {
+        if !line.starts_with(char::is_whitespace) {
+            let mut parts = line.split_whitespace();
+            current_output = match (parts.next(), parts.next()) {
+                (Some(name), Some("connected")) => Some(name),
+                _ => None,
+            };
+            continue;
+        }
+
+        let name = match current_output {
+            Some(name) => name,
+            None => continue,
+        };
+        let mut parts = line.split_whitespace();
+        let mode = parts.next().unwrap_or("");
+        let rate = match parts.find(|rate| rate.contains('*')) {
+            Some(rate) => rate.trim_end_matches(['*', '+']),
+            None => continue,
+        };
+
+        // Interlaced modes carry a suffix, e.g. 1920x1080i
+        let size = mode.split_once('x').and_then(|(width, height)| {
+            let height = height.trim_end_matches(|c: char| !c.is_ascii_digit());
+            Some((width.parse().ok()?, height.parse().ok()?))
+        });
+        let (width, height) = match size {
+            Some(size) => size,
+            None => continue,
+        };
+
+        active.push(DisplayCapabilities {
+            name: Some(name.to_string()),
+            refresh_hz: rate.parse().ok(),
+            is_hdr: false,
+            color_space: None,
+            resolution: DisplayResolution { width, height },
+        });
+        current_output = None;
+    }
+
+    active.into_iter().nth(index)
+}
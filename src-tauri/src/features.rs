@@ -0,0 +1,34 @@
+use crate::models::FeatureMatrix;
+
+/// Report which features work on this platform. Commands for unsupported features
+/// usually succeed without doing anything, so the frontend should check this instead.
+#[tauri::command]
+pub fn get_feature_support() -> FeatureMatrix {
+    let macos = cfg!(target_os = "macos");
+
+    FeatureMatrix {
+        notch_detection: macos,
+        haptics: macos,
+        dock_icon: macos,
+        window_corner_radius: macos,
+        space_behavior: macos,
+        calendar: macos,
+        reminders: macos,
+        media_controls: true,
+        media_seek: macos,
+        media_volume: macos,
+        mute: true,
+        volume_hud_suppression: macos,
+        focus_current_track: macos,
+        open_files: macos,
+        drag_out: true,
+        // The visualizer is simulated on every platform for now
+        audio_capture: false,
+        input_level: true,
+        // Depends on the display (DDC/CI, backlight device), so probe it
+        brightness: crate::display::get_display_brightness().is_some(),
+        night_shift: cfg!(all(target_os = "macos", feature = "night-shift")),
+        frontmost_app: true,
+        screen_lock_detection: true,
+    }
+}
@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 #[cfg(target_os = "macos")]
 use std::process::Command;
-use tauri::{command, AppHandle};
+use tauri::{command, AppHandle, Emitter, Manager};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct FileTrayItem {
@@ -46,6 +46,9 @@ pub fn save_file_tray(app_handle: AppHandle, files: Vec<FileTrayItem>) -> Result
     }
 
     conn.execute_batch("COMMIT;").map_err(|e| e.to_string())?;
+    drop(conn);
+
+    prune_dropped_images(&app_handle);
     Ok(())
 }
 
@@ -105,6 +108,227 @@ pub fn on_file_drop(path: String) {
     log::debug!("File dropped: {}", path);
 }
 
+/// What was dropped on the notch, as read from the drop's data transfer
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct DropPayload {
+    /// Raw pasteboard/data transfer types offered by the drop (e.g. "Files", "text/uri-list")
+    pub types: Vec<String>,
+    /// Local file paths
+    pub paths: Vec<String>,
+    /// Raw image data (e.g. an image dragged out of a browser)
+    pub image: Option<Vec<u8>>,
+    /// MIME type of `image`
+    pub image_type: Option<String>,
+    /// Contents of text/uri-list
+    pub uri_list: Option<String>,
+    /// Contents of text/plain
+    pub text: Option<String>,
+}
+
+/// What handle_drop did with a drop
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DropResult {
+    /// Files were added to the tray (already present ones are skipped)
+    Files { files: Vec<FileTrayItem> },
+    /// Image data was saved as a file and added to the tray
+    Image { file: FileTrayItem },
+    /// A link was recognized
+    Url { url: String },
+    /// Text was appended to the notes
+    Note { text: String },
+    /// Nothing usable was dropped
+    Ignored { types: Vec<String> },
+}
+
+/// Same type labels the frontend uses for tray items
+fn tray_mime_type(path: &std::path::Path) -> String {
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "png" | "jpg" | "jpeg" | "gif" | "webp" | "svg" => format!("image/{}", ext),
+        _ => "unknown".to_string(),
+    }
+}
+
+fn tray_item(path: &std::path::Path) -> Result<FileTrayItem, String> {
+    let metadata = fs::metadata(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let last_modified = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0);
+
+    Ok(FileTrayItem {
+        name: path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.to_string_lossy().into_owned()),
+        size: metadata.len() as i64,
+        path: path.to_string_lossy().into_owned(),
+        mime_type: tray_mime_type(path),
+        last_modified,
+    })
+}
+
+/// Append items to the tray (skipping paths already in it) and emit `file-tray-updated`
+fn add_to_file_tray(app_handle: &AppHandle, items: &[FileTrayItem]) -> Result<(), String> {
+    let conn = get_connection(app_handle).map_err(|e| e.to_string())?;
+
+    conn.execute_batch("BEGIN TRANSACTION;")
+        .map_err(|e| e.to_string())?;
+
+    for file in items {
+        let sql = "INSERT OR IGNORE INTO file_tray (path, name, size, mime_type, last_modified) VALUES (?1, ?2, ?3, ?4, ?5)";
+        log_sql(&format!("{} [{}]", sql, file.path));
+
+        if let Err(e) = conn.execute(
+            sql,
+            rusqlite::params![
+                file.path,
+                file.name,
+                file.size,
+                file.mime_type,
+                file.last_modified
+            ],
+        ) {
+            let _ = conn.execute_batch("ROLLBACK;");
+            return Err(e.to_string());
+        }
+    }
+
+    conn.execute_batch("COMMIT;").map_err(|e| e.to_string())?;
+
+    let _ = app_handle.emit("file-tray-updated", load_file_tray(app_handle.clone())?);
+    Ok(())
+}
+
+/// The app's "Dropped" folder, where image data dropped on the notch is saved
+fn dropped_dir(app_handle: &AppHandle) -> Result<std::path::PathBuf, String> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .map(|dir| dir.join("Dropped"))
+        .map_err(|e| e.to_string())
+}
+
+/// Delete saved drops that are no longer in the file tray
+fn prune_dropped_images(app_handle: &AppHandle) {
+    let entries = match dropped_dir(app_handle)
+        .and_then(|dir| fs::read_dir(dir).map_err(|e| e.to_string()))
+    {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    let in_tray: std::collections::HashSet<String> = match load_file_tray(app_handle.clone()) {
+        Ok(files) => files.into_iter().map(|f| f.path).collect(),
+        Err(e) => {
+            log::warn!("Skipping dropped image cleanup: {}", e);
+            return;
+        }
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_file() || in_tray.contains(path.to_string_lossy().as_ref()) {
+            continue;
+        }
+        if let Err(e) = fs::remove_file(&path) {
+            log::warn!("Failed to remove dropped image {:?}: {}", path, e);
+        }
+    }
+}
+
+/// Write dropped image data into the app's "Dropped" folder
+fn save_dropped_image(
+    app_handle: &AppHandle,
+    data: &[u8],
+    mime_type: Option<&str>,
+) -> Result<std::path::PathBuf, String> {
+    prune_dropped_images(app_handle);
+
+    let dir = dropped_dir(app_handle)?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let ext = match mime_type.unwrap_or("") {
+        "image/jpeg" | "image/jpg" => "jpg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "image/svg+xml" => "svg",
+        _ => "png",
+    };
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let path = dir.join(format!("Dropped Image {}.{}", stamp, ext));
+
+    fs::write(&path, data).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+/// A single http(s) link, from a uri-list (first non-comment line) or bare text
+fn dropped_url(payload: &DropPayload) -> Option<String> {
+    let from_list = payload.uri_list.as_deref().and_then(|list| {
+        list.lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'))
+    });
+    let candidate = from_list.or(payload.text.as_deref().map(str::trim))?;
+    if candidate.contains(char::is_whitespace) {
+        return None;
+    }
+
+    match reqwest::Url::parse(candidate) {
+        Ok(url) if url.scheme() == "http" || url.scheme() == "https" => Some(url.to_string()),
+        _ => None,
+    }
+}
+
+/// Route a drop by what it carries: files go to the tray, image data is saved as a tray file,
+/// links are recognized and plain text is appended to the notes
+#[command]
+pub fn handle_drop(app_handle: AppHandle, payload: DropPayload) -> Result<DropResult, String> {
+    log::debug!("Drop with types {:?}", payload.types);
+
+    if !payload.paths.is_empty() {
+        let files = payload
+            .paths
+            .iter()
+            .map(|path| tray_item(std::path::Path::new(path)))
+            .collect::<Result<Vec<_>, _>>()?;
+        add_to_file_tray(&app_handle, &files)?;
+        return Ok(DropResult::Files { files });
+    }
+
+    if let Some(data) = payload.image.as_deref().filter(|data| !data.is_empty()) {
+        let path = save_dropped_image(&app_handle, data, payload.image_type.as_deref())?;
+        let file = tray_item(&path)?;
+        add_to_file_tray(&app_handle, std::slice::from_ref(&file))?;
+        return Ok(DropResult::Image { file });
+    }
+
+    if let Some(url) = dropped_url(&payload) {
+        return Ok(DropResult::Url { url });
+    }
+
+    match payload.text.as_deref().map(str::trim) {
+        Some(text) if !text.is_empty() => {
+            crate::notes::append_to_notes(&app_handle, text)?;
+            Ok(DropResult::Note {
+                text: text.to_string(),
+            })
+        }
+        _ => Ok(DropResult::Ignored {
+            types: payload.types,
+        }),
+    }
+}
+
 #[command]
 pub fn resolve_path(path: String) -> Result<String, String> {
     fs::canonicalize(&path)
@@ -0,0 +1,262 @@
+use crate::models::AppInfo;
+use log;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter};
+
+/// Minimum time between frontmost app checks in the watcher
+const WATCH_INTERVAL_MS: u64 = 500;
+
+static WATCHER_ENABLED: AtomicBool = AtomicBool::new(false);
+static WATCHER_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// Get the currently focused application
+#[tauri::command]
+pub fn get_frontmost_app() -> Option<AppInfo> {
+    #[cfg(target_os = "macos")]
+    {
+        use objc2::rc::Retained;
+        use objc2::runtime::AnyObject;
+        use objc2::*;
+        use objc2_foundation::NSString;
+
+        unsafe {
+            let workspace: *mut AnyObject = msg_send![class!(NSWorkspace), sharedWorkspace];
+            if workspace.is_null() {
+                return None;
+            }
+
+            let app: *mut AnyObject = msg_send![workspace, frontmostApplication];
+            if app.is_null() {
+                return None;
+            }
+
+            let name: Option<Retained<NSString>> = msg_send![app, localizedName];
+            let bundle_id: Option<Retained<NSString>> = msg_send![app, bundleIdentifier];
+
+            Some(AppInfo {
+                name: name.map(|n| n.to_string()).unwrap_or_default(),
+                bundle_id: bundle_id.map(|b| b.to_string()),
+            })
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use windows::core::PWSTR;
+        use windows::Win32::Foundation::{CloseHandle, FALSE};
+        use windows::Win32::System::Threading::{
+            OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+            PROCESS_QUERY_LIMITED_INFORMATION,
+        };
+        use windows::Win32::UI::WindowsAndMessaging::{
+            GetForegroundWindow, GetWindowThreadProcessId,
+        };
+
+        unsafe {
+            let hwnd = GetForegroundWindow();
+            if hwnd.0 == 0 {
+                return None;
+            }
+
+            let mut pid = 0u32;
+            GetWindowThreadProcessId(hwnd, Some(&mut pid));
+            if pid == 0 {
+                return None;
+            }
+
+            let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, FALSE, pid).ok()?;
+            let mut buffer = [0u16; 1024];
+            let mut size = buffer.len() as u32;
+            let result = QueryFullProcessImageNameW(
+                process,
+                PROCESS_NAME_WIN32,
+                PWSTR(buffer.as_mut_ptr()),
+                &mut size,
+            );
+            let _ = CloseHandle(process);
+            result.ok()?;
+
+            let path = String::from_utf16_lossy(&buffer[..size as usize]);
+            let name = std::path::Path::new(&path)
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+
+            Some(AppInfo {
+                name,
+                bundle_id: Some(path),
+            })
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use std::process::Command;
+
+        // X11 only: read _NET_ACTIVE_WINDOW, then that window's WM_CLASS
+        let output = Command::new("xprop")
+            .args(["-root", "_NET_ACTIVE_WINDOW"])
+            .output()
+            .ok()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let window_id = stdout.split_whitespace().last()?.to_string();
+        if !window_id.starts_with("0x") || window_id == "0x0" {
+            return None;
+        }
+
+        let output = Command::new("xprop")
+            .args(["-id", &window_id, "WM_CLASS"])
+            .output()
+            .ok()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        // WM_CLASS(STRING) = "instance", "Class"
+        let values: Vec<String> = stdout
+            .split('=')
+            .nth(1)?
+            .split(',')
+            .map(|v| v.trim().trim_matches('"').to_string())
+            .collect();
+
+        Some(AppInfo {
+            name: values.get(1).or(values.first())?.clone(),
+            bundle_id: values.first().cloned(),
+        })
+    }
+}
+
+/// Text currently selected in the focused element of the frontmost app (macOS).
+/// Needs Accessibility permission; returns None without it, when nothing is selected
+/// or on other platforms.
+#[tauri::command]
+pub fn get_selected_text() -> Option<String> {
+    #[cfg(target_os = "macos")]
+    {
+        use core_foundation::base::{CFType, CFTypeRef, TCFType};
+        use core_foundation::string::{CFString, CFStringRef};
+
+        #[link(name = "ApplicationServices", kind = "framework")]
+        extern "C" {
+            fn AXUIElementCreateSystemWide() -> CFTypeRef;
+            fn AXUIElementCopyAttributeValue(
+                element: CFTypeRef,
+                attribute: CFStringRef,
+                value: *mut CFTypeRef,
+            ) -> i32;
+        }
+
+        unsafe fn copy_attribute(element: &CFType, attribute: &str) -> Option<CFType> {
+            let attribute = CFString::new(attribute);
+            let mut value: CFTypeRef = std::ptr::null();
+            let error = AXUIElementCopyAttributeValue(
+                element.as_CFTypeRef(),
+                attribute.as_concrete_TypeRef(),
+                &mut value,
+            );
+            if error != 0 || value.is_null() {
+                return None;
+            }
+            Some(CFType::wrap_under_create_rule(value))
+        }
+
+        unsafe {
+            let system = AXUIElementCreateSystemWide();
+            if system.is_null() {
+                return None;
+            }
+            let system = CFType::wrap_under_create_rule(system);
+
+            copy_attribute(&system, "AXFocusedUIElement")
+                .and_then(|focused| copy_attribute(&focused, "AXSelectedText"))
+                .and_then(|text| text.downcast::<CFString>())
+                .map(|text| text.to_string())
+                .filter(|text| !text.trim().is_empty())
+        }
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        None
+    }
+}
+
+/// Enable or disable `frontmost-app-changed` events (polled every 500ms). The watcher keeps
+/// running without them while app expand rules exist.
+#[tauri::command]
+pub fn set_frontmost_app_watcher(app_handle: AppHandle, enabled: bool) {
+    WATCHER_ENABLED.store(enabled, Ordering::Relaxed);
+
+    if enabled {
+        ensure_watcher_running(app_handle);
+    }
+}
+
+/// Start the watcher at launch if any per-app expand rules exist
+pub fn setup_frontmost_monitoring(app_handle: &AppHandle) {
+    if crate::window::has_app_expand_rules(app_handle) {
+        ensure_watcher_running(app_handle.clone());
+    }
+}
+
+/// Whether the frontmost app is this app: matched by bundle identifier (macOS), executable
+/// path (Windows) or executable name (Linux WM_CLASS, unbundled dev builds)
+fn is_own_app(app_handle: &AppHandle, app: &AppInfo) -> bool {
+    let exe = std::env::current_exe().ok();
+    let exe_name = exe
+        .as_ref()
+        .and_then(|path| path.file_stem())
+        .map(|stem| stem.to_string_lossy().into_owned());
+
+    let matches_exe = |value: &str| {
+        exe.as_ref()
+            .is_some_and(|path| std::path::Path::new(value) == path.as_path())
+            || exe_name
+                .as_ref()
+                .is_some_and(|name| name.eq_ignore_ascii_case(value))
+    };
+
+    app.bundle_id
+        .as_deref()
+        .is_some_and(|id| id == app_handle.config().identifier || matches_exe(id))
+        || matches_exe(&app.name)
+}
+
+/// Spawn the polling thread once. It runs while events are wanted or app expand rules exist.
+pub fn ensure_watcher_running(app_handle: AppHandle) {
+    if WATCHER_STARTED.swap(true, Ordering::Relaxed) {
+        return;
+    }
+
+    std::thread::spawn(move || {
+        log::info!("Starting frontmost app watcher");
+        let mut last: Option<AppInfo> = None;
+
+        loop {
+            std::thread::sleep(std::time::Duration::from_millis(WATCH_INTERVAL_MS));
+
+            let emit_events = WATCHER_ENABLED.load(Ordering::Relaxed);
+            let has_rules = crate::window::has_app_expand_rules(&app_handle);
+
+            if (!emit_events && !has_rules) || crate::power::is_monitoring_paused() {
+                // Re-emit after resuming so listeners get the current app
+                last = None;
+                continue;
+            }
+
+            let current = get_frontmost_app();
+            if current.is_some() && current != last {
+                if emit_events {
+                    let _ = app_handle.emit("frontmost-app-changed", &current);
+                }
+                // Clicking into the notch makes us frontmost; that must not collapse it
+                if !current
+                    .as_ref()
+                    .is_some_and(|app| is_own_app(&app_handle, app))
+                {
+                    crate::window::apply_app_expand_rule(&app_handle, current.as_ref());
+                }
+                last = current;
+            }
+        }
+    });
+}
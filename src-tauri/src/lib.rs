@@ -1,11 +1,31 @@
+pub mod alarms;
 pub mod audio;
+pub mod audio_devices;
+pub mod cache;
 pub mod calendar;
+pub mod connectivity;
+pub mod dashboard;
 pub mod database;
+pub mod display;
+pub mod features;
 pub mod files;
+pub mod frontmost;
+pub mod logging;
+pub mod microphone;
 pub mod models;
+pub mod musicbrainz;
 pub mod notes;
+pub mod permissions;
+pub mod play_history;
 pub mod plugins;
+pub mod power;
+pub mod quiet_hours;
+pub mod scenes;
+pub mod scrobble;
+pub mod toast;
 pub mod utils;
+pub mod wallpaper;
+pub mod waveform;
 pub mod widgets;
 pub mod window;
 
@@ -18,58 +38,203 @@ pub fn run() {
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_drag::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(|app, _shortcut, event| {
+                    if event.state() == tauri_plugin_global_shortcut::ShortcutState::Pressed {
+                        window::handle_overlay_shortcut(app);
+                    }
+                })
+                .build(),
+        )
         .invoke_handler(tauri::generate_handler![
             window::get_notch_info,
+            window::refresh_screen_info,
+            window::get_raw_screen_metrics,
+            window::detect_notch_by_hardware,
+            window::compute_collapsed_width,
+            window::get_notch_geometry_overrides,
+            window::set_notch_geometry_override,
             window::position_at_notch,
             window::fit_to_notch,
             window::set_click_through,
             window::activate_window,
             window::deactivate_window,
+            window::ensure_main_window_visible,
+            window::toggle_overlay_visibility,
+            window::set_overlay_toggle_shortcut,
+            window::get_overlay_toggle_shortcut,
+            window::validate_shortcut,
+            window::set_avoid_focus_steal,
+            window::get_avoid_focus_steal,
+            window::set_hover_activation_mode,
+            window::get_hover_activation_mode,
+            window::set_dock_icon_visible,
+            window::get_dock_icon_visible,
+            window::set_window_corner_radius,
+            window::get_window_corner_radius,
+            window::set_space_behavior,
+            window::get_space_behavior,
             window::trigger_haptics,
             window::update_ui_bounds,
+            window::set_hover_profiling,
+            window::get_hover_latency_stats,
+            window::set_app_expand_rule,
+            window::remove_app_expand_rule,
+            window::get_app_expand_rules,
             window::get_window_settings,
             window::update_window_settings,
+            window::set_reveal_mode,
             window::open_settings,
+            window::get_click_action,
+            window::set_click_action,
+            window::notch_clicked,
+            power::set_auto_hide_when_locked,
+            power::get_battery_status,
+            power::set_battery_saver_policy,
+            power::get_battery_saver_policy,
+            power::get_auto_hide_when_locked,
+            scenes::save_scene,
+            scenes::list_scenes,
+            scenes::apply_scene,
+            scenes::delete_scene,
             audio::get_now_playing,
+            audio::get_all_playing_sources,
+            audio::subscribe_now_playing,
+            audio::unsubscribe_now_playing,
+            audio::subscribe_playback_state,
+            audio::unsubscribe_playback_state,
             audio::get_audio_levels,
+            audio::get_visualizer_snapshot,
+            audio::get_visualizer_config,
+            audio::get_visualizer_bands,
+            audio::set_visualizer_crossfade_ms,
+            audio::get_visualizer_crossfade_ms,
             audio::media_play_pause,
             audio::media_next_track,
             audio::media_previous_track,
             audio::media_seek,
+            audio::media_skip_forward,
+            audio::media_skip_backward,
+            audio::set_skip_seconds,
+            audio::get_skip_seconds,
+            audio::media_command_to,
+            audio::play_queue_item,
+            audio::list_playback_targets,
+            audio::set_playback_target,
+            audio::media_set_volume,
+            audio::get_mute_state,
+            audio::toggle_mute,
+            audio::set_suppress_volume_hud,
+            audio::get_suppress_volume_hud,
+            audio::set_track_ending_threshold,
+            audio::set_visualizer_source,
+            audio::get_visualizer_source,
+            audio_devices::subscribe_device_events,
+            audio_devices::unsubscribe_device_events,
+            microphone::get_input_level,
+            microphone::subscribe_input_level,
+            microphone::unsubscribe_input_level,
             audio::activate_media_app,
+            audio::get_media_source_icon,
+            audio::focus_current_track,
+            audio::get_high_res_artwork,
+            audio::set_artwork_max_dimension,
+            audio::get_artwork_max_dimension,
+            musicbrainz::enrich_track_metadata,
+            musicbrainz::set_metadata_enrichment_enabled,
+            musicbrainz::get_metadata_enrichment_enabled,
+            audio::get_available_media_apps,
+            scrobble::set_lastfm_session,
+            scrobble::set_scrobbling_enabled,
+            play_history::export_play_history_csv,
+            waveform::get_track_waveform,
+            cache::clear_all_caches,
+            connectivity::check_connectivity,
+            wallpaper::get_wallpaper_colors,
+            logging::set_log_level,
+            logging::get_log_level,
+            logging::get_log_file_path,
+            logging::open_log_file,
             database::db_execute,
             database::db_select,
+            database::get_slow_queries,
             notes::save_notes,
             notes::load_notes,
+            notes::quick_note_from_selection,
+            toast::show_toast,
+            quiet_hours::set_quiet_hours,
+            quiet_hours::get_quiet_hours,
+            quiet_hours::is_quiet_hours_active,
             calendar::request_calendar_access,
             calendar::get_upcoming_events,
+            calendar::list_calendars,
+            calendar::set_calendar_order,
+            calendar::export_events_ics,
             calendar::get_reminders,
+            calendar::get_reminders_grouped,
+            calendar::schedule_reminder_notifications,
+            alarms::get_next_system_alarm,
+            dashboard::get_dashboard,
+            calendar::get_reminder_lists,
             calendar::complete_reminder,
             calendar::create_reminder,
             calendar::create_calendar_event,
             calendar::open_calendar_event,
+            calendar::pin_event,
+            calendar::unpin_event,
+            calendar::get_pinned_event,
+            calendar::format_time_until,
+            calendar::set_starting_soon_minutes,
+            calendar::get_starting_soon_minutes,
             calendar::open_calendar_app,
             calendar::open_reminders_app,
             calendar::open_privacy_settings,
+            permissions::get_all_permissions,
+            permissions::request_all_permissions,
+            permissions::open_automation_settings,
+            permissions::get_accessibility_permission,
+            permissions::request_accessibility_permission,
+            permissions::open_accessibility_settings,
             files::open_file,
             files::reveal_file,
             files::on_file_drop,
+            files::handle_drop,
             files::save_file_tray,
             files::load_file_tray,
             files::resolve_path,
             files::save_drag_icon,
             window::get_system_accent_color,
+            window::capture_notch_image,
+            display::get_night_shift,
+            display::set_night_shift,
+            display::get_display_brightness,
+            display::set_display_brightness,
+            display::get_display_capabilities,
+            features::get_feature_support,
+            frontmost::get_frontmost_app,
+            frontmost::set_frontmost_app_watcher,
+            frontmost::get_selected_text,
             widgets::save_widget_state,
             widgets::load_widget_state,
+            widgets::get_widget_config,
+            widgets::set_widget_config,
             widgets::run_speed_test,
+            widgets::get_speed_test_history,
             plugins::scan_plugins_directory,
             plugins::read_plugin_bundle,
+            plugins::get_plugin_render_spec,
+            plugins::inspect_plugin,
+            plugins::set_plugins_enabled_by_category,
             plugins::get_plugins_directory_path,
             plugins::install_plugin_from_folder,
             plugins::install_plugin_from_git,
+            plugins::install_plugin_from_zip,
             plugins::delete_plugin
         ])
         .setup(|app| {
+            logging::init_logging(app.handle());
+
             // Auto-position and resize window to match notch on startup
             if let Some(window) = app.get_webview_window("main") {
                 let window_clone = window.clone();
@@ -81,6 +246,9 @@ pub fn run() {
                     tauri::WindowEvent::DragDrop(tauri::DragDropEvent::Drop { paths, .. }) => {
                         let _ = window_clone.emit("file-drop-event", paths);
                     }
+                    tauri::WindowEvent::ScaleFactorChanged { .. } => {
+                        window::invalidate_screen_info();
+                    }
                     _ => {}
                 });
 
@@ -111,10 +279,12 @@ pub fn run() {
                                 // This allows positioning in the notch area
                                 let _: () = msg_send![ns_win, setLevel: 25_i64];
 
-                                // Also set collection behavior to allow appearing on all spaces
-                                // NSWindowCollectionBehaviorCanJoinAllSpaces = 1 << 0
-                                // NSWindowCollectionBehaviorStationary = 1 << 4
-                                let _: () = msg_send![ns_win, setCollectionBehavior: 17_u64];
+                                // Also set collection behavior, appearing on all spaces unless
+                                // the user chose the current space only (set_space_behavior)
+                                let _: () = msg_send![
+                                    ns_win,
+                                    setCollectionBehavior: window::main_window_collection_behavior()
+                                ];
 
                                 // Remove window shadow to prevent border effect
                                 let _: () = msg_send![ns_win, setHasShadow: 0];
@@ -143,6 +313,13 @@ pub fn run() {
                 #[cfg(not(target_os = "windows"))]
                 let _ = window.set_decorations(false);
 
+                // Apply the user's explicit dock icon preference, if any
+                window::initialize_dock_icon_preference(app.handle());
+
+                // Round the native window to match the UI, if a radius was saved
+                window::initialize_window_corner_radius(app.handle());
+                window::initialize_space_behavior(app.handle());
+
                 // Enable click-through by default (no notification showing)
                 let _ = window.set_ignore_cursor_events(true);
 
@@ -153,8 +330,33 @@ pub fn run() {
                 // Initial positioning and sizing - window is always fixed size
                 let _ = window::setup_fixed_window_size(&window);
 
+                #[cfg(target_os = "macos")]
+                window::setup_display_change_monitoring(app.handle());
+
                 window::setup_mouse_monitoring(app.handle().clone());
+                window::setup_main_window_watchdog(app.handle().clone());
+                window::setup_overlay_shortcut(app.handle());
+                frontmost::setup_frontmost_monitoring(app.handle());
+                calendar::initialize_event_status_thresholds(app.handle());
+                calendar::initialize_calendar_order(app.handle());
+                calendar::setup_calendar_change_monitoring(app.handle());
                 audio::setup_audio_monitoring(app.handle().clone());
+
+                #[cfg(target_os = "linux")]
+                audio::setup_mpris_monitoring(app.handle().clone());
+
+                scrobble::initialize_scrobbler(app.handle());
+                connectivity::setup_connectivity_monitoring(app.handle().clone());
+                wallpaper::setup_wallpaper_watcher(app.handle().clone());
+
+                quiet_hours::initialize_quiet_hours(app.handle());
+
+                // Hide the overlay and idle monitoring while locked or asleep
+                power::initialize_auto_hide_preference(app.handle());
+                power::setup_screen_state_monitoring(app.handle().clone());
+
+                // Pause non-essential work when the battery runs low
+                power::setup_battery_saver_monitoring(app.handle().clone());
             }
             Ok(())
         })
@@ -0,0 +1,226 @@
+use crate::database::{get_connection, log_sql};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Manager};
+
+const LOG_FILE_NAME: &str = "overdone.log";
+/// Rotate once the active log grows past this size
+const MAX_LOG_SIZE: u64 = 5 * 1024 * 1024;
+/// Rotated files kept as overdone.log.1 .. overdone.log.N
+const MAX_ROTATED_LOGS: u32 = 3;
+
+struct FileLogger {
+    path: PathBuf,
+    file: Mutex<Option<File>>,
+}
+
+static LOGGER: OnceLock<FileLogger> = OnceLock::new();
+
+fn default_level() -> log::LevelFilter {
+    if cfg!(debug_assertions) {
+        log::LevelFilter::Debug
+    } else {
+        log::LevelFilter::Info
+    }
+}
+
+fn parse_level(level: &str) -> Option<log::LevelFilter> {
+    match level.to_lowercase().as_str() {
+        "off" => Some(log::LevelFilter::Off),
+        "error" => Some(log::LevelFilter::Error),
+        "warn" | "warning" => Some(log::LevelFilter::Warn),
+        "info" => Some(log::LevelFilter::Info),
+        "debug" => Some(log::LevelFilter::Debug),
+        "trace" => Some(log::LevelFilter::Trace),
+        _ => None,
+    }
+}
+
+/// UTC timestamp like 2024-01-31 09:30:00.123
+fn timestamp() -> String {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default();
+    let secs = now.as_secs() as i64;
+    let (year, month, day) = crate::calendar::civil_from_days(secs.div_euclid(86_400));
+    let time = secs.rem_euclid(86_400);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:03}",
+        year,
+        month,
+        day,
+        time / 3600,
+        (time % 3600) / 60,
+        time % 60,
+        now.subsec_millis()
+    )
+}
+
+fn open_for_append(path: &Path) -> Option<File> {
+    OpenOptions::new().create(true).append(true).open(path).ok()
+}
+
+/// Shift overdone.log -> .1 -> .2 ..., dropping the oldest
+fn rotate_logs(path: &Path) {
+    let rotated = |n: u32| PathBuf::from(format!("{}.{}", path.display(), n));
+
+    let _ = fs::remove_file(rotated(MAX_ROTATED_LOGS));
+    for n in (1..MAX_ROTATED_LOGS).rev() {
+        let _ = fs::rename(rotated(n), rotated(n + 1));
+    }
+    let _ = fs::rename(path, rotated(1));
+}
+
+impl log::Log for FileLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let line = format!(
+            "{} {:<5} [{}] {}\n",
+            timestamp(),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+
+        #[cfg(debug_assertions)]
+        eprint!("{}", line);
+
+        let mut file = match self.file.lock() {
+            Ok(guard) => guard,
+            Err(_) => return,
+        };
+
+        let too_large = file
+            .as_ref()
+            .and_then(|f| f.metadata().ok())
+            .map(|m| m.len() >= MAX_LOG_SIZE)
+            .unwrap_or(false);
+        if too_large {
+            *file = None;
+            rotate_logs(&self.path);
+        }
+
+        if file.is_none() {
+            *file = open_for_append(&self.path);
+        }
+        if let Some(f) = file.as_mut() {
+            let _ = f.write_all(line.as_bytes());
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            if let Some(f) = file.as_mut() {
+                let _ = f.flush();
+            }
+        }
+    }
+}
+
+fn log_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .map(|dir| dir.join("logs"))
+        .map_err(|e| e.to_string())
+}
+
+/// Install the file logger and apply the persisted log level
+pub fn init_logging(app_handle: &AppHandle) {
+    let dir = match log_dir(app_handle) {
+        Ok(dir) => dir,
+        Err(e) => {
+            eprintln!("Failed to resolve log directory: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = fs::create_dir_all(&dir) {
+        eprintln!("Failed to create log directory: {}", e);
+        return;
+    }
+
+    let path = dir.join(LOG_FILE_NAME);
+    let logger = LOGGER.get_or_init(|| FileLogger {
+        file: Mutex::new(open_for_append(&path)),
+        path,
+    });
+
+    if log::set_logger(logger).is_err() {
+        return;
+    }
+
+    let level = get_connection(app_handle)
+        .ok()
+        .and_then(|conn| {
+            let sql = "SELECT value FROM settings WHERE key = 'log_level'";
+            log_sql(sql);
+            conn.query_row(sql, [], |row| row.get::<_, String>(0)).ok()
+        })
+        .and_then(|value| parse_level(&value))
+        .unwrap_or_else(default_level);
+    log::set_max_level(level);
+
+    log::info!("Logging to {} at level {}", logger.path.display(), level);
+}
+
+/// Change the log level at runtime (off/error/warn/info/debug/trace), persisted across launches
+#[tauri::command]
+pub fn set_log_level(app_handle: AppHandle, level: String) -> Result<(), String> {
+    let filter = parse_level(&level).ok_or(format!("Invalid log level: {}", level))?;
+    log::set_max_level(filter);
+
+    let conn = get_connection(&app_handle).map_err(|e| e.to_string())?;
+    let sql = "INSERT OR REPLACE INTO settings (key, value) VALUES ('log_level', ?1)";
+    log_sql(sql);
+    conn.execute(sql, rusqlite::params![filter.to_string().to_lowercase()])
+        .map_err(|e| e.to_string())?;
+
+    log::info!("Log level set to {}", filter);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_log_level() -> String {
+    log::max_level().to_string().to_lowercase()
+}
+
+/// Path of the active log file
+#[tauri::command]
+pub fn get_log_file_path(app_handle: AppHandle) -> Result<String, String> {
+    match LOGGER.get() {
+        Some(logger) => Ok(logger.path.to_string_lossy().into_owned()),
+        None => {
+            log_dir(&app_handle).map(|dir| dir.join(LOG_FILE_NAME).to_string_lossy().into_owned())
+        }
+    }
+}
+
+/// Open the log file in the default viewer
+#[tauri::command]
+pub fn open_log_file(app_handle: AppHandle) -> Result<(), String> {
+    log::logger().flush();
+    let path = get_log_file_path(app_handle)?;
+    if !Path::new(&path).exists() {
+        return Err("No log file yet".to_string());
+    }
+
+    #[cfg(target_os = "macos")]
+    let mut command = std::process::Command::new("open");
+    #[cfg(target_os = "windows")]
+    let mut command = std::process::Command::new("explorer");
+    #[cfg(target_os = "linux")]
+    let mut command = std::process::Command::new("xdg-open");
+
+    command.arg(&path).spawn().map_err(|e| e.to_string())?;
+    Ok(())
+}
@@ -0,0 +1,412 @@
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use tauri::{AppHandle, Emitter};
+
+/// Sample rate requested from the input device where we get to choose it
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+const SAMPLE_RATE: u32 = 16_000;
+
+/// Samples per level reading (100ms at SAMPLE_RATE)
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+const SAMPLES_PER_READING: usize = (SAMPLE_RATE / 10) as usize;
+
+/// Bumped on every subscribe/unsubscribe, a capture keeps running while its generation is current
+static INPUT_SUBSCRIPTION: AtomicU64 = AtomicU64::new(0);
+
+/// Generation of the capture that is currently delivering levels, 0 when none is
+static ACTIVE_CAPTURE: AtomicU64 = AtomicU64::new(0);
+
+/// Latest level from the subscribed capture (f32 bits)
+static INPUT_LEVEL: AtomicU32 = AtomicU32::new(0);
+
+/// Root mean square of a block of samples, 0.0 (silence) to 1.0 (full scale)
+fn rms(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let sum: f32 = samples.iter().map(|s| s * s).sum();
+    (sum / samples.len() as f32).sqrt().min(1.0)
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use coreaudio_sys::*;
+    use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
+    #[link(name = "AVFoundation", kind = "framework")]
+    extern "C" {
+        static AVMediaTypeAudio: *const objc2::runtime::AnyObject;
+    }
+
+    /// Levels handed from the AudioQueue thread to the capture loop
+    struct Shared {
+        level: AtomicU32,
+        readings: AtomicU64,
+    }
+
+    unsafe extern "C" fn on_input(
+        user_data: *mut std::ffi::c_void,
+        queue: AudioQueueRef,
+        buffer: AudioQueueBufferRef,
+        _start_time: *const AudioTimeStamp,
+        _packet_count: u32,
+        _packet_descriptions: *const AudioStreamPacketDescription,
+    ) {
+        let shared = &*(user_data as *const Shared);
+        let samples = std::slice::from_raw_parts(
+            (*buffer).mAudioData as *const f32,
+            (*buffer).mAudioDataByteSize as usize / std::mem::size_of::<f32>(),
+        );
+        shared
+            .level
+            .store(super::rms(samples).to_bits(), Ordering::Relaxed);
+        shared.readings.fetch_add(1, Ordering::Release);
+
+        AudioQueueEnqueueBuffer(queue, buffer, 0, std::ptr::null());
+    }
+
+    /// Fail early when microphone access was denied; when it hasn't been asked yet,
+    /// starting the queue shows the system prompt
+    fn check_permission() -> Result<(), String> {
+        use objc2::*;
+
+        // AVAuthorizationStatusRestricted = 1, AVAuthorizationStatusDenied = 2
+        let status: isize = unsafe {
+            msg_send![class!(AVCaptureDevice), authorizationStatusForMediaType: AVMediaTypeAudio]
+        };
+        match status {
+            1 | 2 => {
+                Err("PermissionDenied: microphone access is off in Privacy & Security".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn capture_input(
+        keep_running: &dyn Fn() -> bool,
+        on_level: &mut dyn FnMut(f32),
+    ) -> Result<(), String> {
+        check_permission()?;
+
+        let format = AudioStreamBasicDescription {
+            mSampleRate: super::SAMPLE_RATE as f64,
+            mFormatID: kAudioFormatLinearPCM as u32,
+            mFormatFlags: (kAudioFormatFlagIsFloat | kAudioFormatFlagIsPacked) as u32,
+            mBytesPerPacket: 4,
+            mFramesPerPacket: 1,
+            mBytesPerFrame: 4,
+            mChannelsPerFrame: 1,
+            mBitsPerChannel: 32,
+            mReserved: 0,
+        };
+        let shared = Box::new(Shared {
+            level: AtomicU32::new(0),
+            readings: AtomicU64::new(0),
+        });
+
+        unsafe {
+            let mut queue: AudioQueueRef = std::ptr::null_mut();
+            let status = AudioQueueNewInput(
+                &format,
+                Some(on_input),
+                &*shared as *const Shared as *mut std::ffi::c_void,
+                std::ptr::null_mut(),
+                std::ptr::null(),
+                0,
+                &mut queue,
+            );
+            if status != 0 {
+                return Err(format!("Failed to open the input device ({})", status));
+            }
+
+            // A few buffers in flight so a slow consumer doesn't drop audio
+            for _ in 0..3 {
+                let mut buffer: AudioQueueBufferRef = std::ptr::null_mut();
+                let size = (super::SAMPLES_PER_READING * std::mem::size_of::<f32>()) as u32;
+                if AudioQueueAllocateBuffer(queue, size, &mut buffer) == 0 {
+                    AudioQueueEnqueueBuffer(queue, buffer, 0, std::ptr::null());
+                }
+            }
+
+            let status = AudioQueueStart(queue, std::ptr::null());
+            if status != 0 {
+                AudioQueueDispose(queue, 1);
+                return Err(format!("Failed to start input capture ({})", status));
+            }
+
+            let mut seen = 0;
+            while keep_running() {
+                std::thread::sleep(std::time::Duration::from_millis(20));
+                let readings = shared.readings.load(Ordering::Acquire);
+                if readings != seen {
+                    seen = readings;
+                    on_level(f32::from_bits(shared.level.load(Ordering::Relaxed)));
+                }
+            }
+
+            // Synchronous stop and dispose, no callback can touch `shared` afterwards
+            AudioQueueStop(queue, 1);
+            AudioQueueDispose(queue, 1);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod windows_capture {
+    use windows::Win32::Foundation::E_ACCESSDENIED;
+    use windows::Win32::Media::Audio::{
+        eCapture, eConsole, IAudioCaptureClient, IAudioClient, IMMDeviceEnumerator,
+        MMDeviceEnumerator, AUDCLNT_BUFFERFLAGS_SILENT, AUDCLNT_SHAREMODE_SHARED, WAVEFORMATEX,
+    };
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CoTaskMemFree, CLSCTX_ALL, COINIT_MULTITHREADED,
+    };
+
+    fn map_error(e: windows::core::Error) -> String {
+        if e.code() == E_ACCESSDENIED {
+            "PermissionDenied: microphone access is off in Windows privacy settings".to_string()
+        } else {
+            e.to_string()
+        }
+    }
+
+    pub fn capture_input(
+        keep_running: &dyn Fn() -> bool,
+        on_level: &mut dyn FnMut(f32),
+    ) -> Result<(), String> {
+        unsafe {
+            // Fails harmlessly if COM is already initialized on this thread
+            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+
+            let enumerator: IMMDeviceEnumerator =
+                CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL).map_err(map_error)?;
+            let device = enumerator
+                .GetDefaultAudioEndpoint(eCapture, eConsole)
+                .map_err(|_| "No input device".to_string())?;
+            let client: IAudioClient = device.Activate(CLSCTX_ALL, None).map_err(map_error)?;
+
+            // Shared mode captures in the engine's mix format, 32-bit float in practice
+            let mix_format = client.GetMixFormat().map_err(map_error)?;
+            let format: WAVEFORMATEX = std::ptr::read_unaligned(mix_format);
+            let channels = format.nChannels.max(1) as usize;
+            let bits_per_sample = format.wBitsPerSample;
+            let samples_per_reading = format.nSamplesPerSec as usize / 10 * channels;
+
+            // 100ms buffer, in 100ns units
+            let initialized =
+                client.Initialize(AUDCLNT_SHAREMODE_SHARED, 0, 1_000_000, 0, mix_format, None);
+            CoTaskMemFree(Some(mix_format as *const std::ffi::c_void));
+            initialized.map_err(map_error)?;
+
+            let capture: IAudioCaptureClient = client.GetService().map_err(map_error)?;
+            client.Start().map_err(map_error)?;
+
+            let mut pending: Vec<f32> = Vec::with_capacity(samples_per_reading);
+            let mut result = Ok(());
+            'capture: while keep_running() {
+                std::thread::sleep(std::time::Duration::from_millis(20));
+
+                loop {
+                    match capture.GetNextPacketSize() {
+                        Ok(0) => break,
+                        Ok(_) => {}
+                        Err(e) => {
+                            result = Err(map_error(e));
+                            break 'capture;
+                        }
+                    }
+
+                    let mut data: *mut u8 = std::ptr::null_mut();
+                    let mut frames = 0u32;
+                    let mut flags = 0u32;
+                    if let Err(e) =
+                        capture.GetBuffer(&mut data, &mut frames, &mut flags, None, None)
+                    {
+                        result = Err(map_error(e));
+                        break 'capture;
+                    }
+
+                    let count = frames as usize * channels;
+                    if flags & AUDCLNT_BUFFERFLAGS_SILENT.0 as u32 != 0 || data.is_null() {
+                        pending.extend(std::iter::repeat(0.0).take(count));
+                    } else if bits_per_sample == 32 {
+                        pending.extend_from_slice(std::slice::from_raw_parts(
+                            data as *const f32,
+                            count,
+                        ));
+                    } else if bits_per_sample == 16 {
+                        pending.extend(
+                            std::slice::from_raw_parts(data as *const i16, count)
+                                .iter()
+                                .map(|s| *s as f32 / i16::MAX as f32),
+                        );
+                    }
+                    let _ = capture.ReleaseBuffer(frames);
+
+                    if pending.len() >= samples_per_reading {
+                        on_level(super::rms(&pending));
+                        pending.clear();
+                    }
+                }
+            }
+
+            let _ = client.Stop();
+            result
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::io::Read;
+    use std::process::{Child, Command, Stdio};
+
+    /// Record the default source as raw mono f32: pw-record on PipeWire, parec on PulseAudio
+    fn spawn_recorder() -> Result<Child, String> {
+        let rate = super::SAMPLE_RATE.to_string();
+        let recorders: [(&str, Vec<String>); 2] = [
+            (
+                "pw-record",
+                vec![
+                    "--format=f32".to_string(),
+                    format!("--rate={}", rate),
+                    "--channels=1".to_string(),
+                    "-".to_string(),
+                ],
+            ),
+            (
+                "parec",
+                vec![
+                    "--raw".to_string(),
+                    "--format=float32le".to_string(),
+                    format!("--rate={}", rate),
+                    "--channels=1".to_string(),
+                    "--latency-msec=50".to_string(),
+                ],
+            ),
+        ];
+
+        for (program, args) in recorders {
+            let child = Command::new(program)
+                .args(&args)
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn();
+            if let Ok(child) = child {
+                return Ok(child);
+            }
+        }
+        Err("Unsupported: neither pw-record nor parec is available".to_string())
+    }
+
+    pub fn capture_input(
+        keep_running: &dyn Fn() -> bool,
+        on_level: &mut dyn FnMut(f32),
+    ) -> Result<(), String> {
+        let mut child = spawn_recorder()?;
+        let mut stdout = match child.stdout.take() {
+            Some(stdout) => stdout,
+            None => {
+                let _ = child.kill();
+                return Err("Failed to read from the recorder".to_string());
+            }
+        };
+
+        let mut bytes = vec![0u8; super::SAMPLES_PER_READING * 4];
+        let mut result = Ok(());
+        while keep_running() {
+            if stdout.read_exact(&mut bytes).is_err() {
+                result = Err("Input capture stopped unexpectedly".to_string());
+                break;
+            }
+            let samples: Vec<f32> = bytes
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect();
+            on_level(super::rms(&samples));
+        }
+
+        let _ = child.kill();
+        let _ = child.wait();
+        result
+    }
+}
+
+/// Run a capture of the default input device until `keep_running` returns false,
+/// calling `on_level` with the RMS of every ~100ms of audio
+fn capture_input(
+    keep_running: &dyn Fn() -> bool,
+    on_level: &mut dyn FnMut(f32),
+) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        macos::capture_input(keep_running, on_level)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        windows_capture::capture_input(keep_running, on_level)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        linux::capture_input(keep_running, on_level)
+    }
+}
+
+/// Current level of the default input device (RMS, 0.0-1.0). Uses the subscribed capture
+/// when one is running, otherwise briefly opens the device for a single reading.
+/// Errors starting with "PermissionDenied" mean microphone access was refused.
+#[tauri::command]
+pub async fn get_input_level() -> Result<f64, String> {
+    if ACTIVE_CAPTURE.load(Ordering::Relaxed) != 0 {
+        return Ok(f32::from_bits(INPUT_LEVEL.load(Ordering::Relaxed)) as f64);
+    }
+
+    tauri::async_runtime::spawn_blocking(|| {
+        // Stops after the first reading
+        let level = std::cell::Cell::new(None);
+        capture_input(&|| level.get().is_none(), &mut |rms| level.set(Some(rms)))?;
+        Ok(level.get().unwrap_or(0.0) as f64)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Start emitting `input-level-update` (RMS, 0.0-1.0) about every 100ms. The input device
+/// is only held open while subscribed. If capture can't start or stops, `input-level-error`
+/// is emitted with the reason ("PermissionDenied: ..." when microphone access was refused).
+#[tauri::command]
+pub fn subscribe_input_level(app_handle: AppHandle) {
+    let subscription = INPUT_SUBSCRIPTION.fetch_add(1, Ordering::SeqCst) + 1;
+
+    std::thread::spawn(move || {
+        log::debug!("Input level capture started");
+
+        let result = capture_input(
+            &|| INPUT_SUBSCRIPTION.load(Ordering::SeqCst) == subscription,
+            &mut |rms| {
+                ACTIVE_CAPTURE.store(subscription, Ordering::Relaxed);
+                INPUT_LEVEL.store(rms.to_bits(), Ordering::Relaxed);
+                let _ = app_handle.emit("input-level-update", rms as f64);
+            },
+        );
+
+        let _ =
+            ACTIVE_CAPTURE.compare_exchange(subscription, 0, Ordering::Relaxed, Ordering::Relaxed);
+        match result {
+            Ok(()) => log::debug!("Input level capture stopped"),
+            Err(e) => {
+                log::warn!("Input level capture failed: {}", e);
+                let _ = app_handle.emit("input-level-error", e);
+            }
+        }
+    });
+}
+
+/// Stop the input level capture and release the input device
+#[tauri::command]
+pub fn unsubscribe_input_level() {
+    INPUT_SUBSCRIPTION.fetch_add(1, Ordering::SeqCst);
+}
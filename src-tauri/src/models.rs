@@ -3,7 +3,7 @@ use serde::Serialize;
 /// Notch and screen information returned to the frontend
 #[derive(Debug, Serialize, Clone)]
 pub struct NotchInfo {
-    /// Whether the screen has a notch (safeAreaInsets.top > 0)
+    /// Whether the screen has a physical notch (notched MacBook model + built-in display on macOS)
     pub has_notch: bool,
     /// Height of the notch/safe area inset from the top (typically 30-40px on notched MacBooks)
     pub notch_height: f64,
@@ -17,16 +17,91 @@ pub struct NotchInfo {
     pub visible_height: f64,
 }
 
+/// Raw main screen metrics, for diagnosing notch detection issues
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct RawMetrics {
+    /// Screen frame width in points
+    pub frame_width: f64,
+    /// Screen frame height in points
+    pub frame_height: f64,
+    /// safeAreaInsets (zero where the platform has no equivalent)
+    pub inset_top: f64,
+    pub inset_left: f64,
+    pub inset_bottom: f64,
+    pub inset_right: f64,
+    /// Backing scale factor (2.0 on Retina)
+    pub scale_factor: f64,
+    /// Localized display name, if available
+    pub display_name: Option<String>,
+}
+
+/// One cache emptied by clear_all_caches
+#[derive(Debug, Serialize, Clone)]
+pub struct ClearedCache {
+    pub name: String,
+    /// In-memory entries or files removed
+    pub entries: usize,
+    /// Bytes freed on disk (0 for in-memory caches)
+    pub bytes: u64,
+}
+
+/// Result of clear_all_caches
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct CacheClearReport {
+    pub caches: Vec<ClearedCache>,
+    pub total_bytes: u64,
+}
+
+/// Which features actually work on the running platform, so the UI can hide the rest
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct FeatureMatrix {
+    /// Hardware notch detection and notch-sized geometry
+    pub notch_detection: bool,
+    pub haptics: bool,
+    pub dock_icon: bool,
+    /// Native window corner radius (set_window_corner_radius)
+    pub window_corner_radius: bool,
+    /// Choosing whether the window joins all Spaces (set_space_behavior)
+    pub space_behavior: bool,
+    pub calendar: bool,
+    pub reminders: bool,
+    pub media_controls: bool,
+    pub media_seek: bool,
+    pub media_volume: bool,
+    pub mute: bool,
+    pub volume_hud_suppression: bool,
+    /// Jump to the playing track inside its app
+    pub focus_current_track: bool,
+    pub open_files: bool,
+    pub drag_out: bool,
+    /// Real audio capture for the visualizer (levels are simulated otherwise)
+    pub audio_capture: bool,
+    /// Default input device level for the mic meter
+    pub input_level: bool,
+    pub brightness: bool,
+    pub night_shift: bool,
+    pub frontmost_app: bool,
+    pub screen_lock_detection: bool,
+}
+
 /// Now Playing track information
 #[derive(Debug, Serialize, Clone, Default)]
 pub struct NowPlayingData {
     /// Track title
     pub title: Option<String>,
-    /// Artist name
+    /// Artist name (all artists joined with ", " when the source lists several)
     pub artist: Option<String>,
+    /// Individual artist names, de-duplicated
+    pub artists: Option<Vec<String>>,
     /// Album name
     pub album: Option<String>,
-    /// Base64 encoded artwork (PNG)
+    /// Genre, where the source exposes it (Music.app, MPRIS)
+    pub genre: Option<String>,
+    /// Release year, where the source exposes it (Music.app, MPRIS)
+    pub year: Option<i64>,
+    /// AirPlay device(s) playback is routed to, where the source exposes it (Music.app)
+    pub output_target: Option<String>,
+    /// Base64 encoded artwork (JPEG when downscaled, PNG when transparent, otherwise as the source sent it)
     pub artwork_base64: Option<String>,
     /// Track duration in seconds
     pub duration: Option<f64>,
@@ -38,4 +113,33 @@ pub struct NowPlayingData {
     pub audio_levels: Option<Vec<f64>>,
     /// Name of the app playing the media (Spotify, Music, Safari)
     pub app_name: Option<String>,
+    /// "music", "podcast" or "audiobook" ("music" when the source gives no hint)
+    pub content_type: Option<String>,
+}
+
+/// Payload for `automation-denied` events (macOS Automation permission missing)
+#[derive(Debug, Serialize, Clone)]
+pub struct AutomationDenied {
+    /// Name of the app that couldn't be controlled (Spotify, Music, Safari)
+    pub app: String,
+}
+
+/// A media-capable app and its current state, for the source picker
+#[derive(Debug, Serialize, Clone)]
+pub struct MediaAppInfo {
+    /// Display name (macOS app name, Windows app id, or MPRIS player name)
+    pub name: String,
+    /// Whether the app process / media session exists
+    pub is_running: bool,
+    /// Whether the app is currently playing
+    pub is_playing: bool,
+}
+
+/// The frontmost (focused) application, emitted as `frontmost-app-changed`
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct AppInfo {
+    /// Display name (e.g. "Safari", "chrome", "firefox")
+    pub name: String,
+    /// Bundle identifier (macOS), executable path (Windows) or WM_CLASS instance (Linux)
+    pub bundle_id: Option<String>,
 }
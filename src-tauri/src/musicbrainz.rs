@@ -0,0 +1,274 @@
+use crate::database::{get_connection, log_sql};
+use rusqlite::OptionalExtension;
+use serde::Serialize;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+const MUSICBRAINZ_API_URL: &str = "https://musicbrainz.org/ws/2/recording";
+
+/// MusicBrainz rejects requests without an identifying user agent
+const USER_AGENT: &str = concat!(
+    "openNook/",
+    env!("CARGO_PKG_VERSION"),
+    " ( https://github.com/prodBirdy/openNook )"
+);
+
+/// MusicBrainz allows one request per second per client
+const MIN_REQUEST_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Search scores below this are too loose a match to trust
+const MIN_MATCH_SCORE: i64 = 90;
+
+/// Time of the last request, held across the request so lookups are serialized
+static LAST_REQUEST: OnceLock<tokio::sync::Mutex<Option<Instant>>> = OnceLock::new();
+
+/// Canonical recording metadata from MusicBrainz
+#[derive(Serialize, Clone, Debug)]
+pub struct TrackMetadata {
+    /// MusicBrainz recording id
+    pub mbid: String,
+    pub title: String,
+    pub artist: String,
+    pub album: Option<String>,
+    pub year: Option<i32>,
+}
+
+fn ensure_cache_table(conn: &rusqlite::Connection) -> Result<(), String> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS musicbrainz_cache (
+            artist TEXT NOT NULL,
+            title TEXT NOT NULL,
+            mbid TEXT,
+            canonical_title TEXT,
+            canonical_artist TEXT,
+            album TEXT,
+            year INTEGER,
+            PRIMARY KEY (artist, title)
+        )",
+        [],
+    )
+    .map(|_| ())
+    .map_err(|e| e.to_string())
+}
+
+fn cache_key(value: &str) -> String {
+    value.trim().to_lowercase()
+}
+
+/// Some(None) is a cached miss, None means the track was never looked up
+fn load_cached(app_handle: &AppHandle, artist: &str, title: &str) -> Option<Option<TrackMetadata>> {
+    let conn = get_connection(app_handle).ok()?;
+    ensure_cache_table(&conn).ok()?;
+
+    let sql = "SELECT mbid, canonical_title, canonical_artist, album, year FROM musicbrainz_cache WHERE artist = ?1 AND title = ?2";
+    log_sql(sql);
+    conn.query_row(sql, [cache_key(artist), cache_key(title)], |row| {
+        let mbid: Option<String> = row.get(0)?;
+        Ok(match mbid {
+            Some(mbid) => Some(TrackMetadata {
+                mbid,
+                title: row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+                artist: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+                album: row.get(3)?,
+                year: row.get(4)?,
+            }),
+            None => None,
+        })
+    })
+    .optional()
+    .ok()
+    .flatten()
+}
+
+fn save_cached(
+    app_handle: &AppHandle,
+    artist: &str,
+    title: &str,
+    metadata: Option<&TrackMetadata>,
+) -> Result<(), String> {
+    let conn = get_connection(app_handle).map_err(|e| e.to_string())?;
+    ensure_cache_table(&conn)?;
+
+    let sql = "INSERT OR REPLACE INTO musicbrainz_cache (artist, title, mbid, canonical_title, canonical_artist, album, year) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)";
+    log_sql(sql);
+    conn.execute(
+        sql,
+        rusqlite::params![
+            cache_key(artist),
+            cache_key(title),
+            metadata.map(|m| m.mbid.as_str()),
+            metadata.map(|m| m.title.as_str()),
+            metadata.map(|m| m.artist.as_str()),
+            metadata.and_then(|m| m.album.as_deref()),
+            metadata.and_then(|m| m.year),
+        ],
+    )
+    .map(|_| ())
+    .map_err(|e| e.to_string())
+}
+
+fn is_enrichment_enabled(app_handle: &AppHandle) -> bool {
+    get_connection(app_handle)
+        .ok()
+        .and_then(|conn| {
+            let sql = "SELECT value FROM settings WHERE key = 'metadata_enrichment_enabled'";
+            log_sql(sql);
+            conn.query_row(sql, [], |row| row.get::<_, String>(0)).ok()
+        })
+        .map(|value| value == "true")
+        .unwrap_or(false)
+}
+
+/// Quote a value for a Lucene phrase query
+fn lucene_phrase(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Pick the best matching recording out of a search response
+fn parse_recording(body: &serde_json::Value) -> Option<TrackMetadata> {
+    let recording = body.get("recordings")?.as_array()?.first()?;
+
+    let score = recording.get("score").and_then(|s| s.as_i64()).unwrap_or(0);
+    if score < MIN_MATCH_SCORE {
+        return None;
+    }
+
+    // "Artist A feat. Artist B" is stored as names joined by their join phrases
+    let artist: String = recording
+        .get("artist-credit")?
+        .as_array()?
+        .iter()
+        .map(|credit| {
+            let name = credit.get("name").and_then(|n| n.as_str()).unwrap_or("");
+            let join = credit
+                .get("joinphrase")
+                .and_then(|j| j.as_str())
+                .unwrap_or("");
+            format!("{}{}", name, join)
+        })
+        .collect();
+
+    let album = recording
+        .get("releases")
+        .and_then(|r| r.as_array())
+        .and_then(|releases| {
+            releases
+                .iter()
+                .find(|r| r.get("status").and_then(|s| s.as_str()) == Some("Official"))
+                .or_else(|| releases.first())
+        })
+        .and_then(|release| release.get("title"))
+        .and_then(|t| t.as_str())
+        .map(|t| t.to_string());
+
+    // "YYYY-MM-DD", "YYYY-MM" or "YYYY"
+    let year = recording
+        .get("first-release-date")
+        .and_then(|d| d.as_str())
+        .and_then(|d| d.get(..4))
+        .and_then(|y| y.parse().ok());
+
+    Some(TrackMetadata {
+        mbid: recording.get("id")?.as_str()?.to_string(),
+        title: recording.get("title")?.as_str()?.to_string(),
+        artist,
+        album,
+        year,
+    })
+}
+
+async fn fetch_recording(title: &str, artist: &str) -> Result<Option<TrackMetadata>, String> {
+    let mut last_request = LAST_REQUEST
+        .get_or_init(|| tokio::sync::Mutex::new(None))
+        .lock()
+        .await;
+    if let Some(last) = *last_request {
+        let elapsed = last.elapsed();
+        if elapsed < MIN_REQUEST_INTERVAL {
+            tokio::time::sleep(MIN_REQUEST_INTERVAL - elapsed).await;
+        }
+    }
+
+    let client = reqwest::Client::builder()
+        .user_agent(USER_AGENT)
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let query = format!(
+        "recording:{} AND artist:{}",
+        lucene_phrase(title),
+        lucene_phrase(artist)
+    );
+    let result = client
+        .get(MUSICBRAINZ_API_URL)
+        .query(&[("query", query.as_str()), ("fmt", "json"), ("limit", "1")])
+        .send()
+        .await;
+    *last_request = Some(Instant::now());
+
+    let response = result.map_err(|e| format!("MusicBrainz unreachable: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("MusicBrainz returned {}", response.status()));
+    }
+
+    let body: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid response from MusicBrainz: {}", e))?;
+    Ok(parse_recording(&body))
+}
+
+/// Look up the canonical album, year and MusicBrainz id of a track, for sources that
+/// report sparse metadata. Results (including misses) are cached per artist and title.
+/// Returns None when enrichment is disabled, nothing matched or MusicBrainz is unreachable.
+#[tauri::command]
+pub async fn enrich_track_metadata(
+    app_handle: AppHandle,
+    title: String,
+    artist: String,
+) -> Option<TrackMetadata> {
+    if !is_enrichment_enabled(&app_handle) || title.trim().is_empty() || artist.trim().is_empty() {
+        return None;
+    }
+
+    if let Some(cached) = load_cached(&app_handle, &artist, &title) {
+        return cached;
+    }
+    if !crate::connectivity::connectivity_status().await.online {
+        return None;
+    }
+
+    match fetch_recording(&title, &artist).await {
+        Ok(metadata) => {
+            if let Err(e) = save_cached(&app_handle, &artist, &title, metadata.as_ref()) {
+                log::warn!("Failed to cache MusicBrainz metadata: {}", e);
+            }
+            metadata
+        }
+        // Not cached, so the lookup is retried next time
+        Err(e) => {
+            log::warn!("Track metadata lookup failed: {}", e);
+            None
+        }
+    }
+}
+
+/// Enable or disable MusicBrainz metadata lookups (off by default, needs network access)
+#[tauri::command]
+pub fn set_metadata_enrichment_enabled(app_handle: AppHandle, enabled: bool) -> Result<(), String> {
+    let conn = get_connection(&app_handle).map_err(|e| e.to_string())?;
+    let sql =
+        "INSERT OR REPLACE INTO settings (key, value) VALUES ('metadata_enrichment_enabled', ?1)";
+    log_sql(sql);
+    conn.execute(sql, rusqlite::params![enabled.to_string()])
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_metadata_enrichment_enabled(app_handle: AppHandle) -> bool {
+    is_enrichment_enabled(&app_handle)
+}
@@ -1,5 +1,5 @@
 use crate::database::{get_connection, log_sql};
-use tauri::AppHandle;
+use tauri::{AppHandle, Emitter};
 
 /// Save notes to the database (settings table)
 #[tauri::command]
@@ -33,3 +33,31 @@ pub fn load_notes(app_handle: AppHandle) -> Result<String, String> {
         Err(e) => Err(e.to_string()),
     }
 }
+
+/// Append the text selected in the frontmost app to the notes, on its own line.
+/// Emits `notes-updated` with the new notes and returns the appended text,
+/// or None when nothing is selected (or Accessibility permission is missing).
+#[tauri::command]
+pub fn quick_note_from_selection(app_handle: AppHandle) -> Result<Option<String>, String> {
+    let selection = match crate::frontmost::get_selected_text() {
+        Some(text) => text.trim().to_string(),
+        None => return Ok(None),
+    };
+
+    append_to_notes(&app_handle, &selection)?;
+
+    Ok(Some(selection))
+}
+
+/// Append `text` to the notes on its own line and emit `notes-updated`
+pub fn append_to_notes(app_handle: &AppHandle, text: &str) -> Result<(), String> {
+    let mut notes = load_notes(app_handle.clone())?;
+    if !notes.is_empty() && !notes.ends_with('\n') {
+        notes.push('\n');
+    }
+    notes.push_str(text);
+
+    save_notes(app_handle.clone(), notes.clone())?;
+    let _ = app_handle.emit("notes-updated", notes);
+    Ok(())
+}
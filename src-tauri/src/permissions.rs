@@ -0,0 +1,267 @@
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Apps controlled via AppleScript that need Automation permission
+#[cfg(target_os = "macos")]
+const AUTOMATION_APPS: [&str; 3] = ["Spotify", "Music", "Safari"];
+
+/// Status of a single system permission
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionStatus {
+    /// Permission granted (or not required on this platform)
+    Granted,
+    /// Permission explicitly denied by the user
+    Denied,
+    /// The user has not been asked yet
+    NotDetermined,
+    /// Blocked by parental controls / MDM
+    Restricted,
+    /// The target app isn't running, so Automation can't be checked without launching it
+    NotRunning,
+    /// Status could not be determined
+    Unknown,
+}
+
+/// All permissions the app needs, reported at once for onboarding
+#[derive(Debug, Clone, Serialize)]
+pub struct PermissionReport {
+    pub calendar: PermissionStatus,
+    pub reminders: PermissionStatus,
+    pub screen_recording: PermissionStatus,
+    /// Accessibility, only needed by advanced interaction features (event taps, focus detection)
+    pub accessibility: PermissionStatus,
+    /// Automation (Apple Events) status keyed by app name
+    pub automation: HashMap<String, PermissionStatus>,
+    /// True when nothing needs the user's attention
+    pub all_granted: bool,
+}
+
+#[cfg(target_os = "macos")]
+extern "C" {
+    fn CGPreflightScreenCaptureAccess() -> bool;
+    fn CGRequestScreenCaptureAccess() -> bool;
+}
+
+#[cfg(target_os = "macos")]
+#[link(name = "ApplicationServices", kind = "framework")]
+extern "C" {
+    fn AXIsProcessTrustedWithOptions(options: core_foundation::dictionary::CFDictionaryRef)
+        -> bool;
+    static kAXTrustedCheckOptionPrompt: core_foundation::string::CFStringRef;
+}
+
+/// Whether the app is trusted for Accessibility. With `prompt`, macOS shows its
+/// "grant access" dialog if the app isn't trusted yet.
+#[cfg(target_os = "macos")]
+fn accessibility_trusted(prompt: bool) -> bool {
+    use core_foundation::base::TCFType;
+    use core_foundation::boolean::CFBoolean;
+    use core_foundation::dictionary::CFDictionary;
+    use core_foundation::string::CFString;
+
+    unsafe {
+        let key = CFString::wrap_under_get_rule(kAXTrustedCheckOptionPrompt);
+        let value = if prompt {
+            CFBoolean::true_value()
+        } else {
+            CFBoolean::false_value()
+        };
+        let options = CFDictionary::from_CFType_pairs(&[(key, value)]);
+        AXIsProcessTrustedWithOptions(options.as_concrete_TypeRef())
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn screen_recording_status() -> PermissionStatus {
+    if unsafe { CGPreflightScreenCaptureAccess() } {
+        PermissionStatus::Granted
+    } else {
+        PermissionStatus::Denied
+    }
+}
+
+/// Names of the automation target apps that are currently running
+#[cfg(target_os = "macos")]
+fn running_automation_apps() -> Vec<&'static str> {
+    use sysinfo::System;
+
+    let sys = System::new_all();
+    AUTOMATION_APPS
+        .into_iter()
+        .filter(|app| sys.processes().values().any(|p| p.name() == *app))
+        .collect()
+}
+
+/// Dry-run an Apple Event against the app and map osascript's result to a status.
+/// Note: the first call for an app triggers the system consent prompt.
+#[cfg(target_os = "macos")]
+fn automation_status(app: &str) -> PermissionStatus {
+    use std::process::Command;
+
+    let script = format!(r#"tell application "{}" to get name"#, app);
+
+    match Command::new("osascript").arg("-e").arg(&script).output() {
+        Ok(output) if output.status.success() => {
+            crate::audio::clear_automation_denied(app);
+            PermissionStatus::Granted
+        }
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if crate::utils::is_automation_denied(&stderr) {
+                PermissionStatus::Denied
+            } else {
+                PermissionStatus::Unknown
+            }
+        }
+        Err(_) => PermissionStatus::Unknown,
+    }
+}
+
+fn build_report(
+    calendar: PermissionStatus,
+    reminders: PermissionStatus,
+    screen_recording: PermissionStatus,
+    accessibility: PermissionStatus,
+    automation: HashMap<String, PermissionStatus>,
+) -> PermissionReport {
+    // Accessibility is optional, so it doesn't count towards all_granted
+    let all_granted = [calendar, reminders, screen_recording]
+        .iter()
+        .chain(automation.values())
+        .all(|s| matches!(s, PermissionStatus::Granted | PermissionStatus::NotRunning));
+
+    PermissionReport {
+        calendar,
+        reminders,
+        screen_recording,
+        accessibility,
+        automation,
+        all_granted,
+    }
+}
+
+/// Report the status of every permission the app needs
+#[tauri::command]
+pub async fn get_all_permissions() -> PermissionReport {
+    #[cfg(target_os = "macos")]
+    {
+        let (calendar, reminders) = crate::calendar::get_calendar_permission_status();
+
+        let running = running_automation_apps();
+        let automation = AUTOMATION_APPS
+            .iter()
+            .map(|app| {
+                let status = if running.contains(app) {
+                    automation_status(app)
+                } else {
+                    PermissionStatus::NotRunning
+                };
+                (app.to_string(), status)
+            })
+            .collect();
+
+        let accessibility = if accessibility_trusted(false) {
+            PermissionStatus::Granted
+        } else {
+            PermissionStatus::Denied
+        };
+
+        build_report(
+            calendar,
+            reminders,
+            screen_recording_status(),
+            accessibility,
+            automation,
+        )
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        // No runtime permission prompts are needed on other platforms
+        build_report(
+            PermissionStatus::Granted,
+            PermissionStatus::Granted,
+            PermissionStatus::Granted,
+            PermissionStatus::Granted,
+            HashMap::new(),
+        )
+    }
+}
+
+/// Sequentially trigger every permission prompt that hasn't been answered yet,
+/// then return the updated report
+#[tauri::command]
+pub async fn request_all_permissions() -> PermissionReport {
+    #[cfg(target_os = "macos")]
+    {
+        // Calendar + Reminders
+        if let Err(e) = crate::calendar::request_calendar_access().await {
+            log::warn!("Calendar access request failed: {}", e);
+        }
+
+        // Screen recording (used for audio capture)
+        if screen_recording_status() != PermissionStatus::Granted {
+            unsafe {
+                CGRequestScreenCaptureAccess();
+            }
+        }
+
+        // Automation - sending an Apple Event prompts if not yet determined
+        for app in running_automation_apps() {
+            let status = automation_status(app);
+            log::debug!("Automation permission for {}: {:?}", app, status);
+        }
+    }
+
+    get_all_permissions().await
+}
+
+/// Open System Settings at the Automation privacy pane
+#[tauri::command]
+pub async fn open_automation_settings() -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        std::process::Command::new("open")
+            .arg("x-apple.systempreferences:com.apple.preference.security?Privacy_Automation")
+            .spawn()
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Whether the app is trusted for Accessibility (always true off macOS)
+#[tauri::command]
+pub fn get_accessibility_permission() -> bool {
+    #[cfg(target_os = "macos")]
+    {
+        accessibility_trusted(false)
+    }
+    #[cfg(not(target_os = "macos"))]
+    true
+}
+
+/// Show the system Accessibility prompt if the app isn't trusted yet, returns the current status.
+/// macOS only asks once; after that the user has to enable the app in System Settings.
+#[tauri::command]
+pub fn request_accessibility_permission() -> bool {
+    #[cfg(target_os = "macos")]
+    {
+        accessibility_trusted(true)
+    }
+    #[cfg(not(target_os = "macos"))]
+    true
+}
+
+/// Open System Settings at the Accessibility privacy pane
+#[tauri::command]
+pub async fn open_accessibility_settings() -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        std::process::Command::new("open")
+            .arg("x-apple.systempreferences:com.apple.preference.security?Privacy_Accessibility")
+            .spawn()
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,117 @@
+use crate::database::{get_connection, log_sql};
+use crate::models::NowPlayingData;
+use tauri::AppHandle;
+
+fn ensure_history_table(conn: &rusqlite::Connection) -> Result<(), String> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS play_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            timestamp INTEGER NOT NULL,
+            title TEXT NOT NULL,
+            artist TEXT,
+            album TEXT,
+            source TEXT
+        )",
+        [],
+    )
+    .map(|_| ())
+    .map_err(|e| e.to_string())
+}
+
+/// Called from get_now_playing on every poll. Records a row when a new track starts playing.
+pub fn record_play(app_handle: &AppHandle, data: &NowPlayingData, track_changed: bool) {
+    if !track_changed || !data.is_playing {
+        return;
+    }
+    let title = match &data.title {
+        Some(title) if !title.is_empty() => title,
+        _ => return,
+    };
+
+    let started_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+        - data.elapsed_time.unwrap_or(0.0) as i64;
+
+    let result = get_connection(app_handle)
+        .map_err(|e| e.to_string())
+        .and_then(|conn| {
+            ensure_history_table(&conn)?;
+            let sql = "INSERT INTO play_history (timestamp, title, artist, album, source) VALUES (?1, ?2, ?3, ?4, ?5)";
+            log_sql(sql);
+            conn.execute(
+                sql,
+                rusqlite::params![started_at, title, data.artist, data.album, data.app_name],
+            )
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+        });
+
+    if let Err(e) = result {
+        log::error!("Failed to record play history: {}", e);
+    }
+}
+
+/// UTC timestamp like 2024-01-31T09:30:00Z
+fn iso_timestamp(secs: i64) -> String {
+    let (year, month, day) = crate::calendar::civil_from_days(secs.div_euclid(86_400));
+    let time = secs.rem_euclid(86_400);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        time / 3600,
+        (time % 3600) / 60,
+        time % 60
+    )
+}
+
+/// Quote a CSV field when it contains a delimiter, quote or line break (RFC 4180)
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Export the recorded play history, oldest first, as CSV with a header row
+#[tauri::command]
+pub fn export_play_history_csv(app_handle: AppHandle) -> Result<String, String> {
+    let conn = get_connection(&app_handle).map_err(|e| e.to_string())?;
+    ensure_history_table(&conn)?;
+
+    let sql =
+        "SELECT timestamp, title, artist, album, source FROM play_history ORDER BY timestamp, id";
+    log_sql(sql);
+    let mut stmt = conn.prepare(sql).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, Option<String>>(4)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut csv = String::from("timestamp,title,artist,album,source\r\n");
+    for row in rows {
+        let (timestamp, title, artist, album, source) = row.map_err(|e| e.to_string())?;
+        let fields = [
+            iso_timestamp(timestamp),
+            csv_field(&title),
+            csv_field(artist.as_deref().unwrap_or("")),
+            csv_field(album.as_deref().unwrap_or("")),
+            csv_field(source.as_deref().unwrap_or("")),
+        ];
+        csv.push_str(&fields.join(","));
+        csv.push_str("\r\n");
+    }
+
+    Ok(csv)
+}
@@ -1,8 +1,10 @@
+use crate::database::{get_connection, log_sql};
 use log;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
-use tauri::{command, AppHandle};
+use std::path::{Path, PathBuf};
+use tauri::{command, AppHandle, Emitter};
 
 /// Plugin manifest as defined in plugin.json
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -31,6 +33,90 @@ pub struct PluginInfo {
     pub plugin_dir: String,
 }
 
+/// Network requests from the bundle, checked by inspect_plugin
+const PERMISSION_NETWORK: &str = "network";
+/// Filesystem access from the bundle, checked by inspect_plugin
+const PERMISSION_FILESYSTEM: &str = "filesystem";
+
+/// Every permission a plugin.json may declare
+const PLUGIN_PERMISSIONS: &[&str] = &[PERMISSION_NETWORK, PERMISSION_FILESYSTEM];
+
+/// Expanded width used when a plugin doesn't declare `minWidth`
+const DEFAULT_MIN_WIDTH: u32 = 260;
+
+/// Compact priority used when a plugin doesn't declare `compactPriority` (lower shows first)
+const DEFAULT_COMPACT_PRIORITY: u32 = 100;
+
+/// Widget category, used to group widgets in settings
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PluginCategory {
+    Productivity,
+    Media,
+    Utility,
+}
+
+impl PluginCategory {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "productivity" => Some(Self::Productivity),
+            "media" => Some(Self::Media),
+            "utility" => Some(Self::Utility),
+            _ => None,
+        }
+    }
+}
+
+/// A plugin's manifest resolved into what the plugin host needs to render it
+#[derive(Serialize, Clone, Debug)]
+pub struct PluginRenderSpec {
+    pub id: String,
+    pub name: String,
+    /// Unknown categories resolve to Utility
+    pub category: PluginCategory,
+    /// `minWidth`, or DEFAULT_MIN_WIDTH when not declared
+    pub min_width: u32,
+    /// Declares a compact mode and may be shown in the collapsed notch
+    pub compact_eligible: bool,
+    /// `compactPriority`, or DEFAULT_COMPACT_PRIORITY when not declared
+    pub compact_priority: u32,
+    /// Declared permissions from PLUGIN_PERMISSIONS
+    pub granted_permissions: Vec<String>,
+    /// Declared permissions outside PLUGIN_PERMISSIONS, which are never granted
+    pub unknown_permissions: Vec<String>,
+}
+
+impl From<&PluginManifest> for PluginRenderSpec {
+    fn from(manifest: &PluginManifest) -> Self {
+        let category = PluginCategory::parse(&manifest.category).unwrap_or_else(|| {
+            log::warn!(
+                "Plugin {} has unknown category '{}', treating it as utility",
+                manifest.id,
+                manifest.category
+            );
+            PluginCategory::Utility
+        });
+        let (granted_permissions, unknown_permissions): (Vec<String>, Vec<String>) = manifest
+            .permissions
+            .iter()
+            .cloned()
+            .partition(|p| PLUGIN_PERMISSIONS.contains(&p.as_str()));
+
+        PluginRenderSpec {
+            id: manifest.id.clone(),
+            name: manifest.name.clone(),
+            category,
+            min_width: manifest.min_width.unwrap_or(DEFAULT_MIN_WIDTH),
+            compact_eligible: manifest.has_compact_mode,
+            compact_priority: manifest
+                .compact_priority
+                .unwrap_or(DEFAULT_COMPACT_PRIORITY),
+            granted_permissions,
+            unknown_permissions,
+        }
+    }
+}
+
 /// Get the plugins directory path
 fn get_plugins_dir() -> PathBuf {
     let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
@@ -120,12 +206,282 @@ pub fn read_plugin_bundle(_app_handle: AppHandle, bundle_path: String) -> Result
     fs::read_to_string(&bundle_path).map_err(|e| e.to_string())
 }
 
+/// Resolve an installed plugin's category, widths, compact mode and permissions in one place,
+/// so the plugin host doesn't interpret the raw manifest itself
+#[command]
+pub fn get_plugin_render_spec(plugin_id: String) -> Result<PluginRenderSpec, String> {
+    let dir = plugin_install_dir(&get_plugins_dir(), &plugin_id)?;
+    if !dir.is_dir() {
+        return Err(format!("Plugin '{}' not found", plugin_id));
+    }
+
+    let info = validate_plugin_folder(&dir)?;
+    Ok(PluginRenderSpec::from(&info.manifest))
+}
+
+/// Enable or disable every installed plugin in `category` in one transaction.
+/// Returns the affected plugin ids (empty when no plugin matches) and emits
+/// `widget-state-changed` so open windows pick up the change.
+#[command]
+pub fn set_plugins_enabled_by_category(
+    app_handle: AppHandle,
+    category: String,
+    enabled: bool,
+) -> Result<Vec<String>, String> {
+    let category =
+        PluginCategory::parse(&category).ok_or(format!("Unknown category: {}", category))?;
+
+    let ids: Vec<String> = scan_plugins_directory(app_handle.clone())?
+        .into_iter()
+        .filter(|plugin| PluginRenderSpec::from(&plugin.manifest).category == category)
+        .map(|plugin| plugin.manifest.id)
+        .collect();
+    if ids.is_empty() {
+        return Ok(ids);
+    }
+
+    let conn = get_connection(&app_handle).map_err(|e| e.to_string())?;
+    conn.execute_batch("BEGIN TRANSACTION;")
+        .map_err(|e| e.to_string())?;
+
+    // Upsert so each plugin's saved config is preserved
+    let sql = "INSERT INTO widget_state (id, enabled) VALUES (?1, ?2) \
+               ON CONFLICT(id) DO UPDATE SET enabled = excluded.enabled";
+    for id in &ids {
+        log_sql(&format!("{} [{}, {}]", sql, id, enabled));
+        if let Err(e) = conn.execute(sql, rusqlite::params![id, enabled]) {
+            let _ = conn.execute_batch("ROLLBACK;");
+            return Err(e.to_string());
+        }
+    }
+
+    conn.execute_batch("COMMIT;").map_err(|e| e.to_string())?;
+
+    let changed: HashMap<&str, bool> = ids.iter().map(|id| (id.as_str(), enabled)).collect();
+    let _ = app_handle.emit(
+        "widget-state-changed",
+        serde_json::json!({ "enabled": changed, "senderId": "backend" }),
+    );
+
+    Ok(ids)
+}
+
+/// How concerning a bundle finding is
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum FindingSeverity {
+    /// Worth knowing, needs no permission
+    Info,
+    /// Uses a capability the manifest doesn't declare
+    Warning,
+    /// Runs code that can't be inspected statically
+    Danger,
+}
+
+/// One flagged API usage in a plugin bundle
+#[derive(Serialize, Clone, Debug)]
+pub struct PluginFinding {
+    /// What the usage gives access to, e.g. "network"
+    pub capability: String,
+    /// The matched source text, e.g. "fetch("
+    pub pattern: String,
+    /// Permission the manifest should declare for this, if any
+    pub required_permission: Option<String>,
+    /// Whether the manifest declares `required_permission`
+    pub declared: bool,
+    pub severity: FindingSeverity,
+    /// 1-based line of the first occurrence
+    pub line: usize,
+    pub occurrences: usize,
+    /// Source around the first occurrence
+    pub snippet: String,
+}
+
+/// Static risk summary of a plugin bundle, see inspect_plugin
+#[derive(Serialize, Clone, Debug)]
+pub struct PluginInspection {
+    pub plugin_id: String,
+    pub declared_permissions: Vec<String>,
+    pub findings: Vec<PluginFinding>,
+    /// Highest finding severity, None when nothing was flagged
+    pub risk: Option<FindingSeverity>,
+}
+
+/// Flagged API patterns: (capability, permission that should be declared, patterns).
+/// Capabilities without a permission are always reported.
+const INSPECTED_APIS: &[(&str, Option<&str>, &[&str])] = &[
+    (
+        "network",
+        Some(PERMISSION_NETWORK),
+        &[
+            "fetch(",
+            "XMLHttpRequest",
+            "WebSocket(",
+            "EventSource(",
+            "sendBeacon(",
+        ],
+    ),
+    (
+        "filesystem",
+        Some(PERMISSION_FILESYSTEM),
+        &[
+            "@tauri-apps/plugin-fs",
+            "plugin:fs|",
+            "readTextFile(",
+            "writeTextFile(",
+            "readFile(",
+            "writeFile(",
+            "readDir(",
+        ],
+    ),
+    (
+        "dynamic_code",
+        None,
+        &["eval(", "new Function(", "import(", "document.write("],
+    ),
+    (
+        "backend",
+        None,
+        &["invoke(", "__TAURI__", "__TAURI_INTERNALS__"],
+    ),
+    (
+        "storage",
+        None,
+        &["localStorage", "indexedDB", "document.cookie"],
+    ),
+];
+
+/// Characters of context kept on each side of a match
+const SNIPPET_CONTEXT: usize = 40;
+
+/// Byte offsets of `pattern` in `source` that aren't the tail of a longer identifier
+/// (so `prefetch(` doesn't count as `fetch(`)
+fn pattern_matches(source: &str, pattern: &str) -> Vec<usize> {
+    let starts_with_ident = pattern
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphanumeric() || c == '_');
+    source
+        .match_indices(pattern)
+        .map(|(offset, _)| offset)
+        .filter(|&offset| {
+            !starts_with_ident
+                || !source[..offset]
+                    .chars()
+                    .next_back()
+                    .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '$')
+        })
+        .collect()
+}
+
+fn snippet_around(source: &str, offset: usize, len: usize) -> String {
+    let mut start = offset.saturating_sub(SNIPPET_CONTEXT);
+    while !source.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = (offset + len + SNIPPET_CONTEXT).min(source.len());
+    while !source.is_char_boundary(end) {
+        end += 1;
+    }
+    source[start..end]
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Scan `source` for the INSPECTED_APIS patterns against the declared `permissions`
+fn inspect_bundle(source: &str, permissions: &[String]) -> Vec<PluginFinding> {
+    let mut findings = Vec::new();
+    for (capability, permission, patterns) in INSPECTED_APIS {
+        let declared = permission.is_some_and(|p| permissions.iter().any(|d| d == p));
+        let severity = if *capability == "dynamic_code" {
+            FindingSeverity::Danger
+        } else if permission.is_some() && !declared {
+            FindingSeverity::Warning
+        } else {
+            FindingSeverity::Info
+        };
+
+        for pattern in patterns.iter() {
+            let offsets = pattern_matches(source, pattern);
+            let first = match offsets.first() {
+                Some(&first) => first,
+                None => continue,
+            };
+
+            findings.push(PluginFinding {
+                capability: capability.to_string(),
+                pattern: pattern.to_string(),
+                required_permission: permission.map(str::to_string),
+                declared,
+                severity,
+                line: source[..first].matches('\n').count() + 1,
+                occurrences: offsets.len(),
+                snippet: snippet_around(source, first, pattern.len()),
+            });
+        }
+    }
+
+    // Most concerning first
+    findings.sort_by(|a, b| b.severity.cmp(&a.severity));
+    findings
+}
+
+/// Statically scan an installed plugin's bundle for network, filesystem, dynamic code and
+/// other API usage, and compare it with the permissions its manifest declares.
+/// Heuristic only (not a sandbox), meant as a risk summary before enabling the plugin.
+#[command]
+pub fn inspect_plugin(plugin_id: String) -> Result<PluginInspection, String> {
+    let dir = plugin_install_dir(&get_plugins_dir(), &plugin_id)?;
+    if !dir.is_dir() {
+        return Err(format!("Plugin '{}' not found", plugin_id));
+    }
+
+    let info = validate_plugin_folder(&dir)?;
+    let source = fs::read_to_string(&info.bundle_path)
+        .map_err(|e| format!("Failed to read plugin bundle: {}", e))?;
+
+    let findings = inspect_bundle(&source, &info.manifest.permissions);
+    let risk = findings.iter().map(|finding| finding.severity).max();
+
+    Ok(PluginInspection {
+        plugin_id,
+        declared_permissions: info.manifest.permissions,
+        findings,
+        risk,
+    })
+}
+
 /// Get the plugins directory path (for frontend use)
 #[command]
 pub fn get_plugins_directory_path() -> String {
     get_plugins_dir().to_string_lossy().to_string()
 }
 
+/// Plugin ids name the install folder, so only `[A-Za-z0-9._-]+` not starting with `.`
+/// or containing `..` is allowed
+fn is_valid_plugin_id(id: &str) -> bool {
+    !id.is_empty()
+        && !id.starts_with('.')
+        && !id.contains("..")
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+}
+
+/// The folder a plugin is installed to, refusing ids that would land outside `plugins_dir`
+fn plugin_install_dir(plugins_dir: &Path, plugin_id: &str) -> Result<PathBuf, String> {
+    if !is_valid_plugin_id(plugin_id) {
+        return Err(format!("Invalid plugin id '{}'", plugin_id));
+    }
+
+    let dest = plugins_dir.join(plugin_id);
+    if dest.parent() != Some(plugins_dir) || !dest.starts_with(plugins_dir) {
+        return Err("Security error: path traversal detected".to_string());
+    }
+    Ok(dest)
+}
+
 /// Validate a plugin folder has valid plugin.json and return its info
 fn validate_plugin_folder(path: &PathBuf) -> Result<PluginInfo, String> {
     let manifest_path = path.join("plugin.json");
@@ -139,6 +495,13 @@ fn validate_plugin_folder(path: &PathBuf) -> Result<PluginInfo, String> {
     let manifest: PluginManifest = serde_json::from_str(&manifest_content)
         .map_err(|e| format!("Invalid plugin.json: {}", e))?;
 
+    if !is_valid_plugin_id(&manifest.id) {
+        return Err(format!(
+            "Invalid plugin id '{}': only letters, digits, '.', '_' and '-' are allowed",
+            manifest.id
+        ));
+    }
+
     let bundle_path = path.join(&manifest.main);
     if !bundle_path.exists() {
         return Err(format!("Bundle file '{}' not found", manifest.main));
@@ -171,7 +534,7 @@ pub fn install_plugin_from_folder(
     let plugins_dir = get_plugins_dir();
     fs::create_dir_all(&plugins_dir).map_err(|e| e.to_string())?;
 
-    let dest = plugins_dir.join(plugin_id);
+    let dest = plugin_install_dir(&plugins_dir, plugin_id)?;
 
     // Remove existing if present
     if dest.exists() {
@@ -246,7 +609,13 @@ pub async fn install_plugin_from_git(
     let plugins_dir = get_plugins_dir();
     fs::create_dir_all(&plugins_dir).map_err(|e| e.to_string())?;
 
-    let dest = plugins_dir.join(&plugin_id);
+    let dest = match plugin_install_dir(&plugins_dir, &plugin_id) {
+        Ok(dest) => dest,
+        Err(e) => {
+            let _ = fs::remove_dir_all(&temp_dir);
+            return Err(e);
+        }
+    };
 
     // Remove existing if present
     if dest.exists() {
@@ -267,11 +636,112 @@ pub async fn install_plugin_from_git(
     validate_plugin_folder(&dest)
 }
 
+/// Install a plugin from a downloaded .zip archive
+#[command]
+pub async fn install_plugin_from_zip(
+    _app_handle: AppHandle,
+    archive_path: String,
+) -> Result<PluginInfo, String> {
+    let temp_dir = std::env::temp_dir().join(format!(
+        "opennook-plugin-zip-{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis()
+    ));
+
+    let result = extract_zip(Path::new(&archive_path), &temp_dir)
+        .and_then(|_| find_plugin_root(&temp_dir))
+        .and_then(|root| {
+            let plugin_info = validate_plugin_folder(&root)?;
+
+            let plugins_dir = get_plugins_dir();
+            fs::create_dir_all(&plugins_dir).map_err(|e| e.to_string())?;
+
+            let dest = plugin_install_dir(&plugins_dir, &plugin_info.manifest.id)?;
+
+            // Remove existing if present
+            if dest.exists() {
+                fs::remove_dir_all(&dest)
+                    .map_err(|e| format!("Failed to remove existing plugin: {}", e))?;
+            }
+
+            // Move from temp to plugins dir
+            fs::rename(&root, &dest)
+                .or_else(|_| copy_dir_all(&root, &dest))
+                .map_err(|e| format!("Failed to install plugin: {}", e))?;
+
+            validate_plugin_folder(&dest)
+        });
+
+    let _ = fs::remove_dir_all(&temp_dir);
+    result
+}
+
+/// Extract a zip archive into `dest`, rejecting entries that escape it
+fn extract_zip(archive_path: &Path, dest: &Path) -> Result<(), String> {
+    let file =
+        fs::File::open(archive_path).map_err(|e| format!("Failed to open archive: {}", e))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Invalid zip archive: {}", e))?;
+
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| format!("Failed to read archive entry: {}", e))?;
+
+        // enclosed_name() is None for absolute paths and `..` components
+        let relative = match entry.enclosed_name() {
+            Some(path) => path,
+            None => {
+                return Err(format!(
+                    "Security error: archive entry '{}' escapes the plugin folder",
+                    entry.name()
+                ))
+            }
+        };
+        let out_path = dest.join(relative);
+
+        if entry.is_dir() {
+            fs::create_dir_all(&out_path).map_err(|e| e.to_string())?;
+            continue;
+        }
+
+        if let Some(parent) = out_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let mut out_file = fs::File::create(&out_path).map_err(|e| e.to_string())?;
+        std::io::copy(&mut entry, &mut out_file).map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Find the folder containing plugin.json: the archive root or a single wrapper folder
+fn find_plugin_root(extracted: &Path) -> Result<PathBuf, String> {
+    if extracted.join("plugin.json").exists() {
+        return Ok(extracted.to_path_buf());
+    }
+
+    // Ignore macOS metadata folders added by Finder's "Compress"
+    let dirs: Vec<PathBuf> = fs::read_dir(extracted)
+        .map_err(|e| e.to_string())?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir() && !path.ends_with("__MACOSX"))
+        .collect();
+
+    match dirs.as_slice() {
+        [wrapper] if wrapper.join("plugin.json").exists() => Ok(wrapper.clone()),
+        _ => Err("plugin.json not found in archive".to_string()),
+    }
+}
+
 /// Delete an installed plugin
 #[command]
 pub fn delete_plugin(_app_handle: AppHandle, plugin_id: String) -> Result<(), String> {
     let plugins_dir = get_plugins_dir();
-    let plugin_path = plugins_dir.join(&plugin_id);
+    let plugin_path = plugin_install_dir(&plugins_dir, &plugin_id)?;
 
     if !plugin_path.exists() {
         return Err(format!("Plugin '{}' not found", plugin_id));
@@ -288,3 +758,35 @@ pub fn delete_plugin(_app_handle: AppHandle, plugin_id: String) -> Result<(), St
 
     fs::remove_dir_all(&plugin_path).map_err(|e| format!("Failed to delete plugin: {}", e))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plugin_ids_stay_inside_plugins_dir() {
+        for id in ["clock", "com.example.weather", "my_plugin-2"] {
+            assert!(is_valid_plugin_id(id), "{}", id);
+        }
+        for id in [
+            "",
+            ".",
+            "..",
+            "../../evil",
+            "a/b",
+            "a\\b",
+            "/abs",
+            "a..b",
+            "sp ace",
+        ] {
+            assert!(!is_valid_plugin_id(id), "{}", id);
+        }
+
+        let plugins_dir = Path::new("/tmp/plugins");
+        assert_eq!(
+            plugin_install_dir(plugins_dir, "clock").unwrap(),
+            plugins_dir.join("clock")
+        );
+        assert!(plugin_install_dir(plugins_dir, "../clock").is_err());
+    }
+}
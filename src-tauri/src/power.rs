@@ -0,0 +1,465 @@
+use crate::database::{get_connection, log_sql};
+use log;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Session is locked (lock screen / screensaver with password)
+static SCREEN_LOCKED: AtomicBool = AtomicBool::new(false);
+/// Displays or the whole system are asleep
+static DISPLAY_ASLEEP: AtomicBool = AtomicBool::new(false);
+/// Whether the overlay should be hidden while locked/asleep (persisted)
+static AUTO_HIDE_WHEN_LOCKED: AtomicBool = AtomicBool::new(true);
+/// Set when the overlay was hidden by us, so it is only re-shown in that case
+static HIDDEN_BY_LOCK: AtomicBool = AtomicBool::new(false);
+
+/// Whether battery saver may kick in when the battery runs low (persisted)
+static BATTERY_SAVER_ENABLED: AtomicBool = AtomicBool::new(false);
+/// Battery percentage at or below which battery saver activates (persisted)
+static BATTERY_SAVER_THRESHOLD: AtomicU8 = AtomicU8::new(20);
+/// Battery saver is currently on: non-essential background work is paused
+static BATTERY_SAVER_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// How often the battery is checked for the battery saver policy
+const BATTERY_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+#[derive(Debug, Serialize, Clone, Copy)]
+pub struct BatteryStatus {
+    /// Charge level, 0-100
+    pub percentage: u8,
+    /// Connected to power (charging or full)
+    pub is_charging: bool,
+}
+
+#[derive(Debug, Serialize, Clone, Copy)]
+pub struct BatterySaverPolicy {
+    pub enabled: bool,
+    pub threshold: u8,
+    pub active: bool,
+}
+
+/// True while the screen is locked or asleep
+pub fn is_screen_inactive() -> bool {
+    SCREEN_LOCKED.load(Ordering::Relaxed) || DISPLAY_ASLEEP.load(Ordering::Relaxed)
+}
+
+/// Monitoring loops should idle while this returns true
+pub fn is_monitoring_paused() -> bool {
+    (AUTO_HIDE_WHEN_LOCKED.load(Ordering::Relaxed) && is_screen_inactive())
+        || crate::window::is_overlay_suppressed()
+        || !crate::window::is_ui_active()
+}
+
+/// Block for up to `timeout` while monitoring is paused, returning as soon as the main
+/// window is shown again. Returns immediately when monitoring isn't paused.
+pub fn idle_while_paused(timeout: std::time::Duration) {
+    let (lock, shown) = &crate::window::UI_SHOWN;
+    let guard = match lock.lock() {
+        Ok(guard) => guard,
+        Err(_) => return,
+    };
+    if !is_monitoring_paused() {
+        return;
+    }
+    let _ = shown.wait_timeout(guard, timeout);
+}
+
+/// True while the overlay is hidden because the screen is locked or asleep
+pub fn is_hidden_by_lock() -> bool {
+    HIDDEN_BY_LOCK.load(Ordering::Relaxed)
+}
+
+fn set_screen_locked(app_handle: &AppHandle, locked: bool) {
+    let was_inactive = is_screen_inactive();
+    SCREEN_LOCKED.store(locked, Ordering::Relaxed);
+    apply_screen_state(app_handle, was_inactive);
+}
+
+fn set_display_asleep(app_handle: &AppHandle, asleep: bool) {
+    let was_inactive = is_screen_inactive();
+    DISPLAY_ASLEEP.store(asleep, Ordering::Relaxed);
+    apply_screen_state(app_handle, was_inactive);
+}
+
+/// Emit events and hide/show the overlay when the combined lock/sleep state flips
+fn apply_screen_state(app_handle: &AppHandle, was_inactive: bool) {
+    let inactive = is_screen_inactive();
+    if inactive == was_inactive {
+        return;
+    }
+
+    if inactive {
+        log::info!("Screen locked or asleep, pausing overlay");
+        let _ = app_handle.emit("screen-locked", ());
+
+        if AUTO_HIDE_WHEN_LOCKED.load(Ordering::Relaxed) {
+            if let Some(window) = app_handle.get_webview_window("main") {
+                if window.is_visible().unwrap_or(false) && window.hide().is_ok() {
+                    HIDDEN_BY_LOCK.store(true, Ordering::Relaxed);
+                    crate::window::set_ui_active(false);
+                }
+            }
+        }
+    } else {
+        log::info!("Screen unlocked and awake, resuming overlay");
+        let _ = app_handle.emit("screen-unlocked", ());
+        restore_hidden_window(app_handle);
+    }
+}
+
+fn restore_hidden_window(app_handle: &AppHandle) {
+    if HIDDEN_BY_LOCK.swap(false, Ordering::Relaxed) {
+        if let Some(window) = app_handle.get_webview_window("main") {
+            let _ = window.show();
+        }
+        crate::window::set_ui_active(true);
+    }
+}
+
+/// Load the persisted auto-hide setting (defaults to enabled)
+pub fn initialize_auto_hide_preference(app_handle: &AppHandle) {
+    let enabled = get_connection(app_handle)
+        .ok()
+        .and_then(|conn| {
+            let sql = "SELECT value FROM settings WHERE key = 'auto_hide_when_locked'";
+            log_sql(sql);
+            conn.query_row(sql, [], |row| row.get::<_, String>(0)).ok()
+        })
+        .map(|value| value == "true")
+        .unwrap_or(true);
+
+    AUTO_HIDE_WHEN_LOCKED.store(enabled, Ordering::Relaxed);
+}
+
+/// Enable or disable hiding the overlay (and pausing monitoring) while locked/asleep
+#[tauri::command]
+pub fn set_auto_hide_when_locked(app_handle: AppHandle, enabled: bool) -> Result<(), String> {
+    AUTO_HIDE_WHEN_LOCKED.store(enabled, Ordering::Relaxed);
+
+    let conn = get_connection(&app_handle).map_err(|e| e.to_string())?;
+    let sql = "INSERT OR REPLACE INTO settings (key, value) VALUES ('auto_hide_when_locked', ?1)";
+    log_sql(sql);
+    conn.execute(sql, rusqlite::params![enabled.to_string()])
+        .map_err(|e| e.to_string())?;
+
+    if !enabled {
+        restore_hidden_window(&app_handle);
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_auto_hide_when_locked() -> bool {
+    AUTO_HIDE_WHEN_LOCKED.load(Ordering::Relaxed)
+}
+
+/// Observe screen lock/unlock and display sleep/wake (macOS)
+#[cfg(target_os = "macos")]
+pub fn setup_screen_state_monitoring(app_handle: AppHandle) {
+    use crate::utils::add_notification_observer;
+    use objc2::runtime::AnyObject;
+    use objc2::*;
+
+    unsafe {
+        let distributed: *mut AnyObject =
+            msg_send![class!(NSDistributedNotificationCenter), defaultCenter];
+        let workspace: *mut AnyObject = msg_send![class!(NSWorkspace), sharedWorkspace];
+        let workspace_center: *mut AnyObject = msg_send![workspace, notificationCenter];
+
+        for (name, locked) in [
+            ("com.apple.screenIsLocked", true),
+            ("com.apple.screenIsUnlocked", false),
+        ] {
+            add_notification_observer(distributed, name, &app_handle, move |h| {
+                set_screen_locked(h, locked)
+            });
+        }
+
+        // Display sleep (screensaver timeout, lid closed) and full system sleep
+        for (name, asleep) in [
+            ("NSWorkspaceScreensDidSleepNotification", true),
+            ("NSWorkspaceScreensDidWakeNotification", false),
+            ("NSWorkspaceWillSleepNotification", true),
+            ("NSWorkspaceDidWakeNotification", false),
+        ] {
+            add_notification_observer(workspace_center, name, &app_handle, move |h| {
+                set_display_asleep(h, asleep)
+            });
+        }
+    }
+
+    log::info!("Screen lock/sleep observers registered");
+}
+
+/// Poll for the secure desktop, which replaces the input desktop while locked (Windows)
+#[cfg(target_os = "windows")]
+pub fn setup_screen_state_monitoring(app_handle: AppHandle) {
+    use windows::Win32::Foundation::FALSE;
+    use windows::Win32::System::StationsAndDesktops::{
+        CloseDesktop, OpenInputDesktop, DESKTOP_CONTROL_FLAGS, DESKTOP_SWITCHDESKTOP,
+    };
+
+    std::thread::spawn(move || loop {
+        // OpenInputDesktop fails while the Winlogon desktop is active (lock screen, UAC)
+        let locked = unsafe {
+            match OpenInputDesktop(DESKTOP_CONTROL_FLAGS(0), FALSE, DESKTOP_SWITCHDESKTOP) {
+                Ok(desktop) => {
+                    let _ = CloseDesktop(desktop);
+                    false
+                }
+                Err(_) => true,
+            }
+        };
+
+        if locked != SCREEN_LOCKED.load(Ordering::Relaxed) {
+            set_screen_locked(&app_handle, locked);
+        }
+
+        std::thread::sleep(std::time::Duration::from_secs(1));
+    });
+}
+
+/// Listen for logind Lock/Unlock and PrepareForSleep signals (Linux)
+#[cfg(target_os = "linux")]
+pub fn setup_screen_state_monitoring(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = watch_logind_signals(&app_handle).await {
+            log::warn!("logind signal subscription failed: {}", e);
+        }
+    });
+}
+
+#[cfg(target_os = "linux")]
+async fn watch_logind_signals(app_handle: &AppHandle) -> zbus::Result<()> {
+    use futures_util::StreamExt;
+    use zbus::{Connection, MatchRule, MessageStream};
+
+    let conn = Connection::system().await?;
+
+    let rule = MatchRule::builder()
+        .msg_type(zbus::message::Type::Signal)
+        .sender("org.freedesktop.login1")?
+        .build();
+
+    let mut stream = MessageStream::for_match_rule(rule, &conn, Some(16)).await?;
+    log::info!("Subscribed to logind lock/sleep signals");
+
+    while let Some(msg) = stream.next().await {
+        let msg = match msg {
+            Ok(msg) => msg,
+            Err(_) => continue,
+        };
+
+        let header = msg.header();
+        match header.member().map(|m| m.as_str()) {
+            Some("Lock") => set_screen_locked(app_handle, true),
+            Some("Unlock") => set_screen_locked(app_handle, false),
+            Some("PrepareForSleep") => {
+                if let Ok(sleeping) = msg.body().deserialize::<bool>() {
+                    set_display_asleep(app_handle, sleeping);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Err(zbus::Error::Failure(
+        "logind signal stream ended".to_string(),
+    ))
+}
+
+/// Non-essential watchers (visualizer, speed test) should stay idle while this returns true
+pub fn is_battery_saver_active() -> bool {
+    BATTERY_SAVER_ACTIVE.load(Ordering::Relaxed)
+}
+
+/// Read the battery level and charging state, None on machines without a battery
+#[tauri::command]
+pub fn get_battery_status() -> Option<BatteryStatus> {
+    #[cfg(target_os = "macos")]
+    {
+        use std::process::Command;
+
+        // e.g. "Now drawing from 'Battery Power'\n -InternalBattery-0 (id=1)\t85%; discharging; ..."
+        let output = Command::new("pmset").args(["-g", "batt"]).output().ok()?;
+        let text = String::from_utf8_lossy(&output.stdout);
+        let line = text.lines().find(|line| line.contains("InternalBattery"))?;
+
+        let percent_end = line.find('%')?;
+        let percentage = line[..percent_end]
+            .rsplit(|c: char| !c.is_ascii_digit())
+            .next()?
+            .parse::<u8>()
+            .ok()?;
+        let state = line[percent_end + 1..]
+            .split(';')
+            .nth(1)
+            .unwrap_or_default()
+            .trim();
+
+        Some(BatteryStatus {
+            percentage: percentage.min(100),
+            is_charging: state != "discharging",
+        })
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+        /// BatteryFlag bit meaning the system has no battery
+        const NO_SYSTEM_BATTERY: u8 = 128;
+
+        let mut status = SYSTEM_POWER_STATUS::default();
+        unsafe { GetSystemPowerStatus(&mut status) }.ok()?;
+
+        // 255 means the charge level is unknown
+        if status.BatteryFlag & NO_SYSTEM_BATTERY != 0 || status.BatteryLifePercent > 100 {
+            return None;
+        }
+
+        Some(BatteryStatus {
+            percentage: status.BatteryLifePercent,
+            is_charging: status.ACLineStatus == 1,
+        })
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let read = |path: std::path::PathBuf| {
+            std::fs::read_to_string(path)
+                .ok()
+                .map(|value| value.trim().to_string())
+        };
+
+        std::fs::read_dir("/sys/class/power_supply")
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .find(|path| read(path.join("type")).as_deref() == Some("Battery"))
+            .and_then(|battery| {
+                let percentage = read(battery.join("capacity"))?.parse::<u8>().ok()?;
+                let status = read(battery.join("status")).unwrap_or_default();
+                Some(BatteryStatus {
+                    percentage: percentage.min(100),
+                    is_charging: status != "Discharging",
+                })
+            })
+    }
+}
+
+/// Turn battery saver on or off to match the policy and current battery state
+fn evaluate_battery_saver(app_handle: &AppHandle) {
+    let status = get_battery_status();
+    let should_be_active = BATTERY_SAVER_ENABLED.load(Ordering::Relaxed)
+        && match status {
+            Some(status) => {
+                !status.is_charging
+                    && status.percentage <= BATTERY_SAVER_THRESHOLD.load(Ordering::Relaxed)
+            }
+            None => false,
+        };
+
+    if BATTERY_SAVER_ACTIVE.swap(should_be_active, Ordering::Relaxed) == should_be_active {
+        return;
+    }
+
+    if should_be_active {
+        log::info!("Battery low, battery saver activated");
+        if let Some(status) = status {
+            crate::toast::show(
+                app_handle,
+                &format!("Battery at {}%, battery saver on", status.percentage),
+                crate::toast::DEFAULT_DURATION_MS,
+                crate::toast::ToastLevel::Warning,
+            );
+        }
+        let _ = app_handle.emit("battery-saver-activated", status);
+    } else {
+        log::info!("Battery saver deactivated");
+        let _ = app_handle.emit("battery-saver-deactivated", status);
+    }
+}
+
+/// Load the persisted battery saver policy and start checking the battery
+pub fn setup_battery_saver_monitoring(app_handle: AppHandle) {
+    if let Ok(conn) = get_connection(&app_handle) {
+        let sql = "SELECT key, value FROM settings WHERE key IN ('battery_saver_enabled', 'battery_saver_threshold')";
+        log_sql(sql);
+        if let Ok(mut stmt) = conn.prepare(sql) {
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            });
+            if let Ok(rows) = rows {
+                for (key, value) in rows.flatten() {
+                    match key.as_str() {
+                        "battery_saver_enabled" => {
+                            BATTERY_SAVER_ENABLED.store(value == "true", Ordering::Relaxed)
+                        }
+                        "battery_saver_threshold" => {
+                            if let Ok(threshold) = value.parse::<u8>() {
+                                BATTERY_SAVER_THRESHOLD
+                                    .store(threshold.min(100), Ordering::Relaxed);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    // Nothing to watch without a battery
+    if get_battery_status().is_none() {
+        return;
+    }
+
+    std::thread::spawn(move || loop {
+        evaluate_battery_saver(&app_handle);
+        std::thread::sleep(BATTERY_POLL_INTERVAL);
+    });
+}
+
+/// Automatically pause non-essential background work (visualizer, speed test) while on
+/// battery at or below `threshold` percent, resuming once charging. Emits
+/// `battery-saver-activated` / `battery-saver-deactivated` with the battery status.
+#[tauri::command]
+pub fn set_battery_saver_policy(
+    app_handle: AppHandle,
+    threshold: u8,
+    enabled: bool,
+) -> Result<BatterySaverPolicy, String> {
+    if threshold > 100 {
+        return Err("Threshold must be between 0 and 100".to_string());
+    }
+
+    BATTERY_SAVER_THRESHOLD.store(threshold, Ordering::Relaxed);
+    BATTERY_SAVER_ENABLED.store(enabled, Ordering::Relaxed);
+
+    let conn = get_connection(&app_handle).map_err(|e| e.to_string())?;
+    let sql = "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)";
+    log_sql(sql);
+    conn.execute(
+        sql,
+        rusqlite::params!["battery_saver_enabled", enabled.to_string()],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute(
+        sql,
+        rusqlite::params!["battery_saver_threshold", threshold.to_string()],
+    )
+    .map_err(|e| e.to_string())?;
+
+    evaluate_battery_saver(&app_handle);
+    Ok(get_battery_saver_policy())
+}
+
+#[tauri::command]
+pub fn get_battery_saver_policy() -> BatterySaverPolicy {
+    BatterySaverPolicy {
+        enabled: BATTERY_SAVER_ENABLED.load(Ordering::Relaxed),
+        threshold: BATTERY_SAVER_THRESHOLD.load(Ordering::Relaxed),
+        active: is_battery_saver_active(),
+    }
+}
@@ -0,0 +1,137 @@
+use crate::database::{get_connection, log_sql};
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+use tauri::{AppHandle, Emitter};
+
+/// Do-not-disturb window in local hours. `end_hour` is exclusive and may be earlier than
+/// `start_hour` for windows that wrap past midnight (e.g. 22-7).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct QuietHours {
+    pub start_hour: u8,
+    pub end_hour: u8,
+    pub enabled: bool,
+}
+
+impl QuietHours {
+    fn contains(&self, hour: u8) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        if self.start_hour < self.end_hour {
+            hour >= self.start_hour && hour < self.end_hour
+        } else {
+            hour >= self.start_hour || hour < self.end_hour
+        }
+    }
+}
+
+/// Off until the user enables it
+const DEFAULT_QUIET_HOURS: QuietHours = QuietHours {
+    start_hour: 22,
+    end_hour: 7,
+    enabled: false,
+};
+
+/// Current quiet hours (persisted)
+static QUIET_HOURS: RwLock<QuietHours> = RwLock::new(DEFAULT_QUIET_HOURS);
+
+/// Current hour in the local timezone
+fn local_hour() -> u8 {
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    {
+        let now = unsafe { libc::time(std::ptr::null_mut()) };
+        let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+        if unsafe { libc::localtime_r(&now, &mut tm) }.is_null() {
+            return 0;
+        }
+        tm.tm_hour as u8
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let now = unsafe { windows::Win32::System::SystemInformation::GetLocalTime() };
+        now.wHour as u8
+    }
+}
+
+/// Load the saved quiet hours
+pub fn initialize_quiet_hours(app_handle: &AppHandle) {
+    let stored = get_connection(app_handle)
+        .ok()
+        .and_then(|conn| {
+            let sql = "SELECT value FROM settings WHERE key = 'quiet_hours'";
+            log_sql(sql);
+            conn.query_row(sql, [], |row| row.get::<_, String>(0)).ok()
+        })
+        .and_then(|value| serde_json::from_str::<QuietHours>(&value).ok());
+
+    if let Some(quiet_hours) = stored {
+        if let Ok(mut current) = QUIET_HOURS.write() {
+            *current = quiet_hours;
+        }
+    }
+}
+
+/// Whether toasts, reminder notifications and haptics are currently suppressed
+#[tauri::command]
+pub fn is_quiet_hours_active() -> bool {
+    QUIET_HOURS
+        .read()
+        .map(|quiet_hours| quiet_hours.contains(local_hour()))
+        .unwrap_or(false)
+}
+
+/// True (and logs what was dropped) when `kind` output should be suppressed right now
+pub fn suppressed(kind: &str, detail: &str) -> bool {
+    if !is_quiet_hours_active() {
+        return false;
+    }
+    log::info!("Quiet hours: suppressed {} {}", kind, detail);
+    true
+}
+
+/// Set the quiet hours window (local hours 0-23, end exclusive, may wrap past midnight)
+/// and whether it applies. Persisted, emits `quiet-hours-changed`.
+#[tauri::command]
+pub fn set_quiet_hours(
+    app_handle: AppHandle,
+    start_hour: u8,
+    end_hour: u8,
+    enabled: bool,
+) -> Result<(), String> {
+    if start_hour > 23 || end_hour > 23 {
+        return Err("Quiet hours must be between 0 and 23".to_string());
+    }
+    if start_hour == end_hour {
+        return Err("Quiet hours start and end must differ".to_string());
+    }
+
+    let quiet_hours = QuietHours {
+        start_hour,
+        end_hour,
+        enabled,
+    };
+    let value = serde_json::to_string(&quiet_hours).map_err(|e| e.to_string())?;
+
+    let conn = get_connection(&app_handle).map_err(|e| e.to_string())?;
+    let sql = "INSERT OR REPLACE INTO settings (key, value) VALUES ('quiet_hours', ?1)";
+    log_sql(sql);
+    conn.execute(sql, rusqlite::params![value])
+        .map_err(|e| e.to_string())?;
+
+    if let Ok(mut current) = QUIET_HOURS.write() {
+        *current = quiet_hours;
+    }
+
+    let _ = app_handle.emit("quiet-hours-changed", quiet_hours);
+    Ok(())
+}
+
+/// Get the quiet hours window and whether it's enabled
+#[tauri::command]
+pub fn get_quiet_hours() -> QuietHours {
+    QUIET_HOURS
+        .read()
+        .map(|quiet_hours| *quiet_hours)
+        .unwrap_or(DEFAULT_QUIET_HOURS)
+}
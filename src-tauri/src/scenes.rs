@@ -0,0 +1,168 @@
+use crate::database::{get_connection, log_sql};
+use crate::window::{ClickAction, WindowSettings};
+use rusqlite::OptionalExtension;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+/// A saved settings preset
+#[derive(Serialize, Clone)]
+pub struct Scene {
+    pub name: String,
+    /// Serialized SceneSettings
+    pub settings_json: String,
+}
+
+/// Settings captured by a scene. Fields are optional so scenes saved by older
+/// versions still apply, leaving anything they don't know about untouched.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct SceneSettings {
+    #[serde(default)]
+    pub window: Option<WindowSettings>,
+    #[serde(default)]
+    pub dock_icon_visible: Option<bool>,
+    #[serde(default)]
+    pub auto_hide_when_locked: Option<bool>,
+    #[serde(default)]
+    pub click_action: Option<ClickAction>,
+    #[serde(default)]
+    pub suppress_volume_hud: Option<bool>,
+    #[serde(default)]
+    pub scrobbling_enabled: Option<bool>,
+    /// Frontend-owned settings (appearance, media source, ...) passed through as-is
+    #[serde(default)]
+    pub frontend: Option<serde_json::Value>,
+}
+
+fn ensure_scenes_table(conn: &rusqlite::Connection) -> Result<(), String> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS scenes (
+            name TEXT PRIMARY KEY,
+            settings_json TEXT NOT NULL
+        )",
+        [],
+    )
+    .map(|_| ())
+    .map_err(|e| e.to_string())
+}
+
+/// Snapshot the current backend settings
+fn capture_settings(app_handle: &AppHandle, frontend: Option<serde_json::Value>) -> SceneSettings {
+    SceneSettings {
+        window: Some(crate::window::get_window_settings()),
+        dock_icon_visible: crate::window::get_dock_icon_preference(),
+        auto_hide_when_locked: Some(crate::power::get_auto_hide_when_locked()),
+        click_action: Some(crate::window::get_click_action(app_handle.clone())),
+        suppress_volume_hud: Some(crate::audio::get_suppress_volume_hud()),
+        scrobbling_enabled: Some(crate::scrobble::is_scrobbling_enabled()),
+        frontend,
+    }
+}
+
+/// Save the current settings as a scene (replacing one with the same name).
+/// `frontend` carries settings only the frontend knows about.
+#[tauri::command]
+pub fn save_scene(
+    app_handle: AppHandle,
+    name: String,
+    frontend: Option<serde_json::Value>,
+) -> Result<Scene, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Scene name cannot be empty".to_string());
+    }
+
+    let settings = capture_settings(&app_handle, frontend);
+    let settings_json = serde_json::to_string(&settings).map_err(|e| e.to_string())?;
+
+    let conn = get_connection(&app_handle).map_err(|e| e.to_string())?;
+    ensure_scenes_table(&conn)?;
+
+    let sql = "INSERT OR REPLACE INTO scenes (name, settings_json) VALUES (?1, ?2)";
+    log_sql(&format!("{} [{}]", sql, name));
+    conn.execute(sql, rusqlite::params![name, settings_json])
+        .map_err(|e| e.to_string())?;
+
+    Ok(Scene {
+        name,
+        settings_json,
+    })
+}
+
+#[tauri::command]
+pub fn list_scenes(app_handle: AppHandle) -> Result<Vec<Scene>, String> {
+    let conn = get_connection(&app_handle).map_err(|e| e.to_string())?;
+    ensure_scenes_table(&conn)?;
+
+    let sql = "SELECT name, settings_json FROM scenes ORDER BY name COLLATE NOCASE";
+    log_sql(sql);
+
+    let mut stmt = conn.prepare(sql).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(Scene {
+                name: row.get(0)?,
+                settings_json: row.get(1)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
+/// Restore a scene's settings, re-apply the window and emit `scene-applied`
+/// (with the scene's settings, so the frontend can restore its own part)
+#[tauri::command]
+pub fn apply_scene(app_handle: AppHandle, name: String) -> Result<SceneSettings, String> {
+    let conn = get_connection(&app_handle).map_err(|e| e.to_string())?;
+    ensure_scenes_table(&conn)?;
+
+    let sql = "SELECT settings_json FROM scenes WHERE name = ?1";
+    log_sql(sql);
+    let settings_json = conn
+        .query_row(sql, rusqlite::params![name], |row| row.get::<_, String>(0))
+        .optional()
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Scene not found: {}", name))?;
+
+    let settings: SceneSettings = serde_json::from_str(&settings_json)
+        .map_err(|e| format!("Invalid scene settings: {}", e))?;
+
+    if let Some(visible) = settings.dock_icon_visible {
+        crate::window::set_dock_icon_visible(app_handle.clone(), visible)?;
+    }
+    if let Some(enabled) = settings.auto_hide_when_locked {
+        crate::power::set_auto_hide_when_locked(app_handle.clone(), enabled)?;
+    }
+    if let Some(action) = settings.click_action.clone() {
+        crate::window::set_click_action(app_handle.clone(), action)?;
+    }
+    if let Some(suppress) = settings.suppress_volume_hud {
+        crate::audio::set_suppress_volume_hud(suppress);
+    }
+    if let Some(enabled) = settings.scrobbling_enabled {
+        crate::scrobble::set_scrobbling_enabled(app_handle.clone(), enabled)?;
+    }
+    // Last, since it resizes the window
+    if let Some(window) = settings.window {
+        crate::window::apply_window_settings(&app_handle, window)?;
+    }
+
+    log::info!("Applied scene {}", name);
+    let _ = app_handle.emit("scene-applied", &settings);
+
+    Ok(settings)
+}
+
+#[tauri::command]
+pub fn delete_scene(app_handle: AppHandle, name: String) -> Result<(), String> {
+    let conn = get_connection(&app_handle).map_err(|e| e.to_string())?;
+    ensure_scenes_table(&conn)?;
+
+    let sql = "DELETE FROM scenes WHERE name = ?1";
+    log_sql(&format!("{} [{}]", sql, name));
+    conn.execute(sql, rusqlite::params![name])
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
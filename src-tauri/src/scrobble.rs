@@ -0,0 +1,504 @@
+use crate::database::{get_connection, log_sql};
+use crate::models::NowPlayingData;
+use log;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock, RwLock};
+use tauri::{AppHandle, Emitter, Listener};
+
+const LASTFM_API_URL: &str = "https://ws.audioscrobbler.com/2.0/";
+
+/// Last.fm allows up to 50 scrobbles per batch
+const MAX_BATCH: usize = 50;
+
+/// Error codes returned by Last.fm when the session or API key is no longer valid
+const AUTH_ERROR_CODES: [i64; 5] = [4, 9, 10, 14, 26];
+
+/// Error codes for temporary Last.fm failures; any other error rejects the batch for good
+const TRANSIENT_ERROR_CODES: [i64; 4] = [8, 11, 16, 29];
+
+static SCROBBLING_ENABLED: AtomicBool = AtomicBool::new(false);
+static LASTFM_SESSION: OnceLock<RwLock<Option<String>>> = OnceLock::new();
+/// Prevents overlapping flushes from submitting the same queued rows twice
+static FLUSHING: AtomicBool = AtomicBool::new(false);
+static CURRENT_PLAY: OnceLock<Mutex<Option<TrackedPlay>>> = OnceLock::new();
+
+/// The track currently being listened to and whether it has been scrobbled
+struct TrackedPlay {
+    artist: String,
+    track: String,
+    album: Option<String>,
+    duration: Option<f64>,
+    /// Unix timestamp the track started playing
+    started_at: i64,
+    last_elapsed: f64,
+    scrobbled: bool,
+}
+
+fn unix_now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+fn api_credentials() -> Result<(&'static str, &'static str), String> {
+    match (
+        option_env!("LASTFM_API_KEY"),
+        option_env!("LASTFM_API_SECRET"),
+    ) {
+        (Some(key), Some(secret)) => Ok((key, secret)),
+        _ => Err("Last.fm API credentials are not configured in this build".to_string()),
+    }
+}
+
+fn session_key() -> Option<String> {
+    LASTFM_SESSION
+        .get_or_init(|| RwLock::new(None))
+        .read()
+        .ok()
+        .and_then(|guard| guard.clone())
+}
+
+/// Service and account the session key is stored under in the OS credential store
+const CREDENTIAL_SERVICE: &str = "com.opennook.lastfm";
+const CREDENTIAL_ACCOUNT: &str = "session_key";
+
+/// Read the session key from the Keychain (macOS)
+#[cfg(target_os = "macos")]
+fn load_session_key(_app_handle: &AppHandle) -> Option<String> {
+    security_framework::passwords::get_generic_password(CREDENTIAL_SERVICE, CREDENTIAL_ACCOUNT)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .filter(|key| !key.is_empty())
+}
+
+/// Save or delete the session key in the Keychain (macOS)
+#[cfg(target_os = "macos")]
+fn persist_session_key(_app_handle: &AppHandle, key: Option<&str>) -> Result<(), String> {
+    use security_framework::passwords::{delete_generic_password, set_generic_password};
+
+    match key {
+        Some(key) => set_generic_password(CREDENTIAL_SERVICE, CREDENTIAL_ACCOUNT, key.as_bytes())
+            .map_err(|e| format!("Failed to store Last.fm session in Keychain: {}", e)),
+        None => {
+            // Deleting a missing item is not an error for our purposes
+            let _ = delete_generic_password(CREDENTIAL_SERVICE, CREDENTIAL_ACCOUNT);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn credential_entry() -> Result<keyring::Entry, String> {
+    keyring::Entry::new(CREDENTIAL_SERVICE, CREDENTIAL_ACCOUNT).map_err(|e| e.to_string())
+}
+
+/// Read the session key from Credential Manager / Secret Service, moving over a
+/// key left in plain text in the settings table by older builds
+#[cfg(not(target_os = "macos"))]
+fn load_session_key(app_handle: &AppHandle) -> Option<String> {
+    if let Some(key) = credential_entry()
+        .ok()
+        .and_then(|entry| entry.get_password().ok())
+        .filter(|key| !key.is_empty())
+    {
+        return Some(key);
+    }
+
+    let legacy = get_connection(app_handle).ok().and_then(|conn| {
+        let sql = "SELECT value FROM settings WHERE key = 'lastfm_session_key'";
+        log_sql(sql);
+        conn.query_row(sql, [], |row| row.get::<_, String>(0)).ok()
+    })?;
+    if legacy.is_empty() {
+        return None;
+    }
+
+    if let Err(e) = persist_session_key(app_handle, Some(&legacy)) {
+        log::warn!(
+            "Failed to migrate Last.fm session to the credential store: {}",
+            e
+        );
+    }
+    Some(legacy)
+}
+
+/// Save or delete the session key in Credential Manager / Secret Service
+#[cfg(not(target_os = "macos"))]
+fn persist_session_key(app_handle: &AppHandle, key: Option<&str>) -> Result<(), String> {
+    let entry = credential_entry()?;
+    match key {
+        Some(key) => entry
+            .set_password(key)
+            .map_err(|e| format!("Failed to store Last.fm session in credential store: {}", e))?,
+        None => match entry.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => {}
+            Err(e) => return Err(e.to_string()),
+        },
+    }
+
+    // Never leave a plain-text copy behind in the database
+    let conn = get_connection(app_handle).map_err(|e| e.to_string())?;
+    let sql = "DELETE FROM settings WHERE key = 'lastfm_session_key'";
+    log_sql(sql);
+    conn.execute(sql, []).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Persist (or clear, with None) the session key and update the in-memory copy
+fn store_session_key(app_handle: &AppHandle, key: Option<&str>) -> Result<(), String> {
+    persist_session_key(app_handle, key)?;
+
+    if let Ok(mut guard) = LASTFM_SESSION.get_or_init(|| RwLock::new(None)).write() {
+        *guard = key.map(|k| k.to_string());
+    }
+
+    Ok(())
+}
+
+/// Load the persisted scrobbling preference and session key
+pub fn initialize_scrobbler(app_handle: &AppHandle) {
+    let enabled = get_connection(app_handle).ok().and_then(|conn| {
+        let sql = "SELECT value FROM settings WHERE key = 'scrobbling_enabled'";
+        log_sql(sql);
+        conn.query_row(sql, [], |row| row.get::<_, String>(0))
+            .ok()
+            .map(|value| value == "true")
+    });
+    SCROBBLING_ENABLED.store(enabled.unwrap_or(false), Ordering::Relaxed);
+
+    let session = load_session_key(app_handle);
+    if let Ok(mut guard) = LASTFM_SESSION.get_or_init(|| RwLock::new(None)).write() {
+        *guard = session;
+    }
+
+    // Submit anything queued while offline during the last run
+    if SCROBBLING_ENABLED.load(Ordering::Relaxed) && session_key().is_some() {
+        spawn_flush(app_handle.clone());
+    }
+
+    // Submit the queue as soon as the network comes back
+    let handle = app_handle.clone();
+    app_handle.listen("connectivity-changed", move |event| {
+        let online = serde_json::from_str::<serde_json::Value>(event.payload())
+            .ok()
+            .and_then(|status| status.get("online").and_then(|o| o.as_bool()))
+            .unwrap_or(false);
+        if online && SCROBBLING_ENABLED.load(Ordering::Relaxed) && session_key().is_some() {
+            spawn_flush(handle.clone());
+        }
+    });
+}
+
+/// Store the Last.fm session key obtained from the web auth flow (empty string signs out)
+#[tauri::command]
+pub fn set_lastfm_session(app_handle: AppHandle, session_key: String) -> Result<(), String> {
+    let key = session_key.trim();
+    if key.is_empty() {
+        return store_session_key(&app_handle, None);
+    }
+
+    store_session_key(&app_handle, Some(key))?;
+    spawn_flush(app_handle);
+    Ok(())
+}
+
+/// Enable or disable scrobbling to Last.fm
+#[tauri::command]
+pub fn set_scrobbling_enabled(app_handle: AppHandle, enabled: bool) -> Result<(), String> {
+    SCROBBLING_ENABLED.store(enabled, Ordering::Relaxed);
+
+    let conn = get_connection(&app_handle).map_err(|e| e.to_string())?;
+    let sql = "INSERT OR REPLACE INTO settings (key, value) VALUES ('scrobbling_enabled', ?1)";
+    log_sql(sql);
+    conn.execute(sql, rusqlite::params![enabled.to_string()])
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+pub fn is_scrobbling_enabled() -> bool {
+    SCROBBLING_ENABLED.load(Ordering::Relaxed)
+}
+
+/// Called from get_now_playing on every poll. Queues a scrobble once the track
+/// has played past half its length or 4 minutes, whichever comes first.
+pub fn track_progress(app_handle: &AppHandle, data: &NowPlayingData, track_changed: bool) {
+    if !SCROBBLING_ENABLED.load(Ordering::Relaxed) || session_key().is_none() {
+        return;
+    }
+
+    let (artist, track) = match (&data.artist, &data.title) {
+        (Some(artist), Some(track)) if !artist.is_empty() && !track.is_empty() => {
+            (artist.clone(), track.clone())
+        }
+        _ => return,
+    };
+    let elapsed = data.elapsed_time.unwrap_or(0.0);
+
+    let store = CURRENT_PLAY.get_or_init(|| Mutex::new(None));
+    let mut guard = match store.lock() {
+        Ok(guard) => guard,
+        Err(_) => return,
+    };
+
+    // A new track, or the same track restarted from the beginning (repeat)
+    let restarted = guard
+        .as_ref()
+        .map(|play| play.scrobbled && elapsed + 30.0 < play.last_elapsed)
+        .unwrap_or(false);
+
+    if track_changed || restarted || guard.is_none() {
+        *guard = Some(TrackedPlay {
+            artist,
+            track,
+            album: data.album.clone(),
+            duration: data.duration,
+            started_at: unix_now() - elapsed as i64,
+            last_elapsed: elapsed,
+            scrobbled: false,
+        });
+        return;
+    }
+
+    let play = match guard.as_mut() {
+        Some(play) => play,
+        None => return,
+    };
+    play.last_elapsed = elapsed;
+
+    if play.scrobbled || !data.is_playing {
+        return;
+    }
+
+    // Last.fm ignores tracks shorter than 30 seconds
+    let duration = play.duration.unwrap_or(0.0);
+    if duration > 0.0 && duration < 30.0 {
+        return;
+    }
+
+    let threshold = if duration > 0.0 {
+        (duration / 2.0).min(240.0)
+    } else {
+        240.0
+    };
+    if elapsed < threshold {
+        return;
+    }
+
+    play.scrobbled = true;
+    if let Err(e) = enqueue_scrobble(app_handle, play) {
+        log::error!("Failed to queue scrobble: {}", e);
+        return;
+    }
+    drop(guard);
+
+    spawn_flush(app_handle.clone());
+}
+
+fn ensure_queue_table(conn: &rusqlite::Connection) -> Result<(), String> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS scrobble_queue (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            artist TEXT NOT NULL,
+            track TEXT NOT NULL,
+            album TEXT,
+            duration INTEGER,
+            timestamp INTEGER NOT NULL
+        )",
+        [],
+    )
+    .map(|_| ())
+    .map_err(|e| e.to_string())
+}
+
+/// Persist the scrobble first so it survives being offline or the app quitting
+fn enqueue_scrobble(app_handle: &AppHandle, play: &TrackedPlay) -> Result<(), String> {
+    let conn = get_connection(app_handle).map_err(|e| e.to_string())?;
+    ensure_queue_table(&conn)?;
+
+    let sql = "INSERT INTO scrobble_queue (artist, track, album, duration, timestamp) VALUES (?1, ?2, ?3, ?4, ?5)";
+    log_sql(sql);
+    conn.execute(
+        sql,
+        rusqlite::params![
+            play.artist,
+            play.track,
+            play.album,
+            play.duration.map(|d| d as i64),
+            play.started_at
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    log::info!("Queued scrobble: {} - {}", play.artist, play.track);
+    Ok(())
+}
+
+struct QueuedScrobble {
+    id: i64,
+    artist: String,
+    track: String,
+    album: Option<String>,
+    duration: Option<i64>,
+    timestamp: i64,
+}
+
+fn load_queue(app_handle: &AppHandle) -> Result<Vec<QueuedScrobble>, String> {
+    let conn = get_connection(app_handle).map_err(|e| e.to_string())?;
+    ensure_queue_table(&conn)?;
+
+    let sql = "SELECT id, artist, track, album, duration, timestamp FROM scrobble_queue ORDER BY timestamp LIMIT ?1";
+    log_sql(sql);
+    let mut stmt = conn.prepare(sql).map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map([MAX_BATCH as i64], |row| {
+            Ok(QueuedScrobble {
+                id: row.get(0)?,
+                artist: row.get(1)?,
+                track: row.get(2)?,
+                album: row.get(3)?,
+                duration: row.get(4)?,
+                timestamp: row.get(5)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
+fn remove_from_queue(app_handle: &AppHandle, ids: &[i64]) -> Result<(), String> {
+    let conn = get_connection(app_handle).map_err(|e| e.to_string())?;
+    let sql = "DELETE FROM scrobble_queue WHERE id = ?1";
+    log_sql(sql);
+
+    for id in ids {
+        conn.execute(sql, [id]).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Sign request parameters as described in the Last.fm API auth spec
+fn sign(params: &[(String, String)], secret: &str) -> String {
+    let mut sorted: Vec<&(String, String)> = params.iter().collect();
+    sorted.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut payload: String = sorted.iter().map(|(k, v)| format!("{}{}", k, v)).collect();
+    payload.push_str(secret);
+
+    format!("{:x}", md5::compute(payload.as_bytes()))
+}
+
+fn spawn_flush(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = flush_queue(&app_handle).await {
+            log::warn!("Scrobble submission deferred: {}", e);
+        }
+    });
+}
+
+/// Submit queued scrobbles in batches. Rows stay queued on network errors and
+/// transient Last.fm errors and are retried on the next scrobble, reconnect or
+/// app launch; batches Last.fm rejects outright are dropped.
+async fn flush_queue(app_handle: &AppHandle) -> Result<(), String> {
+    if FLUSHING.swap(true, Ordering::Relaxed) {
+        return Ok(());
+    }
+
+    let result = flush_queue_inner(app_handle).await;
+    FLUSHING.store(false, Ordering::Relaxed);
+    result
+}
+
+async fn flush_queue_inner(app_handle: &AppHandle) -> Result<(), String> {
+    let (api_key, secret) = api_credentials()?;
+    let session = session_key().ok_or("Not signed in to Last.fm")?;
+    crate::connectivity::ensure_online().await?;
+
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    loop {
+        let batch = load_queue(app_handle)?;
+        if batch.is_empty() {
+            return Ok(());
+        }
+
+        let mut params = vec![
+            ("method".to_string(), "track.scrobble".to_string()),
+            ("api_key".to_string(), api_key.to_string()),
+            ("sk".to_string(), session.clone()),
+        ];
+        for (i, scrobble) in batch.iter().enumerate() {
+            params.push((format!("artist[{}]", i), scrobble.artist.clone()));
+            params.push((format!("track[{}]", i), scrobble.track.clone()));
+            params.push((format!("timestamp[{}]", i), scrobble.timestamp.to_string()));
+            if let Some(album) = &scrobble.album {
+                params.push((format!("album[{}]", i), album.clone()));
+            }
+            if let Some(duration) = scrobble.duration {
+                params.push((format!("duration[{}]", i), duration.to_string()));
+            }
+        }
+        params.push(("api_sig".to_string(), sign(&params, secret)));
+        // `format` is not part of the signature
+        params.push(("format".to_string(), "json".to_string()));
+
+        let response = client
+            .post(LASTFM_API_URL)
+            .form(&params)
+            .send()
+            .await
+            .map_err(|e| {
+                format!(
+                    "Last.fm unreachable, {} scrobbles queued: {}",
+                    batch.len(),
+                    e
+                )
+            })?;
+
+        let body: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| format!("Invalid response from Last.fm: {}", e))?;
+
+        if let Some(code) = body.get("error").and_then(|c| c.as_i64()) {
+            let message = body
+                .get("message")
+                .and_then(|m| m.as_str())
+                .unwrap_or("Unknown error")
+                .to_string();
+
+            if AUTH_ERROR_CODES.contains(&code) {
+                // Drop the invalid session so the user is prompted to sign in again.
+                // Queued scrobbles are kept and submitted after re-authentication.
+                let _ = store_session_key(app_handle, None);
+                let _ = app_handle.emit("lastfm-auth-error", message.clone());
+                return Err(format!("Last.fm authentication failed: {}", message));
+            }
+
+            if TRANSIENT_ERROR_CODES.contains(&code) {
+                return Err(format!("Last.fm error {}: {}", code, message));
+            }
+
+            // Resubmitting would fail the same way and block everything queued after it
+            let ids: Vec<i64> = batch.iter().map(|s| s.id).collect();
+            remove_from_queue(app_handle, &ids)?;
+            log::warn!(
+                "Last.fm rejected {} scrobbles (error {}: {}), dropping them",
+                ids.len(),
+                code,
+                message
+            );
+            continue;
+        }
+
+        let ids: Vec<i64> = batch.iter().map(|s| s.id).collect();
+        remove_from_queue(app_handle, &ids)?;
+        log::info!("Submitted {} scrobbles to Last.fm", ids.len());
+    }
+}
@@ -0,0 +1,131 @@
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+/// How long a toast stays up when no duration is given
+pub const DEFAULT_DURATION_MS: u64 = 3000;
+
+/// Longest a toast may stay up
+const MAX_DURATION_MS: u64 = 30_000;
+
+/// The same message isn't shown again within this window
+const DUPLICATE_WINDOW: Duration = Duration::from_secs(5);
+
+/// At most MAX_BURST toasts are shown per BURST_WINDOW
+const BURST_WINDOW: Duration = Duration::from_secs(10);
+const MAX_BURST: usize = 5;
+
+/// Recently shown toasts, oldest first
+static RECENT_TOASTS: Mutex<VecDeque<(Instant, String)>> = Mutex::new(VecDeque::new());
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ToastLevel {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl ToastLevel {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "info" => Some(Self::Info),
+            "success" => Some(Self::Success),
+            "warning" => Some(Self::Warning),
+            "error" => Some(Self::Error),
+            _ => None,
+        }
+    }
+}
+
+/// Payload for the `toast` event
+#[derive(Debug, Clone, Serialize)]
+pub struct Toast {
+    pub message: String,
+    pub duration_ms: u64,
+    pub level: ToastLevel,
+}
+
+/// Claim a slot for `message`, false when it's a recent duplicate or the burst limit is hit
+fn allow_toast(message: &str) -> bool {
+    let mut recent = match RECENT_TOASTS.lock() {
+        Ok(recent) => recent,
+        Err(_) => return false,
+    };
+
+    let now = Instant::now();
+    while recent
+        .front()
+        .is_some_and(|(shown, _)| now.duration_since(*shown) > BURST_WINDOW)
+    {
+        recent.pop_front();
+    }
+
+    let duplicate = recent
+        .iter()
+        .any(|(shown, m)| m == message && now.duration_since(*shown) < DUPLICATE_WINDOW);
+    if duplicate || recent.len() >= MAX_BURST {
+        return false;
+    }
+
+    recent.push_back((now, message.to_string()));
+    true
+}
+
+/// Briefly show a message in the notch via the `toast` event. Rate limited: repeats of
+/// the same message and bursts are dropped. Returns whether the toast was emitted.
+pub fn show(app_handle: &AppHandle, message: &str, duration_ms: u64, level: ToastLevel) -> bool {
+    let message = message.trim();
+    if message.is_empty() || crate::quiet_hours::suppressed("toast", message) {
+        return false;
+    }
+    if !allow_toast(message) {
+        return false;
+    }
+
+    emit_toast(app_handle, message, duration_ms, level);
+    true
+}
+
+/// Show a toast that skips rate limiting and the toast quiet hours check, for notifications
+/// that must not be dropped (e.g. reminders, which check quiet hours themselves)
+pub fn show_unthrottled(
+    app_handle: &AppHandle,
+    message: &str,
+    duration_ms: u64,
+    level: ToastLevel,
+) {
+    let message = message.trim();
+    if !message.is_empty() {
+        emit_toast(app_handle, message, duration_ms, level);
+    }
+}
+
+fn emit_toast(app_handle: &AppHandle, message: &str, duration_ms: u64, level: ToastLevel) {
+    let toast = Toast {
+        message: message.to_string(),
+        duration_ms: duration_ms.clamp(500, MAX_DURATION_MS),
+        level,
+    };
+    let _ = app_handle.emit("toast", toast);
+}
+
+/// Show a toast in the notch. `level` is "info", "success", "warning" or "error".
+/// Returns false when the toast was dropped by rate limiting.
+#[tauri::command]
+pub fn show_toast(
+    app_handle: AppHandle,
+    message: String,
+    duration_ms: u64,
+    level: String,
+) -> Result<bool, String> {
+    let level = ToastLevel::parse(&level).ok_or(format!("Invalid toast level: {}", level))?;
+    if message.trim().is_empty() {
+        return Err("Toast message is empty".to_string());
+    }
+
+    Ok(show(&app_handle, &message, duration_ms, level))
+}
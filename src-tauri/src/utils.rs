@@ -1,3 +1,35 @@
+/// Parse a number printed with any locale's separators ("1,234.5", "1.234,5", "12,5").
+/// Spaces and apostrophes are treated as grouping. With both ',' and '.', the last one is the
+/// decimal mark. A lone ',' or '.' is a decimal mark; one repeated several times is grouping.
+pub fn parse_locale_f64(s: &str) -> Option<f64> {
+    let cleaned: String = s
+        .trim()
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '\'' && *c != '\u{2019}')
+        .collect();
+
+    let commas = cleaned.matches(',').count();
+    let dots = cleaned.matches('.').count();
+
+    let normalized = match (commas, dots) {
+        (0, 0) => cleaned,
+        (_, 0) if commas > 1 => cleaned.replace(',', ""),
+        (_, 0) => cleaned.replace(',', "."),
+        (0, _) if dots > 1 => cleaned.replace('.', ""),
+        (0, _) => cleaned,
+        _ => {
+            let (group, decimal) = if cleaned.rfind(',') > cleaned.rfind('.') {
+                (".", ",")
+            } else {
+                (",", ".")
+            };
+            cleaned.replace(group, "").replace(decimal, ".")
+        }
+    };
+
+    normalized.parse::<f64>().ok().filter(|v| v.is_finite())
+}
+
 /// Simple base64 encoding
 pub fn base64_encode(data: &[u8]) -> String {
     const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
@@ -31,8 +63,41 @@ pub fn base64_encode(data: &[u8]) -> String {
     result
 }
 
-/// Fetch artwork from a URL (used for Spotify)
+/// Check osascript stderr for the Automation-denied error (-1743, errAEEventNotPermitted)
+pub fn is_automation_denied(stderr: &str) -> bool {
+    stderr.contains("-1743")
+}
+
+/// Extract the target app name from an Automation-denied osascript error
+/// e.g. "Not authorized to send Apple events to Spotify. (-1743)" -> "Spotify"
+pub fn automation_denied_app(stderr: &str) -> Option<String> {
+    if !is_automation_denied(stderr) {
+        return None;
+    }
+
+    let app = stderr
+        .split("send Apple events to ")
+        .nth(1)
+        .and_then(|rest| rest.split(". (").next())
+        .map(|app| app.trim().to_string())
+        .filter(|app| !app.is_empty())
+        .unwrap_or_else(|| "Unknown".to_string());
+
+    Some(app)
+}
+
+/// Escape a value for use inside a double-quoted AppleScript string literal
+pub fn applescript_escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Fetch artwork from a URL (used for Spotify), as base64 at full size
 pub fn fetch_artwork_from_url(url: &str) -> Option<String> {
+    fetch_artwork_bytes(url).map(|data| base64_encode(&data))
+}
+
+/// Download artwork from a URL without encoding it
+pub fn fetch_artwork_bytes(url: &str) -> Option<Vec<u8>> {
     use std::process::Command;
 
     if url.is_empty() {
@@ -48,14 +113,38 @@ pub fn fetch_artwork_from_url(url: &str) -> Option<String> {
         .ok()?;
 
     if output.status.success() && !output.stdout.is_empty() {
-        // Encode to base64
-        let base64 = base64_encode(&output.stdout);
-        Some(base64)
+        Some(output.stdout)
     } else {
         None
     }
 }
 
+/// Shrink an encoded image so its longest side is at most `max_dimension`, re-encoded as
+/// JPEG (PNG when it has transparency). None when it's already small enough or can't be
+/// decoded, so the caller keeps the original bytes.
+pub fn downscale_image(data: &[u8], max_dimension: u32) -> Option<Vec<u8>> {
+    use image::codecs::jpeg::JpegEncoder;
+    use image::imageops::FilterType;
+
+    let image = image::load_from_memory(data).ok()?;
+    if image.width().max(image.height()) <= max_dimension {
+        return None;
+    }
+
+    let resized = image.resize(max_dimension, max_dimension, FilterType::Triangle);
+    let mut encoded = std::io::Cursor::new(Vec::new());
+    if resized.color().has_alpha() {
+        resized
+            .write_to(&mut encoded, image::ImageFormat::Png)
+            .ok()?;
+    } else {
+        JpegEncoder::new_with_quality(&mut encoded, 85)
+            .encode_image(&resized.to_rgb8())
+            .ok()?;
+    }
+    Some(encoded.into_inner())
+}
+
 /// Get the system accent color on macOS
 #[cfg(target_os = "macos")]
 pub fn get_macos_accent_color() -> String {
@@ -104,3 +193,67 @@ pub fn get_macos_accent_color() -> String {
         );
     }
 }
+
+/// Register a block observer on an NSNotificationCenter for the app lifetime
+#[cfg(target_os = "macos")]
+pub unsafe fn add_notification_observer(
+    center: *mut objc2::runtime::AnyObject,
+    name: &str,
+    app_handle: &tauri::AppHandle,
+    callback: impl Fn(&tauri::AppHandle) + 'static,
+) {
+    use objc2::runtime::AnyObject;
+    use objc2::*;
+
+    if center.is_null() {
+        return;
+    }
+
+    let handle = app_handle.clone();
+    let block = block2::RcBlock::new(move |_note: std::ptr::NonNull<AnyObject>| {
+        callback(&handle);
+    });
+
+    let name = objc2_foundation::NSString::from_str(name);
+    let nil: *const AnyObject = std::ptr::null();
+    // The center copies the block and retains the returned observer token
+    let _: *mut AnyObject = msg_send![
+        center,
+        addObserverForName: &*name,
+        object: nil,
+        queue: nil,
+        usingBlock: &*block
+    ];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_locale_f64_accepts_either_decimal_mark() {
+        assert_eq!(parse_locale_f64("1.5"), Some(1.5));
+        assert_eq!(parse_locale_f64("1,5"), Some(1.5));
+        assert_eq!(parse_locale_f64(" 42 "), Some(42.0));
+    }
+
+    #[test]
+    fn parse_locale_f64_drops_grouping() {
+        assert_eq!(parse_locale_f64("1,234.5"), Some(1234.5));
+        assert_eq!(parse_locale_f64("1.234,5"), Some(1234.5));
+        assert_eq!(parse_locale_f64("1 234,5"), Some(1234.5));
+        assert_eq!(parse_locale_f64("1'234.5"), Some(1234.5));
+        assert_eq!(parse_locale_f64("1.234.567"), Some(1234567.0));
+        assert_eq!(parse_locale_f64("1,234,567"), Some(1234567.0));
+    }
+
+    #[test]
+    fn parse_locale_f64_rejects_empty_and_garbage() {
+        assert_eq!(parse_locale_f64(""), None);
+        assert_eq!(parse_locale_f64("   "), None);
+        assert_eq!(parse_locale_f64("abc"), None);
+        assert_eq!(parse_locale_f64("1,2.3,4"), None);
+        assert_eq!(parse_locale_f64("inf"), None);
+        assert_eq!(parse_locale_f64("NaN"), None);
+    }
+}
@@ -0,0 +1,277 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter};
+
+/// Wallpapers are downscaled to fit this many pixels square before extracting colors
+const SAMPLE_SIZE: u32 = 48;
+
+/// Most colors returned in a palette
+const PALETTE_SIZE: usize = 5;
+
+/// Palette colors closer than this (RGB distance) to an earlier one are skipped
+const MIN_COLOR_DISTANCE: f64 = 40.0;
+
+/// How often the watcher checks whether the wallpaper changed
+const WATCH_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Palette of the last wallpaper, keyed by its path and modification time
+/// (Windows keeps the same path when the wallpaper changes)
+static WALLPAPER_COLORS: Mutex<Option<(PathBuf, Option<SystemTime>, Vec<String>)>> =
+    Mutex::new(None);
+
+static WATCHER_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// Wallpaper of NSScreen.mainScreen. AppKit, so main thread only (macOS).
+#[cfg(target_os = "macos")]
+fn main_screen_wallpaper_path() -> Option<PathBuf> {
+    use objc2::rc::Retained;
+    use objc2::runtime::AnyObject;
+    use objc2::*;
+    use objc2_foundation::NSString;
+
+    unsafe {
+        let workspace: *mut AnyObject = msg_send![class!(NSWorkspace), sharedWorkspace];
+        let screen: *mut AnyObject = msg_send![class!(NSScreen), mainScreen];
+        if workspace.is_null() || screen.is_null() {
+            return None;
+        }
+
+        let url: *mut AnyObject = msg_send![workspace, desktopImageURLForScreen: screen];
+        if url.is_null() {
+            return None;
+        }
+        let path: Option<Retained<NSString>> = msg_send![url, path];
+        path.map(|path| PathBuf::from(path.to_string()))
+    }
+}
+
+/// Path of the current desktop wallpaper image on the main screen. Blocks on the main thread
+/// on macOS, so never call it from there.
+fn wallpaper_path(app_handle: &AppHandle) -> Option<PathBuf> {
+    #[cfg(target_os = "macos")]
+    {
+        let (tx, rx) = std::sync::mpsc::channel();
+        app_handle
+            .run_on_main_thread(move || {
+                let _ = tx.send(main_screen_wallpaper_path());
+            })
+            .ok()?;
+        rx.recv().ok().flatten()
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::UI::WindowsAndMessaging::{
+            SystemParametersInfoW, SPI_GETDESKWALLPAPER, SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS,
+        };
+        let _ = &app_handle;
+
+        // Same value as the WallPaper entry under HKCU\Control Panel\Desktop
+        let mut buffer = [0u16; 260];
+        unsafe {
+            SystemParametersInfoW(
+                SPI_GETDESKWALLPAPER,
+                buffer.len() as u32,
+                Some(buffer.as_mut_ptr() as *mut _),
+                SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+            )
+            .ok()?;
+        }
+
+        let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+        let path = String::from_utf16_lossy(&buffer[..len]);
+        if path.is_empty() {
+            None
+        } else {
+            Some(PathBuf::from(path))
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let _ = &app_handle;
+        let gsettings = |schema: &str, key: &str| -> Option<String> {
+            let output = std::process::Command::new("gsettings")
+                .args(["get", schema, key])
+                .output()
+                .ok()?;
+            if !output.status.success() {
+                return None;
+            }
+            Some(
+                String::from_utf8_lossy(&output.stdout)
+                    .trim()
+                    .trim_matches('\'')
+                    .to_string(),
+            )
+        };
+
+        // GNOME keeps a separate wallpaper for the dark style
+        let dark = gsettings("org.gnome.desktop.interface", "color-scheme")
+            .is_some_and(|scheme| scheme == "prefer-dark");
+        let uri = dark
+            .then(|| gsettings("org.gnome.desktop.background", "picture-uri-dark"))
+            .flatten()
+            .filter(|uri| !uri.is_empty())
+            .or_else(|| gsettings("org.gnome.desktop.background", "picture-uri"))?;
+
+        match reqwest::Url::parse(&uri) {
+            Ok(url) => url.to_file_path().ok(),
+            // Older setups store a plain path
+            Err(_) => Some(PathBuf::from(uri)),
+        }
+    }
+}
+
+/// The most common colors of `pixels` as hex strings, most common first. Like the
+/// frontend's getDominantColor, near black and near white pixels are skipped unless
+/// nothing else is left.
+fn extract_palette(pixels: &[[u8; 3]]) -> Vec<String> {
+    let vivid: Vec<[u8; 3]> = pixels
+        .iter()
+        .copied()
+        .filter(|[r, g, b]| {
+            let brightness = (*r as u32 + *g as u32 + *b as u32) / 3;
+            (20..=230).contains(&brightness)
+        })
+        .collect();
+    let pixels = if vivid.is_empty() { pixels } else { &vivid };
+
+    // Bucket by the top 3 bits of each channel, then average each bucket
+    let mut buckets: HashMap<(u8, u8, u8), (u32, [u32; 3])> = HashMap::new();
+    for &[r, g, b] in pixels {
+        let (count, sum) = buckets.entry((r >> 5, g >> 5, b >> 5)).or_default();
+        *count += 1;
+        sum[0] += r as u32;
+        sum[1] += g as u32;
+        sum[2] += b as u32;
+    }
+
+    let mut buckets: Vec<(u32, [u32; 3])> = buckets.into_values().collect();
+    buckets.sort_by(|a, b| b.0.cmp(&a.0));
+
+    let mut palette: Vec<[f64; 3]> = Vec::new();
+    for (count, sum) in buckets {
+        let color = sum.map(|channel| channel as f64 / count as f64);
+        let distinct = palette.iter().all(|picked| {
+            let distance: f64 = (0..3).map(|i| (picked[i] - color[i]).powi(2)).sum();
+            distance.sqrt() >= MIN_COLOR_DISTANCE
+        });
+        if distinct {
+            palette.push(color);
+            if palette.len() == PALETTE_SIZE {
+                break;
+            }
+        }
+    }
+
+    palette
+        .iter()
+        .map(|[r, g, b]| {
+            format!(
+                "#{:02x}{:02x}{:02x}",
+                r.round() as u8,
+                g.round() as u8,
+                b.round() as u8
+            )
+        })
+        .collect()
+}
+
+/// Downscale the wallpaper at `path` and extract its palette. The format is sniffed from the
+/// contents (Windows' TranscodedWallpaper has no extension); formats the image crate can't
+/// decode, like HEIC dynamic wallpapers, are an error.
+fn wallpaper_palette(path: &Path) -> Result<Vec<String>, String> {
+    let image = image::ImageReader::open(path)
+        .and_then(|reader| reader.with_guessed_format())
+        .map_err(|e| format!("Could not read wallpaper image {}: {}", path.display(), e))?
+        .decode()
+        .map_err(|e| format!("Could not decode wallpaper image {}: {}", path.display(), e))?;
+
+    let pixels: Vec<[u8; 3]> = image
+        .thumbnail(SAMPLE_SIZE, SAMPLE_SIZE)
+        .to_rgb8()
+        .pixels()
+        .map(|pixel| pixel.0)
+        .collect();
+
+    Ok(extract_palette(&pixels))
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+}
+
+/// Palette of the current wallpaper, re-extracted only when the wallpaper changed.
+/// Returns the colors and whether they came from a different wallpaper than last time.
+fn current_wallpaper_colors(app_handle: &AppHandle) -> Result<(Vec<String>, bool), String> {
+    let path = wallpaper_path(app_handle).ok_or("Could not determine the desktop wallpaper")?;
+    let modified = modified_time(&path);
+
+    if let Ok(cache) = WALLPAPER_COLORS.lock() {
+        if let Some((cached_path, cached_modified, colors)) = cache.as_ref() {
+            if *cached_path == path && *cached_modified == modified {
+                return Ok((colors.clone(), false));
+            }
+        }
+    }
+
+    let colors = wallpaper_palette(&path)?;
+    if let Ok(mut cache) = WALLPAPER_COLORS.lock() {
+        *cache = Some((path, modified, colors.clone()));
+    }
+    Ok((colors, true))
+}
+
+/// Drop the cached wallpaper palette, returns how many entries were removed
+pub fn clear_wallpaper_colors() -> usize {
+    WALLPAPER_COLORS
+        .lock()
+        .map(|mut cache| usize::from(cache.take().is_some()))
+        .unwrap_or(0)
+}
+
+/// Palette of the desktop wallpaper as hex colors (most common first), so the notch can
+/// theme itself to the desktop when nothing is playing. Cached per wallpaper.
+#[tauri::command]
+pub async fn get_wallpaper_colors(app_handle: AppHandle) -> Result<Vec<String>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        current_wallpaper_colors(&app_handle).map(|(colors, _)| colors)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Poll for wallpaper changes and emit `wallpaper-colors-changed` with the new palette
+/// (call on app setup)
+pub fn setup_wallpaper_watcher(app_handle: AppHandle) {
+    if WATCHER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    std::thread::spawn(move || {
+        // The first run only fills the cache
+        let mut primed = false;
+
+        loop {
+            if !crate::power::is_monitoring_paused() {
+                match current_wallpaper_colors(&app_handle) {
+                    Ok((colors, true)) if primed => {
+                        log::info!("Wallpaper changed, new palette: {}", colors.join(", "));
+                        let _ = app_handle.emit("wallpaper-colors-changed", colors);
+                    }
+                    Ok(_) => {}
+                    Err(e) => log::debug!("Wallpaper colors unavailable: {}", e),
+                }
+                primed = true;
+            }
+
+            std::thread::sleep(WATCH_INTERVAL);
+        }
+    });
+}
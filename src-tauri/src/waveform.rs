@@ -0,0 +1,224 @@
+use log;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+/// Number of mono frames averaged into one energy window while decoding
+const WINDOW_FRAMES: usize = 1024;
+
+/// Cache key: (path, mtime in seconds, buckets)
+type WaveformKey = (PathBuf, u64, usize);
+
+/// Cached waveforms keyed by path, modification time and bucket count
+static WAVEFORM_CACHE: OnceLock<Mutex<HashMap<WaveformKey, Vec<f32>>>> = OnceLock::new();
+
+/// Drop all cached waveforms, returns the number of entries dropped
+pub fn clear_waveform_cache() -> usize {
+    WAVEFORM_CACHE
+        .get()
+        .and_then(|cache| cache.lock().ok())
+        .map(|mut guard| {
+            let count = guard.len();
+            guard.clear();
+            count
+        })
+        .unwrap_or(0)
+}
+
+/// Resolve a plain path or `file://` URI to a local path.
+/// Streaming sources (http, spotify, ...) are unsupported.
+fn resolve_local_path(path: &str) -> Result<PathBuf, String> {
+    if let Some(rest) = path.strip_prefix("file://") {
+        return Ok(PathBuf::from(percent_decode(rest)));
+    }
+
+    if path.contains("://") {
+        return Err("Unsupported: waveforms are only available for local files".to_string());
+    }
+
+    Ok(PathBuf::from(path))
+}
+
+/// Decode %XX escapes in a file URI path
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or("");
+            if let Ok(byte) = u8::from_str_radix(hex, 16) {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Decode the file and return the mean square energy of each window of mono frames
+fn decode_energy_windows(path: &Path) -> Result<Vec<f32>, String> {
+    let file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(ext);
+    }
+
+    let probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            mss,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|e| format!("Unsupported audio format: {}", e))?;
+    let mut format = probed.format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or("No audio track found")?;
+    let track_id = track.id;
+
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|e| format!("Unsupported codec: {}", e))?;
+
+    let mut windows = Vec::new();
+    let mut sum = 0.0f32;
+    let mut count = 0usize;
+    let mut sample_buf: Option<SampleBuffer<f32>> = None;
+
+    loop {
+        let packet = match format.next_packet() {
+            Ok(p) => p,
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                break
+            }
+            Err(SymphoniaError::ResetRequired) => break,
+            Err(e) => return Err(e.to_string()),
+        };
+
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        let decoded = match decoder.decode(&packet) {
+            Ok(d) => d,
+            // Skip corrupt packets rather than failing the whole waveform
+            Err(SymphoniaError::DecodeError(e)) => {
+                log::debug!("Skipping undecodable packet: {}", e);
+                continue;
+            }
+            Err(e) => return Err(e.to_string()),
+        };
+
+        let spec = *decoded.spec();
+        let channels = spec.channels.count().max(1);
+
+        // Packet capacity is fixed per track, so the buffer is allocated once
+        let buf = sample_buf
+            .get_or_insert_with(|| SampleBuffer::<f32>::new(decoded.capacity() as u64, spec));
+        buf.copy_interleaved_ref(decoded);
+
+        for frame in buf.samples().chunks(channels) {
+            let mono = frame.iter().sum::<f32>() / channels as f32;
+            sum += mono * mono;
+            count += 1;
+
+            if count == WINDOW_FRAMES {
+                windows.push(sum / count as f32);
+                sum = 0.0;
+                count = 0;
+            }
+        }
+    }
+
+    if count > 0 {
+        windows.push(sum / count as f32);
+    }
+
+    Ok(windows)
+}
+
+/// Group energy windows into `buckets` RMS peaks normalized to 0.0-1.0
+fn bucket_rms(windows: &[f32], buckets: usize) -> Vec<f32> {
+    if windows.is_empty() || buckets == 0 {
+        return vec![0.0; buckets];
+    }
+
+    let mut peaks: Vec<f32> = (0..buckets)
+        .map(|i| {
+            let start = i * windows.len() / buckets;
+            let end = ((i + 1) * windows.len() / buckets).max(start + 1);
+            let slice = &windows[start.min(windows.len() - 1)..end.min(windows.len())];
+            (slice.iter().sum::<f32>() / slice.len() as f32).sqrt()
+        })
+        .collect();
+
+    let max = peaks.iter().cloned().fold(0.0f32, f32::max);
+    if max > 0.0 {
+        for peak in peaks.iter_mut() {
+            *peak /= max;
+        }
+    }
+
+    peaks
+}
+
+/// Get normalized RMS peaks for a local audio file, for drawing a waveform scrubber.
+/// Results are cached by path + modification time.
+#[tauri::command]
+pub async fn get_track_waveform(path: String, buckets: usize) -> Result<Vec<f32>, String> {
+    let path = resolve_local_path(&path)?;
+    let buckets = buckets.clamp(1, 4096);
+
+    let mtime = fs::metadata(&path)
+        .and_then(|m| m.modified())
+        .map_err(|e| e.to_string())?
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    let key = (path.clone(), mtime, buckets);
+    let cache = WAVEFORM_CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    if let Ok(guard) = cache.lock() {
+        if let Some(peaks) = guard.get(&key) {
+            return Ok(peaks.clone());
+        }
+    }
+
+    log::debug!("Computing waveform for {:?} ({} buckets)", path, buckets);
+
+    // Decoding a full track is CPU heavy, keep it off the async runtime
+    let decode_path = path.clone();
+    let windows = tauri::async_runtime::spawn_blocking(move || decode_energy_windows(&decode_path))
+        .await
+        .map_err(|e| e.to_string())??;
+
+    let peaks = bucket_rms(&windows, buckets);
+
+    if let Ok(mut guard) = cache.lock() {
+        // Drop entries for older versions of this file
+        guard.retain(|(p, m, _), _| p != &path || *m == mtime);
+        guard.insert(key, peaks.clone());
+    }
+
+    Ok(peaks)
+}
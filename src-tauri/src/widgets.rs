@@ -1,12 +1,30 @@
 use crate::database::{get_connection, log_sql};
+use rusqlite::OptionalExtension;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use tauri::{command, AppHandle};
+use tauri::{command, AppHandle, Emitter};
 
 /// Widget enabled state - maps widget IDs to their enabled status
 #[derive(Serialize, Deserialize, Default, Clone)]
 pub struct WidgetState {
     pub enabled: HashMap<String, bool>,
+    /// Per-widget structured settings (only widgets that have saved a config)
+    #[serde(default)]
+    pub config: HashMap<String, serde_json::Value>,
+}
+
+/// Maximum number of speed test results kept in history
+const SPEED_TEST_HISTORY_LIMIT: u32 = 500;
+
+/// A stored speed test result
+#[derive(Serialize, Clone)]
+pub struct SpeedTestResult {
+    pub download_mbps: f64,
+    /// Not measured yet, kept so the history schema doesn't need migrating later
+    pub upload_mbps: Option<f64>,
+    pub ping_ms: Option<f64>,
+    /// Unix timestamp (seconds)
+    pub tested_at: i64,
 }
 
 /// Save widget enabled state to disk (SQLite)
@@ -19,7 +37,9 @@ pub fn save_widget_state(app_handle: AppHandle, state: WidgetState) -> Result<()
         .map_err(|e| e.to_string())?;
 
     for (id, enabled) in state.enabled {
-        let sql = "INSERT OR REPLACE INTO widget_state (id, enabled) VALUES (?1, ?2)";
+        // Upsert so the widget's saved config is preserved
+        let sql = "INSERT INTO widget_state (id, enabled) VALUES (?1, ?2) \
+                   ON CONFLICT(id) DO UPDATE SET enabled = excluded.enabled";
         log_sql(&format!("{} [{}, {}]", sql, id, enabled));
 
         conn.execute(sql, rusqlite::params![id, enabled])
@@ -35,58 +55,196 @@ pub fn save_widget_state(app_handle: AppHandle, state: WidgetState) -> Result<()
 pub fn load_widget_state(app_handle: AppHandle) -> Result<WidgetState, String> {
     let conn = get_connection(&app_handle).map_err(|e| e.to_string())?;
 
-    let sql = "SELECT id, enabled FROM widget_state";
+    let sql = "SELECT id, enabled, config FROM widget_state";
     log_sql(sql);
 
     let mut stmt = conn.prepare(sql).map_err(|e| e.to_string())?;
 
     let rows = stmt
         .query_map([], |row| {
-            Ok((row.get::<_, String>(0)?, row.get::<_, bool>(1)?))
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, bool>(1)?,
+                row.get::<_, Option<String>>(2)?,
+            ))
         })
         .map_err(|e| e.to_string())?;
 
     let mut enabled = HashMap::new();
+    let mut config = HashMap::new();
     for row in rows {
-        let (id, is_enabled) = row.map_err(|e| e.to_string())?;
+        let (id, is_enabled, raw_config) = row.map_err(|e| e.to_string())?;
+        if let Some(value) = raw_config.and_then(|raw| parse_widget_config(&id, &raw)) {
+            config.insert(id.clone(), value);
+        }
         enabled.insert(id, is_enabled);
     }
 
-    Ok(WidgetState { enabled })
+    Ok(WidgetState { enabled, config })
+}
+
+/// Parse a stored config blob, ignoring anything that isn't a JSON object
+fn parse_widget_config(id: &str, raw: &str) -> Option<serde_json::Value> {
+    match serde_json::from_str::<serde_json::Value>(raw) {
+        Ok(value) if value.is_object() => Some(value),
+        Ok(_) => None,
+        Err(e) => {
+            log::warn!("Ignoring invalid config for widget {}: {}", id, e);
+            None
+        }
+    }
+}
+
+/// Get a widget's structured config, if one has been saved
+#[command]
+pub fn get_widget_config(
+    app_handle: AppHandle,
+    id: String,
+) -> Result<Option<serde_json::Value>, String> {
+    let conn = get_connection(&app_handle).map_err(|e| e.to_string())?;
+
+    let sql = "SELECT config FROM widget_state WHERE id = ?1";
+    log_sql(sql);
+
+    let raw = conn
+        .query_row(sql, rusqlite::params![id], |row| {
+            row.get::<_, Option<String>>(0)
+        })
+        .optional()
+        .map_err(|e| e.to_string())?
+        .flatten();
+
+    Ok(raw.and_then(|raw| parse_widget_config(&id, &raw)))
+}
+
+/// Save a widget's structured config (must be a JSON object)
+#[command]
+pub fn set_widget_config(
+    app_handle: AppHandle,
+    id: String,
+    config: serde_json::Value,
+) -> Result<(), String> {
+    if !config.is_object() {
+        return Err("Widget config must be a JSON object".to_string());
+    }
+
+    let conn = get_connection(&app_handle).map_err(|e| e.to_string())?;
+
+    // Keep the enabled flag if the widget already has a row
+    let sql = "INSERT INTO widget_state (id, enabled, config) VALUES (?1, 0, ?2) \
+               ON CONFLICT(id) DO UPDATE SET config = excluded.config";
+    log_sql(&format!("{} [{}]", sql, id));
+
+    conn.execute(sql, rusqlite::params![id, config.to_string()])
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Optional speed test parameters, any missing field uses the default
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct SpeedTestConfig {
+    /// Maximum test duration in seconds
+    pub duration_secs: u64,
+    /// Bytes requested from the Cloudflare endpoint (ignored for custom servers)
+    pub test_bytes: u64,
+    /// Custom http(s) URL to download from instead of the built-in servers
+    pub server_url: Option<String>,
+}
+
+impl Default for SpeedTestConfig {
+    fn default() -> Self {
+        Self {
+            duration_secs: 8,
+            test_bytes: 25_000_000,
+            server_url: None,
+        }
+    }
+}
+
+impl SpeedTestConfig {
+    fn validate(&self) -> Result<(), String> {
+        if !(2..=60).contains(&self.duration_secs) {
+            return Err("Speed test duration must be between 2 and 60 seconds".to_string());
+        }
+
+        if !(1_000_000..=2_000_000_000).contains(&self.test_bytes) {
+            return Err("Speed test size must be between 1 MB and 2 GB".to_string());
+        }
+
+        if let Some(url) = &self.server_url {
+            let parsed =
+                reqwest::Url::parse(url).map_err(|e| format!("Invalid server URL: {}", e))?;
+            if !matches!(parsed.scheme(), "http" | "https") {
+                return Err("Server URL must use http or https".to_string());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn test_urls(&self) -> Vec<String> {
+        match &self.server_url {
+            Some(url) => vec![url.clone()],
+            // Publicly available test files, Cloudflare first
+            None => vec![
+                format!(
+                    "https://speed.cloudflare.com/__down?bytes={}",
+                    self.test_bytes
+                ),
+                "https://proof.ovh.net/files/100Mb.dat".to_string(), // Alternative
+            ],
+        }
+    }
+
+    /// URL used for latency checks (HEAD requests only)
+    fn ping_url(&self) -> &str {
+        self.server_url
+            .as_deref()
+            .unwrap_or("https://speed.cloudflare.com/__down?bytes=0")
+    }
 }
 
 #[command]
-pub async fn run_speed_test(app_handle: AppHandle) -> Result<f64, String> {
+pub async fn run_speed_test(
+    app_handle: AppHandle,
+    config: Option<SpeedTestConfig>,
+) -> Result<f64, String> {
     use futures_util::StreamExt;
     use std::time::Instant;
     use tauri::Emitter;
 
+    if crate::power::is_battery_saver_active() {
+        return Err("Speed test is paused by battery saver".to_string());
+    }
+    crate::connectivity::ensure_online().await?;
+
+    let config = config.unwrap_or_default();
+    config.validate()?;
+
     let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(10))
+        // Leave room for connecting on top of the download itself
+        .timeout(std::time::Duration::from_secs(config.duration_secs + 5))
         .user_agent("Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36")
         .build()
         .map_err(|e| e.to_string())?;
 
-    // Use Cloudflare's speed test infrastructure
-    // These are publicly available test files from Cloudflare
-    let test_urls = vec![
-        "https://speed.cloudflare.com/__down?bytes=25000000", // 25MB
-        "https://proof.ovh.net/files/100Mb.dat",              // Alternative
-    ];
+    let ping_ms = measure_ping(&client, config.ping_url()).await;
 
-    log::debug!("Starting speed test...");
+    log::debug!("Starting speed test with {:?}", config);
 
-    for test_url in test_urls {
+    for test_url in config.test_urls() {
         log::debug!("Testing with: {}", test_url);
 
-        match client.get(test_url).send().await {
+        match client.get(&test_url).send().await {
             Ok(response) => {
                 let mut stream = response.bytes_stream();
                 let start = Instant::now();
                 let mut total_bytes = 0u64;
                 let mut sample_count = 0u32;
                 let mut last_sample_time = start;
-                let max_duration = 8.0; // Maximum test duration in seconds
+                let max_duration = config.duration_secs as f64;
 
                 while let Some(chunk_result) = stream.next().await {
                     match chunk_result {
@@ -101,7 +259,7 @@ pub async fn run_speed_test(app_handle: AppHandle) -> Result<f64, String> {
                                 let mbps = bps / 1_000_000.0;
                                 last_sample_time = Instant::now();
 
-                                // Calculate progress: 0% at 0s, 100% at 8s
+                                // Calculate progress: 0% at 0s, 100% at max_duration
                                 let progress = ((elapsed / max_duration) * 100.0).min(100.0);
 
                                 // Emit both speed and progress to UI
@@ -123,9 +281,9 @@ pub async fn run_speed_test(app_handle: AppHandle) -> Result<f64, String> {
                                 );
                             }
 
-                            // Stop after exactly 8 seconds
-                            if start.elapsed().as_secs() >= 8 {
-                                log::debug!("Stopping after 8 seconds");
+                            // Stop after exactly max_duration seconds
+                            if start.elapsed().as_secs() >= config.duration_secs {
+                                log::debug!("Stopping after {} seconds", config.duration_secs);
                                 break;
                             }
                         }
@@ -166,6 +324,13 @@ pub async fn run_speed_test(app_handle: AppHandle) -> Result<f64, String> {
                     }),
                 );
 
+                crate::toast::show(
+                    &app_handle,
+                    &format!("Speed test: {:.0} Mbps", mbps),
+                    crate::toast::DEFAULT_DURATION_MS,
+                    crate::toast::ToastLevel::Success,
+                );
+
                 log::debug!(
                     "Speed test complete: {:.2} Mbps ({} bytes in {:.2}s)",
                     mbps,
@@ -174,7 +339,22 @@ pub async fn run_speed_test(app_handle: AppHandle) -> Result<f64, String> {
                 );
 
                 // Round to 2 decimal places
-                return Ok((mbps * 100.0).round() / 100.0);
+                let download_mbps = (mbps * 100.0).round() / 100.0;
+
+                let result = SpeedTestResult {
+                    download_mbps,
+                    upload_mbps: None,
+                    ping_ms,
+                    tested_at: std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .map(|d| d.as_secs() as i64)
+                        .unwrap_or(0),
+                };
+                if let Err(e) = record_speed_test(&app_handle, &result) {
+                    log::error!("Failed to save speed test result: {}", e);
+                }
+
+                return Ok(download_mbps);
             }
             Err(e) => {
                 log::debug!("Failed to connect to {}: {}", test_url, e);
@@ -185,3 +365,100 @@ pub async fn run_speed_test(app_handle: AppHandle) -> Result<f64, String> {
 
     Err("All speed test servers failed. Please check your internet connection.".to_string())
 }
+
+/// Round-trip latency of a tiny request, best of a few attempts (None if all fail).
+/// The first request also pays for DNS + TLS, so later ones reuse the connection.
+async fn measure_ping(client: &reqwest::Client, url: &str) -> Option<f64> {
+    let mut best: Option<f64> = None;
+
+    for _ in 0..3 {
+        let start = std::time::Instant::now();
+        match client.head(url).send().await {
+            Ok(_) => {
+                let ms = start.elapsed().as_secs_f64() * 1000.0;
+                best = Some(best.map_or(ms, |b| b.min(ms)));
+            }
+            Err(e) => log::debug!("Ping request failed: {}", e),
+        }
+    }
+
+    best.map(|ms| (ms * 10.0).round() / 10.0)
+}
+
+fn ensure_speed_test_history_table(conn: &rusqlite::Connection) -> Result<(), String> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS speed_test_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            download_mbps REAL NOT NULL,
+            upload_mbps REAL,
+            ping_ms REAL,
+            tested_at INTEGER NOT NULL
+        )",
+        [],
+    )
+    .map(|_| ())
+    .map_err(|e| e.to_string())
+}
+
+/// Store a result and drop the oldest rows beyond SPEED_TEST_HISTORY_LIMIT
+fn record_speed_test(app_handle: &AppHandle, result: &SpeedTestResult) -> Result<(), String> {
+    let conn = get_connection(app_handle).map_err(|e| e.to_string())?;
+    ensure_speed_test_history_table(&conn)?;
+
+    let sql = "INSERT INTO speed_test_history (download_mbps, upload_mbps, ping_ms, tested_at) \
+               VALUES (?1, ?2, ?3, ?4)";
+    log_sql(sql);
+    conn.execute(
+        sql,
+        rusqlite::params![
+            result.download_mbps,
+            result.upload_mbps,
+            result.ping_ms,
+            result.tested_at
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    let sql = "DELETE FROM speed_test_history WHERE id NOT IN \
+               (SELECT id FROM speed_test_history ORDER BY id DESC LIMIT ?1)";
+    log_sql(sql);
+    conn.execute(sql, rusqlite::params![SPEED_TEST_HISTORY_LIMIT])
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Get the most recent speed test results, oldest first (for a trend sparkline)
+#[command]
+pub fn get_speed_test_history(
+    app_handle: AppHandle,
+    limit: Option<u32>,
+) -> Result<Vec<SpeedTestResult>, String> {
+    let limit = limit.unwrap_or(30).min(SPEED_TEST_HISTORY_LIMIT);
+
+    let conn = get_connection(&app_handle).map_err(|e| e.to_string())?;
+    ensure_speed_test_history_table(&conn)?;
+
+    let sql = "SELECT download_mbps, upload_mbps, ping_ms, tested_at FROM speed_test_history \
+               ORDER BY id DESC LIMIT ?1";
+    log_sql(sql);
+
+    let mut stmt = conn.prepare(sql).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(rusqlite::params![limit], |row| {
+            Ok(SpeedTestResult {
+                download_mbps: row.get(0)?,
+                upload_mbps: row.get(1)?,
+                ping_ms: row.get(2)?,
+                tested_at: row.get(3)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut history = rows
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    history.reverse();
+
+    Ok(history)
+}
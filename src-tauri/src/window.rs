@@ -1,8 +1,9 @@
 use crate::database::{get_connection, log_sql};
-use crate::models::NotchInfo;
+use crate::models::{AppInfo, NotchInfo, RawMetrics};
 use log;
 use serde::{Deserialize, Serialize};
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::RwLock;
 use tauri::{
     AppHandle, Emitter, LogicalPosition, LogicalSize, Manager, WebviewUrl, WebviewWindow,
@@ -108,6 +109,38 @@ static UI_BOUNDS: std::sync::OnceLock<RwLock<Option<UiBounds>>> = std::sync::Onc
 /// Global storage for window settings
 static WINDOW_SETTINGS: std::sync::OnceLock<RwLock<WindowSettings>> = std::sync::OnceLock::new();
 
+/// Cached result of get_screen_info, cleared when the display configuration changes
+static SCREEN_INFO_CACHE: std::sync::OnceLock<RwLock<Option<(f64, f64, f64, f64)>>> =
+    std::sync::OnceLock::new();
+
+/// User-defined notch geometry overrides keyed by display name or "WIDTHxHEIGHT"
+static NOTCH_GEOMETRY_OVERRIDES: std::sync::OnceLock<RwLock<HashMap<String, NotchGeometry>>> =
+    std::sync::OnceLock::new();
+
+/// Explicit dock icon preference (None = follow window activation)
+static DOCK_ICON_PREFERENCE: std::sync::OnceLock<RwLock<Option<bool>>> = std::sync::OnceLock::new();
+
+/// Whether hover latency profiling is enabled
+static HOVER_PROFILING: AtomicBool = AtomicBool::new(false);
+
+/// Recent hover latency samples in milliseconds (most recent last)
+static HOVER_LATENCY_SAMPLES: std::sync::OnceLock<
+    std::sync::Mutex<std::collections::VecDeque<f64>>,
+> = std::sync::OnceLock::new();
+
+/// Whether the cursor is inside the notch UI area (updated by mouse monitoring)
+static IS_INSIDE: AtomicBool = AtomicBool::new(false);
+
+/// Per-app expand rules keyed by bundle id (true = auto-expand, false = auto-collapse)
+static APP_EXPAND_RULES: std::sync::OnceLock<RwLock<HashMap<String, bool>>> =
+    std::sync::OnceLock::new();
+
+/// Set while the notch is held open by an app expand rule
+static AUTO_EXPANDED: AtomicBool = AtomicBool::new(false);
+
+/// Action run when the collapsed notch is clicked (loaded lazily from settings)
+static CLICK_ACTION: std::sync::OnceLock<RwLock<ClickAction>> = std::sync::OnceLock::new();
+
 /// Window size settings (adjustable by the user)
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct WindowSettings {
@@ -118,6 +151,12 @@ pub struct WindowSettings {
     /// Whether "non notch mode" is active (hides wings, tighter collision)
     #[serde(default)]
     pub non_notch_mode: bool,
+    /// How the cursor reveals the notch ("hover" or "push")
+    #[serde(default)]
+    pub reveal_mode: RevealMode,
+    /// In push mode, how close to the top edge (in px) the cursor must get first
+    #[serde(default = "default_push_threshold")]
+    pub push_threshold_px: f64,
 }
 
 impl Default for WindowSettings {
@@ -126,7 +165,64 @@ impl Default for WindowSettings {
             extra_width: 400.0,
             extra_height: 800.0,
             non_notch_mode: false,
+            reveal_mode: RevealMode::default(),
+            push_threshold_px: default_push_threshold(),
+        }
+    }
+}
+
+fn default_push_threshold() -> f64 {
+    2.0
+}
+
+/// How the cursor reveals the notch
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RevealMode {
+    /// Expand as soon as the cursor enters the (padded) notch area
+    #[default]
+    Hover,
+    /// Only expand after the cursor is pushed against the top edge inside the notch area
+    Push,
+}
+
+/// Tracks the cursor across mouse monitoring iterations for push-to-reveal
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+#[derive(Default)]
+struct RevealGate {
+    /// Set once the cursor touched the top edge within the notch area
+    armed: bool,
+}
+
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+impl RevealGate {
+    /// Filter the raw "cursor in UI area" result according to the reveal mode
+    fn allows(
+        &mut self,
+        settings: &WindowSettings,
+        in_ui_area: bool,
+        was_inside: bool,
+        y: f64,
+    ) -> bool {
+        if settings.reveal_mode == RevealMode::Hover || was_inside {
+            return in_ui_area;
+        }
+
+        if !in_ui_area {
+            self.armed = false;
+            return false;
+        }
+
+        if y <= settings.push_threshold_px {
+            self.armed = true;
         }
+        self.armed
+    }
+
+    /// The cursor left the interaction zone without being checked
+    #[cfg(target_os = "macos")]
+    fn reset(&mut self) {
+        self.armed = false;
     }
 }
 
@@ -183,251 +279,2151 @@ fn get_ui_bounds_store() -> &'static RwLock<Option<UiBounds>> {
     UI_BOUNDS.get_or_init(|| RwLock::new(None))
 }
 
-/// Update the actual UI element bounds (called from frontend when element resizes)
-#[tauri::command]
-pub fn update_ui_bounds(x: f64, y: f64, width: f64, height: f64) -> Result<(), String> {
-    let store = get_ui_bounds_store();
-    let mut bounds = store.write().map_err(|e| e.to_string())?;
-    *bounds = Some(UiBounds {
-        x,
-        y,
-        width,
-        height,
-    });
-    Ok(())
+/// Explicit dock icon preference, None if the user never set one
+pub fn get_dock_icon_preference() -> Option<bool> {
+    DOCK_ICON_PREFERENCE
+        .get_or_init(|| RwLock::new(None))
+        .read()
+        .map(|guard| *guard)
+        .unwrap_or(None)
 }
 
-/// Get screen dimensions
-/// Returns (screen_width, screen_height, notch_height, notch_width)
-fn get_screen_info(app_handle: Option<&tauri::AppHandle>) -> (f64, f64, f64, f64) {
+/// Set NSApplication's activation policy (0 = Regular with dock icon, 1 = Accessory)
+#[cfg(target_os = "macos")]
+fn set_activation_policy(policy: i64) {
+    use objc2::runtime::AnyObject;
+    use objc2::*;
+
+    unsafe {
+        let ns_app: *mut AnyObject = msg_send![class!(NSApplication), sharedApplication];
+        let _: () = msg_send![ns_app, setActivationPolicy: policy];
+    }
+}
+
+/// Load the persisted dock icon preference and apply it (call on app setup)
+pub fn initialize_dock_icon_preference(app_handle: &AppHandle) {
+    let preference = get_connection(app_handle).ok().and_then(|conn| {
+        let sql = "SELECT value FROM settings WHERE key = 'dock_icon_visible'";
+        log_sql(sql);
+        conn.query_row(sql, [], |row| row.get::<_, String>(0))
+            .ok()
+            .map(|value| value == "true")
+    });
+
+    if let Ok(mut guard) = DOCK_ICON_PREFERENCE
+        .get_or_init(|| RwLock::new(None))
+        .write()
+    {
+        *guard = preference;
+    }
+
     #[cfg(target_os = "macos")]
+    if let Some(visible) = preference {
+        set_activation_policy(if visible { 0 } else { 1 });
+    }
+}
+
+/// Explicitly show or hide the dock icon and persist the preference.
+/// Once set, activate_window/deactivate_window no longer change the activation policy.
+#[tauri::command]
+pub fn set_dock_icon_visible(app_handle: AppHandle, visible: bool) -> Result<(), String> {
     {
-        // Define our own CGSize/CGRect to avoid deprecated cocoa crate fields
+        let store = DOCK_ICON_PREFERENCE.get_or_init(|| RwLock::new(None));
+        let mut guard = store.write().map_err(|e| e.to_string())?;
+        *guard = Some(visible);
+    }
 
-        use objc2::runtime::AnyObject;
-        use objc2::*;
+    let conn = get_connection(&app_handle).map_err(|e| e.to_string())?;
+    let sql = "INSERT OR REPLACE INTO settings (key, value) VALUES ('dock_icon_visible', ?1)";
+    log_sql(sql);
+    conn.execute(sql, rusqlite::params![visible.to_string()])
+        .map_err(|e| e.to_string())?;
 
-        unsafe {
-            let main_screen: *mut AnyObject = msg_send![class!(NSScreen), mainScreen];
+    #[cfg(target_os = "macos")]
+    {
+        set_activation_policy(if visible { 0 } else { 1 });
+        ensure_main_window_visible_in_background(app_handle.clone());
+    }
 
-            if main_screen.is_null() {
-                return (0.0, 0.0, 0.0, 0.0);
-            }
+    #[cfg(not(target_os = "macos"))]
+    if let Some(window) = app_handle.get_webview_window("main") {
+        window
+            .set_skip_taskbar(!visible)
+            .map_err(|e| e.to_string())?;
+    }
 
-            // Get screen frame
-            let frame: CGRect = msg_send![main_screen, frame];
-            let screen_width = frame.size.width;
-            let screen_height = frame.size.height;
+    Ok(())
+}
 
-            // Get safeAreaInsets (macOS 12.0+)
+/// Largest accepted native corner radius, in points
+const MAX_CORNER_RADIUS: f64 = 100.0;
 
-            let insets: NSEdgeInsets = msg_send![main_screen, safeAreaInsets];
-            let safe_area_top = insets.top;
+/// Native corner radius of the main window (f64 bits), 0 for square corners
+static WINDOW_CORNER_RADIUS: AtomicU64 = AtomicU64::new(0);
 
-            let notch_height = if safe_area_top >= 0.0 {
-                (screen_height * 0.1).max(38.0).min(52.0)
-            } else {
-                0.0
-            };
+/// Round the main window's content layer so the native window never shows square corners
+/// behind the rounded UI, e.g. mid-way through the expand animation (macOS)
+#[cfg(target_os = "macos")]
+fn apply_corner_radius(window: &WebviewWindow, radius: f64) -> Result<(), String> {
+    use objc2::runtime::AnyObject;
+    use objc2::*;
+    use raw_window_handle::HasWindowHandle;
 
-            let notch_width = if safe_area_top > 0.0 {
-                (screen_width * 0.1).max(200.0).min(260.0)
-            } else {
-                180.0
-            };
+    let handle = window.window_handle().map_err(|e| e.to_string())?;
+    let appkit_handle = match handle.as_raw() {
+        raw_window_handle::RawWindowHandle::AppKit(appkit_handle) => appkit_handle,
+        _ => return Err("Not an AppKit window".to_string()),
+    };
 
-            (screen_width, screen_height, notch_height, notch_width)
+    unsafe {
+        let ns_view = appkit_handle.ns_view.as_ptr() as *mut AnyObject;
+        let ns_win: *mut AnyObject = msg_send![ns_view, window];
+        if ns_win.is_null() {
+            return Err("Window has no NSWindow".to_string());
+        }
+        let content_view: *mut AnyObject = msg_send![ns_win, contentView];
+        if content_view.is_null() {
+            return Err("Window has no content view".to_string());
         }
-    }
-
-    #[cfg(target_os = "windows")]
-    {
-        use windows::Win32::UI::WindowsAndMessaging::{GetSystemMetrics, SM_CXSCREEN, SM_CYSCREEN};
 
-        unsafe {
-            let width = GetSystemMetrics(SM_CXSCREEN) as f64;
-            let height = GetSystemMetrics(SM_CYSCREEN) as f64;
-            (width, height, 0.0, 0.0)
+        let _: () = msg_send![content_view, setWantsLayer: true];
+        let layer: *mut AnyObject = msg_send![content_view, layer];
+        if layer.is_null() {
+            return Err("Content view has no layer".to_string());
         }
+        let _: () = msg_send![layer, setCornerRadius: radius];
+        let _: () = msg_send![layer, setMasksToBounds: radius > 0.0];
     }
 
-    #[cfg(target_os = "linux")]
-    {
-        // Try to get from app handle if available
-        if let Some(handle) = app_handle {
-            if let Ok(Some(monitor)) = handle.primary_monitor() {
-                let size = monitor.size();
-                let scale_factor = monitor.scale_factor();
-                let width = size.width as f64 / scale_factor;
-                let height = size.height as f64 / scale_factor;
-                return (width, height, 0.0, 0.0);
+    Ok(())
+}
+
+/// Load the persisted corner radius and apply it to the main window (call on app setup)
+pub fn initialize_window_corner_radius(app_handle: &AppHandle) {
+    let radius = get_connection(app_handle)
+        .ok()
+        .and_then(|conn| {
+            let sql = "SELECT value FROM settings WHERE key = 'window_corner_radius'";
+            log_sql(sql);
+            conn.query_row(sql, [], |row| row.get::<_, String>(0)).ok()
+        })
+        .and_then(|value| value.parse::<f64>().ok())
+        .filter(|radius| (0.0..=MAX_CORNER_RADIUS).contains(radius));
+
+    if let Some(radius) = radius {
+        WINDOW_CORNER_RADIUS.store(radius.to_bits(), Ordering::Relaxed);
+
+        #[cfg(target_os = "macos")]
+        if let Some(window) = app_handle.get_webview_window("main") {
+            if let Err(e) = apply_corner_radius(&window, radius) {
+                log::warn!("Failed to apply window corner radius: {}", e);
             }
         }
-        (1920.0, 1080.0, 0.0, 0.0)
     }
 }
 
+/// Set and persist the native corner radius of the main window, in points (0 for square).
+/// Only applied on macOS; elsewhere the value is stored but the window stays square.
 #[tauri::command]
-pub fn get_system_accent_color() -> String {
-    #[cfg(target_os = "macos")]
-    return crate::utils::get_macos_accent_color();
+pub fn set_window_corner_radius(app_handle: AppHandle, radius: f64) -> Result<(), String> {
+    if !(0.0..=MAX_CORNER_RADIUS).contains(&radius) {
+        return Err(format!(
+            "Corner radius must be between 0 and {}",
+            MAX_CORNER_RADIUS
+        ));
+    }
 
-    #[cfg(target_os = "windows")]
-    {
-        // Fallback to blue for now, retrieving registry value is a bit more involved
-        // and dwmapi GetWindowAttribute uses BGR which needs conversion
-        "#007AFF".to_string()
+    #[cfg(target_os = "macos")]
+    if let Some(window) = app_handle.get_webview_window("main") {
+        apply_corner_radius(&window, radius)?;
     }
 
-    #[cfg(target_os = "linux")]
-    return "#007AFF".to_string();
+    WINDOW_CORNER_RADIUS.store(radius.to_bits(), Ordering::Relaxed);
+
+    let conn = get_connection(&app_handle).map_err(|e| e.to_string())?;
+    let sql = "INSERT OR REPLACE INTO settings (key, value) VALUES ('window_corner_radius', ?1)";
+    log_sql(sql);
+    conn.execute(sql, rusqlite::params![radius.to_string()])
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
 }
 
-/// Get notch information from the main screen using NSScreen.safeAreaInsets (macOS 12.0+)
 #[tauri::command]
-pub fn get_notch_info(app_handle: tauri::AppHandle) -> Option<NotchInfo> {
-    let (screen_width, screen_height, notch_height, notch_width) =
-        get_screen_info(Some(&app_handle));
-    let has_notch = notch_height > 0.0;
-    let visible_height = screen_height - notch_height;
+pub fn get_window_corner_radius() -> f64 {
+    f64::from_bits(WINDOW_CORNER_RADIUS.load(Ordering::Relaxed))
+}
 
-    Some(NotchInfo {
-        has_notch,
-        notch_height,
-        notch_width,
-        screen_width,
-        screen_height,
-        visible_height,
-    })
+/// Whether the notch window shows on every Space (persisted as `space_behavior`)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpaceBehavior {
+    AllSpaces,
+    /// Stays on the Space it was opened on
+    CurrentSpace,
 }
 
-/// Position the window at the notch location (centered at top of screen)
-#[tauri::command]
-pub fn position_at_notch(window: Window) -> Result<(), String> {
-    let (screen_width, _screen_height, _notch_height, notch_width) =
-        get_screen_info(Some(window.app_handle()));
+impl SpaceBehavior {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "all_spaces" => Some(Self::AllSpaces),
+            "current_space" => Some(Self::CurrentSpace),
+            _ => None,
+        }
+    }
 
-    // Use notch width if available, otherwise fall back to current window width
-    let target_width = if notch_width > 0.0 {
-        notch_width
-    } else {
-        let window_size = window.outer_size().map_err(|e| e.to_string())?;
-        let scale_factor = window.scale_factor().map_err(|e| e.to_string())?;
-        window_size.width as f64 / scale_factor
-    };
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::AllSpaces => "all_spaces",
+            Self::CurrentSpace => "current_space",
+        }
+    }
+}
 
-    // Center horizontally, position at very top (y=0)
-    let x = (screen_width - target_width) / 2.0;
-    let y = 0.0;
+static SPACE_BEHAVIOR: RwLock<SpaceBehavior> = RwLock::new(SpaceBehavior::AllSpaces);
 
-    window
-        .set_position(LogicalPosition::new(x, y))
-        .map_err(|e| e.to_string())?;
+/// NSWindowCollectionBehavior for the main window under the current space behavior
+#[cfg(target_os = "macos")]
+pub fn main_window_collection_behavior() -> u64 {
+    match get_space_behavior() {
+        // NSWindowCollectionBehaviorCanJoinAllSpaces (1 << 0) | Stationary (1 << 4)
+        SpaceBehavior::AllSpaces => 17,
+        // NSWindowCollectionBehaviorStationary only, the window keeps to its Space
+        SpaceBehavior::CurrentSpace => 16,
+    }
+}
 
-    Ok(())
+/// Load the persisted space behavior and apply it to the main window (call on app setup)
+pub fn initialize_space_behavior(app_handle: &AppHandle) {
+    let stored = get_connection(app_handle)
+        .ok()
+        .and_then(|conn| {
+            let sql = "SELECT value FROM settings WHERE key = 'space_behavior'";
+            log_sql(sql);
+            conn.query_row(sql, [], |row| row.get::<_, String>(0)).ok()
+        })
+        .and_then(|value| SpaceBehavior::parse(&value));
+
+    if let Some(behavior) = stored {
+        if let Ok(mut current) = SPACE_BEHAVIOR.write() {
+            *current = behavior;
+        }
+
+        #[cfg(target_os = "macos")]
+        if let Some(window) = app_handle.get_webview_window("main") {
+            repair_main_window_style(&window);
+        }
+    }
 }
 
-/// Resize and position window to fit the notch area
-/// The window is positioned at y=0 (top of screen) to overlap with the notch
+/// Choose whether the notch shows on every Space ("all_spaces", the default) or only
+/// the one it was opened on ("current_space"). Persisted and re-applied after the app
+/// is activated. Only has an effect on macOS.
 #[tauri::command]
-pub fn fit_to_notch(window: Window, width: f64, height: f64) -> Result<(), String> {
-    let (screen_width, _screen_height, _notch_height, _notch_width) =
-        get_screen_info(Some(window.app_handle()));
-
-    // Resize the window
-    window
-        .set_size(LogicalSize::new(width, height))
+pub fn set_space_behavior(app_handle: AppHandle, mode: String) -> Result<(), String> {
+    let behavior =
+        SpaceBehavior::parse(&mode).ok_or(format!("Invalid space behavior: {}", mode))?;
+
+    let conn = get_connection(&app_handle).map_err(|e| e.to_string())?;
+    let sql = "INSERT OR REPLACE INTO settings (key, value) VALUES ('space_behavior', ?1)";
+    log_sql(sql);
+    conn.execute(sql, [behavior.as_str()])
         .map_err(|e| e.to_string())?;
 
-    // Center horizontally, position at very top (y=0) to overlap with notch
-    let x = (screen_width - width) / 2.0;
-    let y = 0.0;
+    if let Ok(mut current) = SPACE_BEHAVIOR.write() {
+        *current = behavior;
+    }
 
-    window
-        .set_position(LogicalPosition::new(x, y))
-        .map_err(|e| e.to_string())?;
+    #[cfg(target_os = "macos")]
+    if let Some(window) = app_handle.get_webview_window("main") {
+        repair_main_window_style(&window);
+    }
 
     Ok(())
 }
 
-/// Set whether the window should ignore mouse events (click-through)
-/// When true, clicks pass through to the underlying application
 #[tauri::command]
-pub fn set_click_through(window: Window, ignore: bool) -> Result<(), String> {
-    window
-        .set_ignore_cursor_events(ignore)
-        .map_err(|e| e.to_string())?;
-    Ok(())
+pub fn get_space_behavior() -> SpaceBehavior {
+    SPACE_BEHAVIOR
+        .read()
+        .map(|behavior| *behavior)
+        .unwrap_or(SpaceBehavior::AllSpaces)
 }
 
-/// Get current window settings
+/// Get whether the dock icon is currently visible (activation policy is Regular)
 #[tauri::command]
-pub fn get_window_settings() -> WindowSettings {
-    let store = get_window_settings_store();
-    *store.read().unwrap_or_else(|e| e.into_inner())
+pub fn get_dock_icon_visible() -> bool {
+    #[cfg(target_os = "macos")]
+    {
+        use objc2::runtime::AnyObject;
+        use objc2::*;
+
+        unsafe {
+            let ns_app: *mut AnyObject = msg_send![class!(NSApplication), sharedApplication];
+            let policy: i64 = msg_send![ns_app, activationPolicy];
+            policy == 0
+        }
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        get_dock_icon_preference().unwrap_or(false)
+    }
 }
 
-/// Update window settings
+fn ensure_app_expand_rules_table(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS app_expand_rules (
+            bundle_id TEXT PRIMARY KEY,
+            expand BOOLEAN NOT NULL
+        )",
+        [],
+    )
+    .map(|_| ())
+}
+
+fn load_app_expand_rules(app_handle: &AppHandle) -> HashMap<String, bool> {
+    let mut rules = HashMap::new();
+
+    let conn = match get_connection(app_handle) {
+        Ok(conn) => conn,
+        Err(e) => {
+            log::warn!("Failed to load app expand rules: {}", e);
+            return rules;
+        }
+    };
+    if ensure_app_expand_rules_table(&conn).is_err() {
+        return rules;
+    }
+
+    let sql = "SELECT bundle_id, expand FROM app_expand_rules";
+    log_sql(sql);
+    if let Ok(mut stmt) = conn.prepare(sql) {
+        if let Ok(rows) = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, bool>(1)?))
+        }) {
+            rules.extend(rows.flatten());
+        }
+    }
+
+    rules
+}
+
+fn get_app_expand_rules_store(app_handle: &AppHandle) -> &'static RwLock<HashMap<String, bool>> {
+    APP_EXPAND_RULES.get_or_init(|| RwLock::new(load_app_expand_rules(app_handle)))
+}
+
+pub fn has_app_expand_rules(app_handle: &AppHandle) -> bool {
+    get_app_expand_rules_store(app_handle)
+        .read()
+        .map(|rules| !rules.is_empty())
+        .unwrap_or(false)
+}
+
+/// Auto-expand (or collapse) the notch when the given app becomes frontmost
 #[tauri::command]
-pub fn update_window_settings(
-    window: WebviewWindow,
-    extra_width: f64,
-    extra_height: f64,
-    non_notch_mode: bool,
+pub fn set_app_expand_rule(
+    app_handle: AppHandle,
+    bundle_id: String,
+    expand: bool,
 ) -> Result<(), String> {
-    // Update the stored settings
-    {
-        let store = get_window_settings_store();
-        let mut settings = store.write().map_err(|e| e.to_string())?;
-        settings.extra_width = extra_width;
-        settings.extra_height = extra_height;
-        settings.non_notch_mode = non_notch_mode;
+    let conn = get_connection(&app_handle).map_err(|e| e.to_string())?;
+    ensure_app_expand_rules_table(&conn).map_err(|e| e.to_string())?;
 
-        persist_window_settings(window.app_handle(), &settings);
-    }
+    let sql = "INSERT OR REPLACE INTO app_expand_rules (bundle_id, expand) VALUES (?1, ?2)";
+    log_sql(&format!("{} [{}, {}]", sql, bundle_id, expand));
+    conn.execute(sql, rusqlite::params![bundle_id, expand])
+        .map_err(|e| e.to_string())?;
 
-    // Apply the new window size to the MAIN window, not the settings window
-    if let Some(main_window) = window.app_handle().get_webview_window("main") {
-        setup_fixed_window_size(&main_window)?;
+    {
+        let mut rules = get_app_expand_rules_store(&app_handle)
+            .write()
+            .map_err(|e| e.to_string())?;
+        rules.insert(bundle_id, expand);
     }
 
+    // Rules are applied from the frontmost app watcher
+    crate::frontmost::ensure_watcher_running(app_handle);
     Ok(())
 }
 
-/// Set up the window with a fixed size based on notch dimensions and settings.
-/// The window always uses: width = (notch_width + 160) + extra_width, height = notch_height + extra_height
-pub fn setup_fixed_window_size(window: &WebviewWindow) -> Result<(), String> {
-    let (screen_width, _screen_height, notch_height, notch_width) =
-        get_screen_info(Some(window.app_handle()));
-    let settings = get_window_settings();
+/// Remove the expand rule for an app. The watcher idles once no rules are left and
+/// `frontmost-app-changed` events are off.
+#[tauri::command]
+pub fn remove_app_expand_rule(app_handle: AppHandle, bundle_id: String) -> Result<(), String> {
+    let conn = get_connection(&app_handle).map_err(|e| e.to_string())?;
+    ensure_app_expand_rules_table(&conn).map_err(|e| e.to_string())?;
 
-    // Calculate fixed window dimensions
-    // In non-notch mode, we might want a smaller fixed window if possible, but keeping it consistent is safer for now
-    // unless the "too big" comment refers to the window size itself blocking things?
-    // If the window is transparent and click-through, size shouldn't matter much visually, but might block clicks if implementation is wrong.
-    let target_width = (notch_width + 160.0) + settings.extra_width;
-    let target_height = notch_height + settings.extra_height;
+    let sql = "DELETE FROM app_expand_rules WHERE bundle_id = ?1";
+    log_sql(&format!("{} [{}]", sql, bundle_id));
+    conn.execute(sql, [&bundle_id]).map_err(|e| e.to_string())?;
+
+    let mut rules = get_app_expand_rules_store(&app_handle)
+        .write()
+        .map_err(|e| e.to_string())?;
+    rules.remove(&bundle_id);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_app_expand_rules(app_handle: AppHandle) -> HashMap<String, bool> {
+    get_app_expand_rules_store(&app_handle)
+        .read()
+        .map(|rules| rules.clone())
+        .unwrap_or_default()
+}
+
+/// Apply the expand rule for a newly frontmost app (called by the frontmost app watcher, which
+/// skips this app itself). Expands for an `expand: true` rule; collapses for an `expand: false`
+/// rule or when leaving an app that auto-expanded the notch.
+pub fn apply_app_expand_rule(app_handle: &AppHandle, app: Option<&AppInfo>) {
+    let rule = app.and_then(|app| {
+        let rules = get_app_expand_rules_store(app_handle).read().ok()?;
+        app.bundle_id
+            .as_ref()
+            .and_then(|id| rules.get(id))
+            .or_else(|| rules.get(&app.name))
+            .copied()
+    });
+    let window = app_handle.get_webview_window("main");
+
+    if rule == Some(true) {
+        if !AUTO_EXPANDED.swap(true, Ordering::Relaxed) {
+            log::debug!("Auto-expanding notch for {:?}", app);
+            let _ = app_handle.emit("auto-expand", app);
+            if let Some(window) = window {
+                let _ = window.set_ignore_cursor_events(false);
+            }
+        }
+    } else if AUTO_EXPANDED.swap(false, Ordering::Relaxed) || rule == Some(false) {
+        log::debug!("Auto-collapsing notch for {:?}", app);
+        let _ = app_handle.emit("auto-collapse", app);
+
+        // Don't make the window click-through under the user's cursor
+        if !IS_INSIDE.load(Ordering::Relaxed) {
+            if let Some(window) = window {
+                let _ = window.set_ignore_cursor_events(true);
+            }
+        }
+    }
+}
+
+/// What a click on the collapsed notch does
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClickAction {
+    /// Expand the notch (emits `notch-expand`)
+    #[default]
+    Expand,
+    OpenSettings,
+    PlayPause,
+    /// Emit `plugin-trigger` for the plugin with this id
+    Plugin {
+        id: String,
+    },
+}
+
+fn load_click_action(app_handle: &AppHandle) -> ClickAction {
+    get_connection(app_handle)
+        .ok()
+        .and_then(|conn| {
+            let sql = "SELECT value FROM settings WHERE key = 'click_action'";
+            log_sql(sql);
+            conn.query_row(sql, [], |row| row.get::<_, String>(0)).ok()
+        })
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn get_click_action_store(app_handle: &AppHandle) -> &'static RwLock<ClickAction> {
+    CLICK_ACTION.get_or_init(|| RwLock::new(load_click_action(app_handle)))
+}
+
+#[tauri::command]
+pub fn get_click_action(app_handle: AppHandle) -> ClickAction {
+    get_click_action_store(&app_handle)
+        .read()
+        .map(|action| action.clone())
+        .unwrap_or_default()
+}
+
+/// Set and persist the action run by notch_clicked
+#[tauri::command]
+pub fn set_click_action(app_handle: AppHandle, action: ClickAction) -> Result<(), String> {
+    if let ClickAction::Plugin { id } = &action {
+        if id.trim().is_empty() {
+            return Err("Plugin click action needs a plugin id".to_string());
+        }
+    }
+
+    let json = serde_json::to_string(&action).map_err(|e| e.to_string())?;
+    let conn = get_connection(&app_handle).map_err(|e| e.to_string())?;
+    let sql = "INSERT OR REPLACE INTO settings (key, value) VALUES ('click_action', ?1)";
+    log_sql(sql);
+    conn.execute(sql, rusqlite::params![json])
+        .map_err(|e| e.to_string())?;
+
+    let mut guard = get_click_action_store(&app_handle)
+        .write()
+        .map_err(|e| e.to_string())?;
+    *guard = action;
+    Ok(())
+}
+
+/// Called by the frontend when the collapsed notch is clicked; runs the configured action
+#[tauri::command]
+pub async fn notch_clicked(app_handle: AppHandle) -> Result<(), String> {
+    let action = get_click_action(app_handle.clone());
+    log::debug!("Notch clicked, running {:?}", action);
+
+    match action {
+        ClickAction::Expand => {
+            if let Some(window) = app_handle.get_webview_window("main") {
+                let _ = window.set_ignore_cursor_events(false);
+            }
+            let _ = app_handle.emit("notch-expand", ());
+            Ok(())
+        }
+        ClickAction::OpenSettings => open_settings(app_handle),
+        ClickAction::PlayPause => crate::audio::media_play_pause(app_handle).await,
+        ClickAction::Plugin { id } => {
+            let _ = app_handle.emit("plugin-trigger", id);
+            Ok(())
+        }
+    }
+}
+
+/// Changes smaller than this (in points) are ignored by update_ui_bounds
+const UI_BOUNDS_EPSILON: f64 = 0.5;
+
+impl UiBounds {
+    fn differs_from(&self, other: &UiBounds) -> bool {
+        (self.x - other.x).abs() > UI_BOUNDS_EPSILON
+            || (self.y - other.y).abs() > UI_BOUNDS_EPSILON
+            || (self.width - other.width).abs() > UI_BOUNDS_EPSILON
+            || (self.height - other.height).abs() > UI_BOUNDS_EPSILON
+    }
+
+    /// Finite, non-negative and inside a screen of the given size (0 = unknown)
+    fn is_sane(&self, screen_width: f64, screen_height: f64) -> bool {
+        let values = [self.x, self.y, self.width, self.height];
+        if values.iter().any(|v| !v.is_finite() || *v < 0.0) {
+            return false;
+        }
+
+        (screen_width <= 0.0 || self.x + self.width <= screen_width + UI_BOUNDS_EPSILON)
+            && (screen_height <= 0.0 || self.y + self.height <= screen_height + UI_BOUNDS_EPSILON)
+    }
+}
+
+/// Update the actual UI element bounds (called from frontend when element resizes)
+/// Near-identical updates are coalesced to avoid write-lock contention with the mouse thread.
+#[tauri::command]
+pub fn update_ui_bounds(x: f64, y: f64, width: f64, height: f64) -> Result<(), String> {
+    let new_bounds = UiBounds {
+        x,
+        y,
+        width,
+        height,
+    };
+    let store = get_ui_bounds_store();
+
+    // Cheap read first: most calls during animations don't actually change anything
+    if let Ok(guard) = store.read() {
+        if let Some(current) = *guard {
+            if !new_bounds.differs_from(&current) {
+                return Ok(());
+            }
+        }
+    }
+
+    let (screen_width, screen_height, _, _) = get_screen_info(None);
+    if !new_bounds.is_sane(screen_width, screen_height) {
+        log::warn!(
+            "Rejected invalid UI bounds: x={}, y={}, w={}, h={} (screen {}x{})",
+            x,
+            y,
+            width,
+            height,
+            screen_width,
+            screen_height
+        );
+        return Err("Invalid UI bounds".to_string());
+    }
+
+    let mut bounds = store.write().map_err(|e| e.to_string())?;
+    *bounds = Some(new_bounds);
+    Ok(())
+}
+
+/// A single hover latency measurement, emitted as `hover-latency`
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct HoverLatencySample {
+    /// Time spent in the native app activation call (ms)
+    pub activation_ms: f64,
+    /// Time spent in set_ignore_cursor_events(false) (ms)
+    pub cursor_events_ms: f64,
+    /// Total time from detecting the cursor entering to cursor events enabled (ms)
+    pub total_ms: f64,
+    /// Polling interval of the monitor thread (upper bound on detection delay)
+    pub poll_interval_ms: u64,
+}
+
+/// Aggregated hover latency statistics (ms)
+#[derive(Debug, Clone, Copy, Serialize, Default)]
+pub struct HoverLatencyStats {
+    pub avg: f64,
+    pub p95: f64,
+    pub max: f64,
+    pub samples: usize,
+}
+
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+fn record_hover_latency(app_handle: &AppHandle, sample: HoverLatencySample) {
+    // Maximum number of samples kept for stats
+    const MAX_HOVER_SAMPLES: usize = 200;
+
+    let store = HOVER_LATENCY_SAMPLES
+        .get_or_init(|| std::sync::Mutex::new(std::collections::VecDeque::new()));
+    if let Ok(mut samples) = store.lock() {
+        if samples.len() >= MAX_HOVER_SAMPLES {
+            samples.pop_front();
+        }
+        samples.push_back(sample.total_ms);
+    }
+
+    let _ = app_handle.emit("hover-latency", sample);
+}
+
+/// Enable or disable hover latency profiling (clears previous samples when enabling)
+#[tauri::command]
+pub fn set_hover_profiling(enabled: bool) {
+    if enabled {
+        if let Some(store) = HOVER_LATENCY_SAMPLES.get() {
+            if let Ok(mut samples) = store.lock() {
+                samples.clear();
+            }
+        }
+    }
+    HOVER_PROFILING.store(enabled, Ordering::Relaxed);
+    log::info!(
+        "Hover latency profiling {}",
+        if enabled { "enabled" } else { "disabled" }
+    );
+}
+
+/// Get aggregated hover latency stats from the recorded samples
+#[tauri::command]
+pub fn get_hover_latency_stats() -> HoverLatencyStats {
+    let mut samples: Vec<f64> = HOVER_LATENCY_SAMPLES
+        .get()
+        .and_then(|m| m.lock().ok().map(|s| s.iter().copied().collect()))
+        .unwrap_or_default();
+
+    if samples.is_empty() {
+        return HoverLatencyStats::default();
+    }
+
+    samples.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+    let count = samples.len();
+    let p95_index = ((count as f64 * 0.95).ceil() as usize).clamp(1, count) - 1;
+
+    HoverLatencyStats {
+        avg: samples.iter().sum::<f64>() / count as f64,
+        p95: samples[p95_index],
+        max: samples[count - 1],
+        samples: count,
+    }
+}
+
+/// Explicit notch dimensions for a display, used instead of the heuristics
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct NotchGeometry {
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Path of the user-editable notch geometry overrides file
+fn notch_geometry_path(app_handle: &AppHandle) -> Option<std::path::PathBuf> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join("notch_geometry.json"))
+}
+
+/// Read notch geometry overrides from disk (empty map if missing or invalid)
+fn read_notch_geometry_file(app_handle: &AppHandle) -> HashMap<String, NotchGeometry> {
+    let path = match notch_geometry_path(app_handle) {
+        Some(p) => p,
+        None => return HashMap::new(),
+    };
+
+    match std::fs::read_to_string(&path) {
+        Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+            log::error!("Invalid notch geometry file at {:?}: {}", path, e);
+            HashMap::new()
+        }),
+        Err(_) => HashMap::new(),
+    }
+}
+
+fn get_notch_geometry_store(
+    app_handle: Option<&AppHandle>,
+) -> Option<&'static RwLock<HashMap<String, NotchGeometry>>> {
+    if let Some(store) = NOTCH_GEOMETRY_OVERRIDES.get() {
+        return Some(store);
+    }
+    // Overrides can only be loaded once we have an app handle to resolve the data dir
+    let handle = app_handle?;
+    Some(NOTCH_GEOMETRY_OVERRIDES.get_or_init(|| RwLock::new(read_notch_geometry_file(handle))))
+}
+
+/// Look up a notch geometry override by display name first, then by "WIDTHxHEIGHT"
+fn find_notch_geometry_override(
+    app_handle: Option<&AppHandle>,
+    display_name: Option<&str>,
+    screen_width: f64,
+    screen_height: f64,
+) -> Option<NotchGeometry> {
+    let store = get_notch_geometry_store(app_handle)?;
+    let overrides = store.read().ok()?;
+    if overrides.is_empty() {
+        return None;
+    }
+
+    let size_key = format!("{}x{}", screen_width.round(), screen_height.round());
+    display_name
+        .and_then(|name| overrides.get(name))
+        .or_else(|| overrides.get(&size_key))
+        .copied()
+}
+
+/// Get notch geometry overrides (re-reads notch_geometry.json to pick up manual edits)
+#[tauri::command]
+pub fn get_notch_geometry_overrides(app_handle: AppHandle) -> HashMap<String, NotchGeometry> {
+    let overrides = read_notch_geometry_file(&app_handle);
+    if let Some(store) = get_notch_geometry_store(Some(&app_handle)) {
+        if let Ok(mut guard) = store.write() {
+            *guard = overrides.clone();
+        }
+    }
+    overrides
+}
+
+/// Set a notch geometry override for a display (name like "Built-in Retina Display" or "1512x982")
+#[tauri::command]
+pub fn set_notch_geometry_override(
+    app_handle: AppHandle,
+    display: String,
+    width: f64,
+    height: f64,
+) -> Result<(), String> {
+    let path = notch_geometry_path(&app_handle).ok_or("Failed to resolve app data dir")?;
+
+    let mut overrides = read_notch_geometry_file(&app_handle);
+    overrides.insert(display, NotchGeometry { width, height });
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(&overrides).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| e.to_string())?;
+
+    if let Some(store) = get_notch_geometry_store(Some(&app_handle)) {
+        let mut guard = store.write().map_err(|e| e.to_string())?;
+        *guard = overrides;
+    }
+    invalidate_screen_info();
+
+    // Apply the new geometry to the main window
+    if let Some(main_window) = app_handle.get_webview_window("main") {
+        setup_fixed_window_size(&main_window)?;
+    }
+
+    Ok(())
+}
+
+/// Get screen dimensions, served from a cache after the first successful query
+/// Returns (screen_width, screen_height, notch_height, notch_width)
+fn get_screen_info(app_handle: Option<&tauri::AppHandle>) -> (f64, f64, f64, f64) {
+    let cache = SCREEN_INFO_CACHE.get_or_init(|| RwLock::new(None));
+    if let Ok(guard) = cache.read() {
+        if let Some(info) = *guard {
+            return info;
+        }
+    }
+
+    match query_screen_info(app_handle) {
+        Some(info) => {
+            if let Ok(mut guard) = cache.write() {
+                *guard = Some(info);
+            }
+            info
+        }
+        #[cfg(target_os = "linux")]
+        None => (1920.0, 1080.0, 0.0, 0.0),
+        #[cfg(not(target_os = "linux"))]
+        None => (0.0, 0.0, 0.0, 0.0),
+    }
+}
+
+/// Drop the cached screen info so the next get_screen_info call re-queries the OS.
+/// Returns true if something was cached.
+pub fn invalidate_screen_info() -> bool {
+    if let Some(cache) = SCREEN_INFO_CACHE.get() {
+        if let Ok(mut guard) = cache.write() {
+            return guard.take().is_some();
+        }
+    }
+    false
+}
+
+/// Force a recompute of the screen dimensions and notch info (e.g. after a display change)
+#[tauri::command]
+pub fn refresh_screen_info(app_handle: tauri::AppHandle) -> Option<NotchInfo> {
+    invalidate_screen_info();
+    get_notch_info(app_handle)
+}
+
+/// Display the overlay was last placed on, as (CGDirectDisplayID, name). Persisted as
+/// `last_display` and `last_display_name` so unplugging and reconnecting an external monitor
+/// can be reported. Matched by id, since two identical monitors share a name (macOS)
+#[cfg(target_os = "macos")]
+static LAST_DISPLAY: RwLock<Option<(u32, String)>> = RwLock::new(None);
+
+/// Set while LAST_DISPLAY is disconnected and the overlay sits on another screen (macOS)
+#[cfg(target_os = "macos")]
+static LAST_DISPLAY_MISSING: AtomicBool = AtomicBool::new(false);
+
+/// CGDirectDisplayID of an NSScreen, from its NSScreenNumber (macOS)
+#[cfg(target_os = "macos")]
+unsafe fn screen_display_id(screen: *mut objc2::runtime::AnyObject) -> Option<u32> {
+    use objc2::runtime::AnyObject;
+    use objc2::*;
+    use objc2_foundation::NSString;
+
+    let description: *mut AnyObject = msg_send![screen, deviceDescription];
+    if description.is_null() {
+        return None;
+    }
+    let key = NSString::from_str("NSScreenNumber");
+    let number: *mut AnyObject = msg_send![description, objectForKey: &*key];
+    if number.is_null() {
+        return None;
+    }
+    Some(msg_send![number, unsignedIntValue])
+}
+
+/// Display ids of all connected screens (macOS)
+#[cfg(target_os = "macos")]
+fn connected_display_ids() -> Vec<u32> {
+    use objc2::runtime::AnyObject;
+    use objc2::*;
+
+    let mut ids = Vec::new();
+    unsafe {
+        let screens: *mut AnyObject = msg_send![class!(NSScreen), screens];
+        if screens.is_null() {
+            return ids;
+        }
+
+        let count: usize = msg_send![screens, count];
+        for i in 0..count {
+            let screen: *mut AnyObject = msg_send![screens, objectAtIndex: i];
+            ids.extend(screen_display_id(screen));
+        }
+    }
+    ids
+}
+
+/// Display id and name of NSScreen.mainScreen (macOS)
+#[cfg(target_os = "macos")]
+fn main_screen_display() -> Option<(u32, String)> {
+    use objc2::runtime::AnyObject;
+    use objc2::*;
+
+    unsafe {
+        let main_screen: *mut AnyObject = msg_send![class!(NSScreen), mainScreen];
+        if main_screen.is_null() {
+            return None;
+        }
+        let id = screen_display_id(main_screen)?;
+        let name: Option<objc2::rc::Retained<objc2_foundation::NSString>> =
+            msg_send![main_screen, localizedName];
+        Some((id, name.map(|n| n.to_string()).unwrap_or_default()))
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn save_last_display(app_handle: &AppHandle, display: (u32, String)) {
+    if let Ok(conn) = get_connection(app_handle) {
+        let sql = "INSERT OR REPLACE INTO settings (key, value) VALUES ('last_display', ?1), ('last_display_name', ?2)";
+        log_sql(sql);
+        let _ = conn.execute(sql, rusqlite::params![display.0.to_string(), display.1]);
+    }
+
+    if let Ok(mut guard) = LAST_DISPLAY.write() {
+        *guard = Some(display);
+    }
+}
+
+/// After a display change: emit `target-display-missing` when the display the overlay was on
+/// is gone (the overlay falls back to the main screen), `target-display-restored` when it's
+/// back (both with the display's name), and refit the window to whichever screen it's on now
+/// (macOS)
+#[cfg(target_os = "macos")]
+fn handle_display_change(app_handle: &AppHandle) {
+    let last = LAST_DISPLAY.read().ok().and_then(|guard| guard.clone());
+    let current = main_screen_display();
+
+    match last {
+        Some((id, name)) if !connected_display_ids().contains(&id) => {
+            if !LAST_DISPLAY_MISSING.swap(true, Ordering::Relaxed) {
+                log::info!(
+                    "Display {} ({}) disconnected, using the main screen",
+                    name,
+                    id
+                );
+                let _ = app_handle.emit("target-display-missing", &name);
+            }
+        }
+        Some((id, name)) if LAST_DISPLAY_MISSING.swap(false, Ordering::Relaxed) => {
+            log::info!("Display {} ({}) reconnected", name, id);
+            let _ = app_handle.emit("target-display-restored", &name);
+        }
+        // Remember the screen actually in use
+        last => {
+            if let Some(current) = current {
+                if last.as_ref() != Some(&current) {
+                    save_last_display(app_handle, current);
+                }
+            }
+        }
+    }
+
+    if let Some(main_window) = app_handle.get_webview_window("main") {
+        if let Err(e) = setup_fixed_window_size(&main_window) {
+            log::warn!("Failed to refit window after display change: {}", e);
+        }
+    }
+}
+
+/// Invalidate the screen info cache whenever displays are added, removed or rearranged,
+/// and move the overlay to the right screen (see `handle_display_change`)
+#[cfg(target_os = "macos")]
+pub fn setup_display_change_monitoring(app_handle: &tauri::AppHandle) {
+    use objc2::runtime::AnyObject;
+    use objc2::*;
+
+    let saved = get_connection(app_handle).ok().and_then(|conn| {
+        let read = |key: &str| {
+            let sql = "SELECT value FROM settings WHERE key = ?1";
+            log_sql(sql);
+            conn.query_row(sql, [key], |row| row.get::<_, String>(0))
+                .ok()
+        };
+        // A name stored by earlier builds doesn't parse, the current screen is saved instead
+        let id = read("last_display")?.parse::<u32>().ok()?;
+        Some((id, read("last_display_name").unwrap_or_default()))
+    });
+    if let Ok(mut guard) = LAST_DISPLAY.write() {
+        *guard = saved;
+    }
+    handle_display_change(app_handle);
+
+    unsafe {
+        let center: *mut AnyObject = msg_send![class!(NSNotificationCenter), defaultCenter];
+        crate::utils::add_notification_observer(
+            center,
+            "NSApplicationDidChangeScreenParametersNotification",
+            app_handle,
+            |app_handle| {
+                log::debug!("Screen parameters changed, invalidating screen info cache");
+                invalidate_screen_info();
+                handle_display_change(app_handle);
+            },
+        );
+    }
+}
+
+/// Read frame, safe area insets, scale and name of NSScreen.mainScreen (macOS)
+#[cfg(target_os = "macos")]
+fn main_screen_metrics() -> Option<RawMetrics> {
+    // Define our own CGSize/CGRect to avoid deprecated cocoa crate fields
+    use objc2::runtime::AnyObject;
+    use objc2::*;
+
+    unsafe {
+        let main_screen: *mut AnyObject = msg_send![class!(NSScreen), mainScreen];
+
+        if main_screen.is_null() {
+            return None;
+        }
+
+        let frame: CGRect = msg_send![main_screen, frame];
+        // safeAreaInsets is macOS 12.0+
+        let insets: NSEdgeInsets = msg_send![main_screen, safeAreaInsets];
+        let scale_factor: f64 = msg_send![main_screen, backingScaleFactor];
+        let display_name: Option<objc2::rc::Retained<objc2_foundation::NSString>> =
+            msg_send![main_screen, localizedName];
+
+        Some(RawMetrics {
+            frame_width: frame.size.width,
+            frame_height: frame.size.height,
+            inset_top: insets.top,
+            inset_left: insets.left,
+            inset_bottom: insets.bottom,
+            inset_right: insets.right,
+            scale_factor,
+            display_name: display_name.map(|n| n.to_string()),
+        })
+    }
+}
+
+/// Model identifiers (`hw.model`) of MacBooks whose built-in display has a notch. Only a
+/// fallback for when the screen reports no safe area inset.
+#[cfg(target_os = "macos")]
+const NOTCHED_MODELS: &[&str] = &[
+    // MacBook Pro 14"/16" (2021, M1 Pro/Max)
+    "MacBookPro18,1",
+    "MacBookPro18,2",
+    "MacBookPro18,3",
+    "MacBookPro18,4",
+    // MacBook Air 13"/15" (M2)
+    "Mac14,2",
+    "Mac14,15",
+    // MacBook Pro 14"/16" (2023, M2 Pro/Max)
+    "Mac14,5",
+    "Mac14,6",
+    "Mac14,9",
+    "Mac14,10",
+    // MacBook Pro 14"/16" (M3 family)
+    "Mac15,3",
+    "Mac15,6",
+    "Mac15,7",
+    "Mac15,8",
+    "Mac15,9",
+    "Mac15,10",
+    "Mac15,11",
+    // MacBook Air 13"/15" (M3)
+    "Mac15,12",
+    "Mac15,13",
+    // MacBook Pro 14"/16" (M4 family)
+    "Mac16,1",
+    "Mac16,5",
+    "Mac16,6",
+    "Mac16,7",
+    "Mac16,8",
+    // MacBook Air 13"/15" (M4)
+    "Mac16,12",
+    "Mac16,13",
+];
+
+/// Read the Mac model identifier (e.g. "MacBookPro18,3") via sysctl, cached for the process
+#[cfg(target_os = "macos")]
+fn mac_model_identifier() -> Option<&'static str> {
+    use std::sync::OnceLock;
+
+    static MODEL: OnceLock<Option<String>> = OnceLock::new();
+
+    MODEL
+        .get_or_init(|| unsafe {
+            let mut size: libc::size_t = 0;
+            if libc::sysctlbyname(
+                c"hw.model".as_ptr(),
+                std::ptr::null_mut(),
+                &mut size,
+                std::ptr::null_mut(),
+                0,
+            ) != 0
+                || size == 0
+            {
+                return None;
+            }
+
+            let mut buf = vec![0u8; size];
+            if libc::sysctlbyname(
+                c"hw.model".as_ptr(),
+                buf.as_mut_ptr() as *mut libc::c_void,
+                &mut size,
+                std::ptr::null_mut(),
+                0,
+            ) != 0
+            {
+                return None;
+            }
+
+            let model = std::ffi::CStr::from_bytes_until_nul(&buf).ok()?;
+            Some(model.to_string_lossy().into_owned())
+        })
+        .as_deref()
+}
+
+/// Whether NSScreen.mainScreen is the built-in panel rather than an external display (macOS)
+#[cfg(target_os = "macos")]
+fn main_screen_is_builtin() -> bool {
+    use objc2::runtime::AnyObject;
+    use objc2::*;
+
+    extern "C" {
+        fn CGDisplayIsBuiltin(display: u32) -> i32;
+    }
+
+    unsafe {
+        let main_screen: *mut AnyObject = msg_send![class!(NSScreen), mainScreen];
+        if main_screen.is_null() {
+            return false;
+        }
+
+        match screen_display_id(main_screen) {
+            Some(display_id) => CGDisplayIsBuiltin(display_id) != 0,
+            None => false,
+        }
+    }
+}
+
+/// Whether the main screen physically has a notch. A top safe area inset is sufficient on its
+/// own, so notched Macs newer than NOTCHED_MODELS are recognized. Only when the inset is
+/// unavailable (reported as zero, e.g. right after wake) does the model list decide, and then
+/// only for the built-in panel.
+#[tauri::command]
+pub fn detect_notch_by_hardware() -> bool {
+    #[cfg(target_os = "macos")]
+    {
+        let inset_top = main_screen_metrics().map(|m| m.inset_top).unwrap_or(0.0);
+        if inset_top > 0.0 {
+            return true;
+        }
+
+        let notched_model = mac_model_identifier()
+            .map(|model| NOTCHED_MODELS.contains(&model))
+            .unwrap_or(false);
+        notched_model && main_screen_is_builtin()
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        false
+    }
+}
+
+/// Raw, uncached screen metrics for bug reports and diagnostics
+#[tauri::command]
+#[allow(unused_variables)]
+pub fn get_raw_screen_metrics(app_handle: tauri::AppHandle) -> Option<RawMetrics> {
+    #[cfg(target_os = "macos")]
+    {
+        main_screen_metrics()
+    }
+
+    // No safe area concept outside macOS, so insets stay zero
+    #[cfg(not(target_os = "macos"))]
+    {
+        let monitor = app_handle.primary_monitor().ok()??;
+        let scale_factor = monitor.scale_factor();
+
+        Some(RawMetrics {
+            frame_width: monitor.size().width as f64 / scale_factor,
+            frame_height: monitor.size().height as f64 / scale_factor,
+            scale_factor,
+            display_name: monitor.name().cloned(),
+            ..Default::default()
+        })
+    }
+}
+
+/// Query the OS for screen dimensions and notch size. None if no screen is available.
+fn query_screen_info(app_handle: Option<&tauri::AppHandle>) -> Option<(f64, f64, f64, f64)> {
+    #[cfg(target_os = "macos")]
+    {
+        let metrics = main_screen_metrics()?;
+        let screen_width = metrics.frame_width;
+        let screen_height = metrics.frame_height;
+        let safe_area_top = metrics.inset_top;
+
+        let notch_height = if safe_area_top >= 0.0 {
+            (screen_height * 0.1).max(38.0).min(52.0)
+        } else {
+            0.0
+        };
+
+        let notch_width = if detect_notch_by_hardware() {
+            (screen_width * 0.1).max(200.0).min(260.0)
+        } else {
+            180.0
+        };
+
+        // User overrides take precedence over the heuristics
+        if let Some(geometry) = find_notch_geometry_override(
+            app_handle,
+            metrics.display_name.as_deref(),
+            screen_width,
+            screen_height,
+        ) {
+            return Some((screen_width, screen_height, geometry.height, geometry.width));
+        }
+
+        Some((screen_width, screen_height, notch_height, notch_width))
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::UI::WindowsAndMessaging::{GetSystemMetrics, SM_CXSCREEN, SM_CYSCREEN};
+
+        let (width, height) = unsafe {
+            (
+                GetSystemMetrics(SM_CXSCREEN) as f64,
+                GetSystemMetrics(SM_CYSCREEN) as f64,
+            )
+        };
+
+        if let Some(geometry) = find_notch_geometry_override(app_handle, None, width, height) {
+            return Some((width, height, geometry.height, geometry.width));
+        }
+
+        Some((width, height, 0.0, 0.0))
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        // Try to get from app handle if available
+        if let Some(handle) = app_handle {
+            if let Ok(Some(monitor)) = handle.primary_monitor() {
+                let size = monitor.size();
+                let scale_factor = monitor.scale_factor();
+                let width = size.width as f64 / scale_factor;
+                let height = size.height as f64 / scale_factor;
+
+                if let Some(geometry) = find_notch_geometry_override(
+                    app_handle,
+                    monitor.name().map(|n| n.as_str()),
+                    width,
+                    height,
+                ) {
+                    return Some((width, height, geometry.height, geometry.width));
+                }
+
+                return Some((width, height, 0.0, 0.0));
+            }
+        }
+        None
+    }
+}
+
+#[tauri::command]
+pub fn get_system_accent_color() -> String {
+    #[cfg(target_os = "macos")]
+    return crate::utils::get_macos_accent_color();
+
+    #[cfg(target_os = "windows")]
+    {
+        // Fallback to blue for now, retrieving registry value is a bit more involved
+        // and dwmapi GetWindowAttribute uses BGR which needs conversion
+        "#007AFF".to_string()
+    }
+
+    #[cfg(target_os = "linux")]
+    return "#007AFF".to_string();
+}
+
+/// Get notch information from the main screen using NSScreen.safeAreaInsets (macOS 12.0+)
+#[tauri::command]
+pub fn get_notch_info(app_handle: tauri::AppHandle) -> Option<NotchInfo> {
+    let (screen_width, screen_height, notch_height, notch_width) =
+        get_screen_info(Some(&app_handle));
+    #[cfg(target_os = "macos")]
+    let has_notch = detect_notch_by_hardware();
+    #[cfg(not(target_os = "macos"))]
+    let has_notch = notch_height > 0.0;
+    let visible_height = screen_height - notch_height;
+
+    Some(NotchInfo {
+        has_notch,
+        notch_height,
+        notch_width,
+        screen_width,
+        screen_height,
+        visible_height,
+    })
+}
+
+/// Position the window at the notch location (centered at top of screen)
+#[tauri::command]
+pub fn position_at_notch(window: Window) -> Result<(), String> {
+    let (screen_width, _screen_height, _notch_height, notch_width) =
+        get_screen_info(Some(window.app_handle()));
+
+    // Use notch width if available, otherwise fall back to current window width
+    let target_width = if notch_width > 0.0 {
+        notch_width
+    } else {
+        let window_size = window.outer_size().map_err(|e| e.to_string())?;
+        let scale_factor = window.scale_factor().map_err(|e| e.to_string())?;
+        window_size.width as f64 / scale_factor
+    };
+
+    // Center horizontally, position at very top (y=0)
+    let x = (screen_width - target_width) / 2.0;
+    let y = 0.0;
+
+    window
+        .set_position(LogicalPosition::new(x, y))
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Resize and position window to fit the notch area
+/// The window is positioned at y=0 (top of screen) to overlap with the notch
+#[tauri::command]
+pub fn fit_to_notch(window: Window, width: f64, height: f64) -> Result<(), String> {
+    let (screen_width, _screen_height, _notch_height, _notch_width) =
+        get_screen_info(Some(window.app_handle()));
+
+    // Resize the window
+    window
+        .set_size(LogicalSize::new(width, height))
+        .map_err(|e| e.to_string())?;
+
+    // Center horizontally, position at very top (y=0) to overlap with notch
+    let x = (screen_width - width) / 2.0;
+    let y = 0.0;
+
+    window
+        .set_position(LogicalPosition::new(x, y))
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Set whether the window should ignore mouse events (click-through)
+/// When true, clicks pass through to the underlying application
+#[tauri::command]
+pub fn set_click_through(window: Window, ignore: bool) -> Result<(), String> {
+    window
+        .set_ignore_cursor_events(ignore)
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Temporary file the platform screenshot tools write the capture to
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn capture_temp_path() -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("opennook-capture-{}.png", std::process::id()))
+}
+
+/// Read a capture written by a screenshot tool as base64 and remove the file
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn take_capture_file(path: &std::path::Path) -> Result<String, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("Capture failed: {}", e));
+    let _ = std::fs::remove_file(path);
+    let bytes = bytes?;
+    if bytes.is_empty() {
+        return Err("Capture failed: empty image".to_string());
+    }
+    Ok(crate::utils::base64_encode(&bytes))
+}
+
+/// Screenshot just the notch overlay (the main window's bounds), as a base64 PNG.
+/// Transparent areas of the overlay stay transparent where the platform preserves alpha.
+#[tauri::command]
+pub fn capture_notch_image(app_handle: AppHandle) -> Result<String, String> {
+    let window = app_handle
+        .get_webview_window("main")
+        .ok_or("Main window not found")?;
+
+    #[cfg(target_os = "macos")]
+    {
+        use objc2::runtime::AnyObject;
+        use objc2::*;
+        use raw_window_handle::HasWindowHandle;
+
+        let handle = window.window_handle().map_err(|e| e.to_string())?;
+        let window_id: i64 = match handle.as_raw() {
+            raw_window_handle::RawWindowHandle::AppKit(appkit_handle) => unsafe {
+                let ns_view = appkit_handle.ns_view.as_ptr() as *mut AnyObject;
+                let ns_win: *mut AnyObject = msg_send![ns_view, window];
+                // windowNumber is the CGWindowID screencapture expects
+                msg_send![ns_win, windowNumber]
+            },
+            _ => return Err("Unsupported window handle".to_string()),
+        };
+
+        let path = capture_temp_path();
+        // -l: only this window, -o: without the drop shadow, -x: no sound
+        let status = std::process::Command::new("screencapture")
+            .args(["-x", "-o", "-t", "png"])
+            .arg(format!("-l{}", window_id))
+            .arg(&path)
+            .status()
+            .map_err(|e| e.to_string())?;
+        if !status.success() {
+            let _ = std::fs::remove_file(&path);
+            return Err("screencapture failed (is Screen Recording permission granted?)".into());
+        }
+
+        take_capture_file(&path)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use raw_window_handle::HasWindowHandle;
+        use windows::Win32::Foundation::{HWND, RECT};
+        use windows::Win32::Graphics::Gdi::{
+            CreateCompatibleBitmap, CreateCompatibleDC, DeleteDC, DeleteObject, GetDC, GetDIBits,
+            ReleaseDC, SelectObject, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS,
+        };
+        use windows::Win32::Storage::Xps::{PrintWindow, PRINT_WINDOW_FLAGS};
+        use windows::Win32::UI::WindowsAndMessaging::GetWindowRect;
+
+        let handle = window.window_handle().map_err(|e| e.to_string())?;
+        let hwnd = match handle.as_raw() {
+            raw_window_handle::RawWindowHandle::Win32(win32_handle) => {
+                HWND(win32_handle.hwnd.get() as _)
+            }
+            _ => return Err("Unsupported window handle".to_string()),
+        };
+
+        // PW_RENDERFULLCONTENT, needed to capture WebView2 content (not in windows 0.52)
+        const PW_RENDERFULLCONTENT: PRINT_WINDOW_FLAGS = PRINT_WINDOW_FLAGS(2);
+
+        let (width, height, mut pixels) = unsafe {
+            let mut rect = RECT::default();
+            GetWindowRect(hwnd, &mut rect).map_err(|e| e.to_string())?;
+            let width = rect.right - rect.left;
+            let height = rect.bottom - rect.top;
+            if width <= 0 || height <= 0 {
+                return Err("Window has no size".to_string());
+            }
+
+            let screen_dc = GetDC(HWND::default());
+            let mem_dc = CreateCompatibleDC(screen_dc);
+            let bitmap = CreateCompatibleBitmap(screen_dc, width, height);
+            let previous = SelectObject(mem_dc, bitmap);
+
+            let printed = PrintWindow(hwnd, mem_dc, PW_RENDERFULLCONTENT).as_bool();
+
+            let mut info = BITMAPINFO {
+                bmiHeader: BITMAPINFOHEADER {
+                    biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+                    biWidth: width,
+                    // Negative height: rows top-down
+                    biHeight: -height,
+                    biPlanes: 1,
+                    biBitCount: 32,
+                    biCompression: BI_RGB.0,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            let mut pixels = vec![0u8; (width * height * 4) as usize];
+            let lines = GetDIBits(
+                mem_dc,
+                bitmap,
+                0,
+                height as u32,
+                Some(pixels.as_mut_ptr() as *mut _),
+                &mut info,
+                DIB_RGB_COLORS,
+            );
+
+            SelectObject(mem_dc, previous);
+            let _ = DeleteObject(bitmap);
+            let _ = DeleteDC(mem_dc);
+            ReleaseDC(HWND::default(), screen_dc);
+
+            if !printed || lines == 0 {
+                return Err("PrintWindow failed".to_string());
+            }
+            (width as u32, height as u32, pixels)
+        };
+
+        // GDI gives BGRA, and leaves alpha at 0 unless the content provided it
+        let has_alpha = pixels.chunks(4).any(|px| px[3] != 0);
+        for px in pixels.chunks_mut(4) {
+            px.swap(0, 2);
+            if !has_alpha {
+                px[3] = 255;
+            }
+        }
+
+        let image = image::RgbaImage::from_raw(width, height, pixels)
+            .ok_or("Captured pixels don't match the window size")?;
+        let mut png = std::io::Cursor::new(Vec::new());
+        image
+            .write_to(&mut png, image::ImageFormat::Png)
+            .map_err(|e| e.to_string())?;
+        Ok(crate::utils::base64_encode(&png.into_inner()))
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let position = window.outer_position().map_err(|e| e.to_string())?;
+        let size = window.outer_size().map_err(|e| e.to_string())?;
+        let path = capture_temp_path();
+
+        // grim on Wayland, ImageMagick's import on X11
+        let region = format!(
+            "{},{} {}x{}",
+            position.x, position.y, size.width, size.height
+        );
+        let grabbed = std::process::Command::new("grim")
+            .args(["-g", &region])
+            .arg(&path)
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(false)
+            || std::process::Command::new("import")
+                .args(["-window", "root", "-crop"])
+                .arg(format!(
+                    "{}x{}+{}+{}",
+                    size.width, size.height, position.x, position.y
+                ))
+                .arg(&path)
+                .status()
+                .map(|status| status.success())
+                .unwrap_or(false);
+
+        if !grabbed {
+            let _ = std::fs::remove_file(&path);
+            return Err("No screenshot tool available (install grim or ImageMagick)".to_string());
+        }
+
+        take_capture_file(&path)
+    }
+}
+
+/// Get current window settings
+#[tauri::command]
+pub fn get_window_settings() -> WindowSettings {
+    let store = get_window_settings_store();
+    *store.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Length of the resize animation when window settings change
+const SETTINGS_RESIZE_DURATION: std::time::Duration = std::time::Duration::from_millis(180);
+/// Frame interval of the resize animation (~60fps)
+const SETTINGS_RESIZE_FRAME: std::time::Duration = std::time::Duration::from_millis(16);
+
+/// Bumped by every settings resize, so a newer slider value takes over a running animation
+static SETTINGS_RESIZE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Update window settings
+/// The main window animates to the new size; rapid calls (slider drags) retarget the running
+/// animation and only the final value is stored, persisted and seen by mouse monitoring.
+#[tauri::command]
+pub fn update_window_settings(
+    window: WebviewWindow,
+    extra_width: f64,
+    extra_height: f64,
+    non_notch_mode: bool,
+) -> Result<(), String> {
+    animate_window_settings(
+        window.app_handle(),
+        WindowSettings {
+            extra_width,
+            extra_height,
+            non_notch_mode,
+            ..get_window_settings()
+        },
+    )
+}
+
+/// Animate the main window to the frame for `new_settings`, then apply them once it settles
+fn animate_window_settings(
+    app_handle: &AppHandle,
+    new_settings: WindowSettings,
+) -> Result<(), String> {
+    let generation = SETTINGS_RESIZE_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+
+    let main_window = match app_handle.get_webview_window("main") {
+        Some(window) => window,
+        None => return apply_window_settings(app_handle, new_settings),
+    };
+
+    // Start from wherever the window is now, which may be mid-animation
+    let scale = main_window.scale_factor().unwrap_or(1.0);
+    let start = match (main_window.outer_position(), main_window.inner_size()) {
+        (Ok(position), Ok(size)) => {
+            let position = position.to_logical::<f64>(scale);
+            let size = size.to_logical::<f64>(scale);
+            (position.x, size.width, size.height)
+        }
+        _ => return apply_window_settings(app_handle, new_settings),
+    };
+    let target = fixed_window_frame(app_handle, &new_settings);
+
+    std::thread::spawn(move || {
+        let started = std::time::Instant::now();
+
+        loop {
+            if SETTINGS_RESIZE_GENERATION.load(Ordering::SeqCst) != generation {
+                return;
+            }
+
+            let t =
+                (started.elapsed().as_secs_f64() / SETTINGS_RESIZE_DURATION.as_secs_f64()).min(1.0);
+            // Ease-out cubic
+            let eased = 1.0 - (1.0 - t).powi(3);
+            let lerp = |from: f64, to: f64| from + (to - from) * eased;
+
+            let _ = main_window.set_size(LogicalSize::new(
+                lerp(start.1, target.1),
+                lerp(start.2, target.2),
+            ));
+            let _ = main_window.set_position(LogicalPosition::new(lerp(start.0, target.0), 0.0));
+
+            if t >= 1.0 {
+                break;
+            }
+            std::thread::sleep(SETTINGS_RESIZE_FRAME);
+        }
+
+        if SETTINGS_RESIZE_GENERATION.load(Ordering::SeqCst) == generation {
+            if let Err(e) = apply_window_settings(main_window.app_handle(), new_settings) {
+                log::warn!("Failed to apply window settings: {}", e);
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Set how the cursor reveals the notch: "hover" (default) or "push" against the top edge
+#[tauri::command]
+pub fn set_reveal_mode(
+    app_handle: AppHandle,
+    mode: RevealMode,
+    push_threshold_px: Option<f64>,
+) -> Result<(), String> {
+    let push_threshold_px = push_threshold_px.unwrap_or_else(default_push_threshold);
+    if !push_threshold_px.is_finite() || !(0.0..=50.0).contains(&push_threshold_px) {
+        return Err("Push threshold must be between 0 and 50 px".to_string());
+    }
+
+    // Read by setup_mouse_monitoring on every iteration, no resize needed
+    let store = get_window_settings_store();
+    let mut settings = store.write().map_err(|e| e.to_string())?;
+    settings.reveal_mode = mode;
+    settings.push_threshold_px = push_threshold_px;
+    persist_window_settings(&app_handle, &settings);
+
+    Ok(())
+}
+
+/// Store, persist and apply window settings to the main window
+pub fn apply_window_settings(
+    app_handle: &AppHandle,
+    new_settings: WindowSettings,
+) -> Result<(), String> {
+    // Update the stored settings
+    {
+        let store = get_window_settings_store();
+        let mut settings = store.write().map_err(|e| e.to_string())?;
+        *settings = new_settings;
+
+        persist_window_settings(app_handle, &settings);
+    }
+
+    // Apply the new window size to the MAIN window, not the settings window
+    if let Some(main_window) = app_handle.get_webview_window("main") {
+        setup_fixed_window_size(&main_window)?;
+    }
+
+    Ok(())
+}
+
+/// Width the collapsed notch needs beside the notch itself when showing artwork and visualizer
+const DEFAULT_COLLAPSED_CONTENT_WIDTH: f64 = 160.0;
+/// Breathing room for the hover grow animation
+const COLLAPSED_BASE_PADDING: f64 = 40.0;
+/// Album thumbnail wing
+const COLLAPSED_ARTWORK_WIDTH: f64 = 60.0;
+/// Visualizer bars wing
+const COLLAPSED_VISUALIZER_WIDTH: f64 = 60.0;
+/// One compact widget (icon + value)
+const COLLAPSED_WIDGET_WIDTH: f64 = 36.0;
+/// Upper bound so a long widget list can't push the window off screen
+const MAX_COLLAPSED_WIDGETS: u32 = 8;
+
+/// Content-driven width set by compute_collapsed_width (None = default)
+static COLLAPSED_CONTENT_WIDTH: RwLock<Option<f64>> = RwLock::new(None);
+
+/// Which elements the collapsed notch shows
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+pub struct CollapsedContentSpec {
+    #[serde(default)]
+    pub artwork: bool,
+    #[serde(default)]
+    pub visualizer: bool,
+    /// Number of compact widgets shown next to the notch
+    #[serde(default)]
+    pub compact_widgets: u32,
+}
+
+impl CollapsedContentSpec {
+    /// Width needed beside the notch for this content
+    fn content_width(&self) -> f64 {
+        let mut width = COLLAPSED_BASE_PADDING;
+        if self.artwork {
+            width += COLLAPSED_ARTWORK_WIDTH;
+        }
+        if self.visualizer {
+            width += COLLAPSED_VISUALIZER_WIDTH;
+        }
+        width + self.compact_widgets.min(MAX_COLLAPSED_WIDGETS) as f64 * COLLAPSED_WIDGET_WIDTH
+    }
+}
+
+fn collapsed_content_width() -> f64 {
+    COLLAPSED_CONTENT_WIDTH
+        .read()
+        .ok()
+        .and_then(|guard| *guard)
+        .unwrap_or(DEFAULT_COLLAPSED_CONTENT_WIDTH)
+}
+
+/// Compute the collapsed notch width for the given content and use it for the window size.
+/// Returns the full collapsed width (notch included); the window is only resized if it changed.
+#[tauri::command]
+pub fn compute_collapsed_width(app_handle: AppHandle, content: CollapsedContentSpec) -> f64 {
+    let (_, _, _, notch_width) = get_screen_info(Some(&app_handle));
+    let content_width = content.content_width();
+
+    let changed = match COLLAPSED_CONTENT_WIDTH.write() {
+        Ok(mut guard) => {
+            let changed = match *guard {
+                Some(previous) => (previous - content_width).abs() > UI_BOUNDS_EPSILON,
+                None => (content_width - DEFAULT_COLLAPSED_CONTENT_WIDTH).abs() > UI_BOUNDS_EPSILON,
+            };
+            *guard = Some(content_width);
+            changed
+        }
+        Err(_) => false,
+    };
+
+    if changed {
+        if let Some(window) = app_handle.get_webview_window("main") {
+            if let Err(e) = setup_fixed_window_size(&window) {
+                log::warn!("Failed to resize for collapsed content: {}", e);
+            }
+        }
+    }
+
+    notch_width + content_width
+}
+
+/// (x, width, height) of the main window for the given settings, centered horizontally
+fn fixed_window_frame(app_handle: &AppHandle, settings: &WindowSettings) -> (f64, f64, f64) {
+    let (screen_width, _screen_height, notch_height, notch_width) =
+        get_screen_info(Some(app_handle));
+
+    // Calculate fixed window dimensions
+    // In non-notch mode, we might want a smaller fixed window if possible, but keeping it consistent is safer for now
+    // unless the "too big" comment refers to the window size itself blocking things?
+    // If the window is transparent and click-through, size shouldn't matter much visually, but might block clicks if implementation is wrong.
+    let width = (notch_width + collapsed_content_width()) + settings.extra_width;
+    let height = notch_height + settings.extra_height;
+
+    ((screen_width - width) / 2.0, width, height)
+}
+
+/// Set up the window with a fixed size based on notch dimensions and settings.
+/// The window always uses: width = (notch_width + collapsed content width) + extra_width,
+/// height = notch_height + extra_height
+pub fn setup_fixed_window_size(window: &WebviewWindow) -> Result<(), String> {
+    let (x, target_width, target_height) =
+        fixed_window_frame(window.app_handle(), &get_window_settings());
+
+    // Resize the window
+    window
+        .set_size(LogicalSize::new(target_width, target_height))
+        .map_err(|e| e.to_string())?;
+
+    // Position at very top (y=0) to overlap with notch
+    let y = 0.0;
+
+    window
+        .set_position(LogicalPosition::new(x, y))
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Activate the window (focus it)
+/// Uses native macOS APIs to properly activate an accessory app
+#[tauri::command]
+pub fn activate_window(window: Window) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        use objc2::runtime::AnyObject;
+        use objc2::*;
+        use raw_window_handle::HasWindowHandle;
+
+        unsafe {
+            // Get NSApplication shared instance and activate it
+            let ns_app: *mut AnyObject = msg_send![class!(NSApplication), sharedApplication];
+            // NSApplicationActivationPolicyRegular = 0
+            // Ensure the app is in Regular mode so it appears in the Dock and App Switcher,
+            // unless the user set an explicit dock icon preference
+            if get_dock_icon_preference().is_none() {
+                let _: () = msg_send![ns_app, setActivationPolicy: 0_i64];
+            }
+            let _: () = msg_send![ns_app, activateIgnoringOtherApps: true];
+
+            // Re-apply styles to the main notch window to prevent it from disappearing/resetting
+            if let Some(main_window) = window.app_handle().get_webview_window("main") {
+                if let Ok(handle) = main_window.window_handle() {
+                    if let raw_window_handle::RawWindowHandle::AppKit(appkit_handle) =
+                        handle.as_raw()
+                    {
+                        let ns_view = appkit_handle.ns_view.as_ptr() as *mut AnyObject;
+                        let ns_win: *mut AnyObject = msg_send![ns_view, window];
+
+                        // Re-apply level and collection behavior
+                        let _: () = msg_send![ns_win, setLevel: 25_i64];
+                        let _: () = msg_send![
+                            ns_win,
+                            setCollectionBehavior: main_window_collection_behavior()
+                        ];
+                    }
+                }
+            }
+
+            // Also make the window key and bring it to front
+            if let Ok(handle) = window.window_handle() {
+                if let raw_window_handle::RawWindowHandle::AppKit(appkit_handle) = handle.as_raw() {
+                    let ns_view = appkit_handle.ns_view.as_ptr() as *mut AnyObject;
+                    let ns_win: *mut AnyObject = msg_send![ns_view, window];
+                    let _: () =
+                        msg_send![ns_win, makeKeyAndOrderFront: std::ptr::null::<AnyObject>()];
+                }
+            }
+        }
+
+        ensure_main_window_visible_in_background(window.app_handle().clone());
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use raw_window_handle::HasWindowHandle;
+        use windows::Win32::Foundation::HWND;
+        use windows::Win32::UI::WindowsAndMessaging::{
+            SetForegroundWindow, ShowWindow, SW_RESTORE,
+        };
+
+        if let Ok(handle) = window.window_handle() {
+            if let raw_window_handle::RawWindowHandle::Win32(win32_handle) = handle.as_raw() {
+                unsafe {
+                    // Non-zero handle
+                    let hwnd = HWND(win32_handle.hwnd.get() as _);
+                    // Force restore if minimized and bring to front
+                    ShowWindow(hwnd, SW_RESTORE);
+                    SetForegroundWindow(hwnd);
+                }
+            }
+        }
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        window.set_focus().map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Overlay hidden on request (screen sharing, screenshots). Never persisted, so the
+/// overlay always starts visible.
+static OVERLAY_SUPPRESSED: AtomicBool = AtomicBool::new(false);
+
+/// Shortcut that toggles the overlay when none has been configured
+const DEFAULT_OVERLAY_SHORTCUT: &str = "CommandOrControl+Shift+H";
+
+/// Currently registered overlay toggle shortcut
+static OVERLAY_SHORTCUT: RwLock<Option<String>> = RwLock::new(None);
+
+/// True while the overlay is hidden by `toggle_overlay_visibility`
+pub fn is_overlay_suppressed() -> bool {
+    OVERLAY_SUPPRESSED.load(Ordering::Relaxed)
+}
+
+/// Whether the main window is shown, whoever hid it (overlay toggle, screen lock)
+static UI_ACTIVE: AtomicBool = AtomicBool::new(true);
+
+/// Lets loops idling while the UI is hidden wake as soon as it's shown again
+pub static UI_SHOWN: (std::sync::Mutex<()>, std::sync::Condvar) =
+    (std::sync::Mutex::new(()), std::sync::Condvar::new());
+
+/// Call whenever the main window is hidden or shown
+pub fn set_ui_active(active: bool) {
+    if UI_ACTIVE.swap(active, Ordering::SeqCst) == active {
+        return;
+    }
+
+    log::debug!("UI {}", if active { "active" } else { "inactive" });
+    if active {
+        // Taken so a loop can't miss the wakeup between its check and its wait
+        let _guard = UI_SHOWN.0.lock();
+        UI_SHOWN.1.notify_all();
+    }
+}
+
+/// False while the main window is hidden and nothing it shows is rendered
+pub fn is_ui_active() -> bool {
+    UI_ACTIVE.load(Ordering::SeqCst)
+}
+
+/// Hide or show the overlay, pausing monitoring while it is hidden.
+/// Emits `overlay-hidden` / `overlay-shown` and returns whether the overlay is now hidden.
+#[tauri::command]
+pub fn toggle_overlay_visibility(app_handle: AppHandle) -> Result<bool, String> {
+    let window = app_handle
+        .get_webview_window("main")
+        .ok_or("Main window not found")?;
+
+    let hide = !is_overlay_suppressed();
+    if hide {
+        window.hide().map_err(|e| e.to_string())?;
+        OVERLAY_SUPPRESSED.store(true, Ordering::Relaxed);
+        set_ui_active(false);
+        let _ = app_handle.emit("overlay-hidden", ());
+    } else {
+        OVERLAY_SUPPRESSED.store(false, Ordering::Relaxed);
+        window.show().map_err(|e| e.to_string())?;
+        set_ui_active(true);
+        let _ = app_handle.emit("overlay-shown", ());
+    }
+
+    log::info!("Overlay {}", if hide { "hidden" } else { "shown" });
+    Ok(hide)
+}
+
+/// Action name of the overlay toggle shortcut, as reported in `conflicts_with`
+const OVERLAY_SHORTCUT_ACTION: &str = "overlay_toggle";
+
+/// Combos the OS (or every app) already uses, checked by validate_shortcut
+#[cfg(target_os = "macos")]
+const OS_RESERVED_SHORTCUTS: &[&str] = &[
+    "Command+Q",
+    "Command+W",
+    "Command+H",
+    "Command+M",
+    "Command+Tab",
+    "Command+Space",
+    "Control+Space",
+    "Command+Alt+Escape",
+    "Control+Command+Q",
+    "Command+Shift+3",
+    "Command+Shift+4",
+    "Command+Shift+5",
+    "Command+C",
+    "Command+V",
+    "Command+X",
+    "Command+Z",
+    "Command+A",
+];
+#[cfg(target_os = "windows")]
+const OS_RESERVED_SHORTCUTS: &[&str] = &[
+    "Alt+Tab",
+    "Alt+F4",
+    "Control+Alt+Delete",
+    "Control+Shift+Escape",
+    "Super+L",
+    "Super+D",
+    "Super+E",
+    "Super+R",
+    "Super+Tab",
+    "PrintScreen",
+    "Control+C",
+    "Control+V",
+    "Control+X",
+    "Control+Z",
+    "Control+A",
+];
+#[cfg(target_os = "linux")]
+const OS_RESERVED_SHORTCUTS: &[&str] = &[
+    "Alt+Tab",
+    "Alt+F4",
+    "Control+Alt+Delete",
+    "Control+Alt+T",
+    "Super+L",
+    "Super+Tab",
+    "Control+C",
+    "Control+V",
+    "Control+X",
+    "Control+Z",
+    "Control+A",
+];
+
+/// Result of validate_shortcut
+#[derive(Debug, Clone, Serialize)]
+pub struct ShortcutValidation {
+    /// Parses, isn't taken by another action and isn't reserved by the OS
+    pub valid: bool,
+    /// Action already bound to the same combo (e.g. "overlay_toggle")
+    pub conflicts_with: Option<String>,
+    pub reserved_by_os: bool,
+    /// Why the accelerator couldn't be parsed
+    pub error: Option<String>,
+}
+
+/// Every global shortcut the app registers, as (action, accelerator)
+fn registered_shortcuts() -> Vec<(&'static str, String)> {
+    vec![(OVERLAY_SHORTCUT_ACTION, get_overlay_toggle_shortcut())]
+}
+
+fn same_combo(
+    a: &tauri_plugin_global_shortcut::Shortcut,
+    b: &tauri_plugin_global_shortcut::Shortcut,
+) -> bool {
+    a.mods == b.mods && a.key == b.key
+}
+
+/// Check `accelerator` against the app's other shortcuts (ignoring `action`'s own binding)
+/// and the OS-reserved combos
+fn check_shortcut(accelerator: &str, action: Option<&str>) -> ShortcutValidation {
+    use tauri_plugin_global_shortcut::Shortcut;
+
+    let parsed: Shortcut = match accelerator.parse() {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            return ShortcutValidation {
+                valid: false,
+                conflicts_with: None,
+                reserved_by_os: false,
+                error: Some(format!("Invalid shortcut {}: {}", accelerator, e)),
+            }
+        }
+    };
+
+    let conflicts_with = registered_shortcuts()
+        .into_iter()
+        .filter(|(other, _)| Some(*other) != action)
+        .find(|(_, bound)| {
+            bound
+                .parse::<Shortcut>()
+                .is_ok_and(|bound| same_combo(&bound, &parsed))
+        })
+        .map(|(other, _)| other.to_string());
+
+    let reserved_by_os = OS_RESERVED_SHORTCUTS.iter().any(|reserved| {
+        reserved
+            .parse::<Shortcut>()
+            .is_ok_and(|reserved| same_combo(&reserved, &parsed))
+    });
+
+    ShortcutValidation {
+        valid: conflicts_with.is_none() && !reserved_by_os,
+        conflicts_with,
+        reserved_by_os,
+        error: None,
+    }
+}
+
+/// Check whether `accelerator` can be used as a global shortcut: it must parse, not be bound
+/// to another action and not be an OS-reserved combo. Pass `action` when rebinding so its
+/// current shortcut doesn't count as a conflict.
+#[tauri::command]
+pub fn validate_shortcut(accelerator: String, action: Option<String>) -> ShortcutValidation {
+    check_shortcut(&accelerator, action.as_deref())
+}
+
+/// validate_shortcut as an error for the set-shortcut commands
+fn ensure_shortcut_available(accelerator: &str, action: &str) -> Result<(), String> {
+    let validation = check_shortcut(accelerator, Some(action));
+    if let Some(error) = validation.error {
+        return Err(error);
+    }
+    if let Some(other) = validation.conflicts_with {
+        return Err(format!(
+            "Shortcut {} is already used by {}",
+            accelerator, other
+        ));
+    }
+    if validation.reserved_by_os {
+        return Err(format!(
+            "Shortcut {} is reserved by the system",
+            accelerator
+        ));
+    }
+    Ok(())
+}
+
+/// Swap the registered overlay toggle shortcut for `shortcut`
+fn register_overlay_shortcut(app_handle: &AppHandle, shortcut: &str) -> Result<(), String> {
+    use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
+
+    let parsed: Shortcut = shortcut
+        .parse()
+        .map_err(|e| format!("Invalid shortcut {}: {}", shortcut, e))?;
+
+    let mut current = OVERLAY_SHORTCUT.write().map_err(|e| e.to_string())?;
+    if let Some(previous) = current.as_deref() {
+        if let Ok(previous) = previous.parse::<Shortcut>() {
+            let _ = app_handle.global_shortcut().unregister(previous);
+        }
+    }
 
-    // Resize the window
-    window
-        .set_size(LogicalSize::new(target_width, target_height))
+    app_handle
+        .global_shortcut()
+        .register(parsed)
         .map_err(|e| e.to_string())?;
+    *current = Some(shortcut.to_string());
+    Ok(())
+}
 
-    // Center horizontally, position at very top (y=0) to overlap with notch
-    let x = (screen_width - target_width) / 2.0;
-    let y = 0.0;
+/// Register the persisted overlay toggle shortcut (defaults to Cmd/Ctrl+Shift+H)
+pub fn setup_overlay_shortcut(app_handle: &AppHandle) {
+    let shortcut = get_connection(app_handle)
+        .ok()
+        .and_then(|conn| {
+            let sql = "SELECT value FROM settings WHERE key = 'overlay_toggle_shortcut'";
+            log_sql(sql);
+            conn.query_row(sql, [], |row| row.get::<_, String>(0)).ok()
+        })
+        .unwrap_or_else(|| DEFAULT_OVERLAY_SHORTCUT.to_string());
 
-    window
-        .set_position(LogicalPosition::new(x, y))
-        .map_err(|e| e.to_string())?;
+    if let Err(e) = register_overlay_shortcut(app_handle, &shortcut) {
+        log::warn!("Failed to register overlay shortcut: {}", e);
+    }
+}
+
+/// Handle a press of the overlay toggle shortcut (the only global shortcut registered)
+pub fn handle_overlay_shortcut(app_handle: &AppHandle) {
+    if let Err(e) = toggle_overlay_visibility(app_handle.clone()) {
+        log::error!("Failed to toggle overlay: {}", e);
+    }
+}
+
+/// Change the global shortcut that hides/shows the overlay, e.g. "CommandOrControl+Shift+H"
+#[tauri::command]
+pub fn set_overlay_toggle_shortcut(app_handle: AppHandle, shortcut: String) -> Result<(), String> {
+    ensure_shortcut_available(&shortcut, OVERLAY_SHORTCUT_ACTION)?;
+    register_overlay_shortcut(&app_handle, &shortcut)?;
+
+    let conn = get_connection(&app_handle).map_err(|e| e.to_string())?;
+    let sql = "INSERT OR REPLACE INTO settings (key, value) VALUES ('overlay_toggle_shortcut', ?1)";
+    log_sql(sql);
+    conn.execute(sql, [&shortcut]).map_err(|e| e.to_string())?;
 
     Ok(())
 }
 
-/// Activate the window (focus it)
-/// Uses native macOS APIs to properly activate an accessory app
 #[tauri::command]
-pub fn activate_window(window: Window) -> Result<(), String> {
+pub fn get_overlay_toggle_shortcut() -> String {
+    OVERLAY_SHORTCUT
+        .read()
+        .ok()
+        .and_then(|shortcut| shortcut.clone())
+        .unwrap_or_else(|| DEFAULT_OVERLAY_SHORTCUT.to_string())
+}
+
+/// Deactivate the window and reset activation policy (hide from dock)
+#[tauri::command]
+pub fn deactivate_window(window: Window) -> Result<(), String> {
     #[cfg(target_os = "macos")]
     {
         use objc2::runtime::AnyObject;
@@ -435,14 +2431,15 @@ pub fn activate_window(window: Window) -> Result<(), String> {
         use raw_window_handle::HasWindowHandle;
 
         unsafe {
-            // Get NSApplication shared instance and activate it
+            // Get NSApplication shared instance
             let ns_app: *mut AnyObject = msg_send![class!(NSApplication), sharedApplication];
-            // NSApplicationActivationPolicyRegular = 0
-            // Ensure the app is in Regular mode so it appears in the Dock and App Switcher
-            let _: () = msg_send![ns_app, setActivationPolicy: 0_i64];
-            let _: () = msg_send![ns_app, activateIgnoringOtherApps: true];
+            // NSApplicationActivationPolicyAccessory = 1
+            // Revert to Accessory mode so it hides from Dock (unless explicitly set by the user)
+            if get_dock_icon_preference().is_none() {
+                let _: () = msg_send![ns_app, setActivationPolicy: 1_i64];
+            }
 
-            // Re-apply styles to the main notch window to prevent it from disappearing/resetting
+            // Re-apply styles to the main notch window explicitly
             if let Some(main_window) = window.app_handle().get_webview_window("main") {
                 if let Ok(handle) = main_window.window_handle() {
                     if let raw_window_handle::RawWindowHandle::AppKit(appkit_handle) =
@@ -451,88 +2448,173 @@ pub fn activate_window(window: Window) -> Result<(), String> {
                         let ns_view = appkit_handle.ns_view.as_ptr() as *mut AnyObject;
                         let ns_win: *mut AnyObject = msg_send![ns_view, window];
 
-                        // Re-apply level and collection behavior
                         let _: () = msg_send![ns_win, setLevel: 25_i64];
-                        let _: () = msg_send![ns_win, setCollectionBehavior: 17_u64];
+                        let _: () = msg_send![
+                            ns_win,
+                            setCollectionBehavior: main_window_collection_behavior()
+                        ];
                     }
                 }
             }
-
-            // Also make the window key and bring it to front
-            if let Ok(handle) = window.window_handle() {
-                if let raw_window_handle::RawWindowHandle::AppKit(appkit_handle) = handle.as_raw() {
-                    let ns_view = appkit_handle.ns_view.as_ptr() as *mut AnyObject;
-                    let ns_win: *mut AnyObject = msg_send![ns_view, window];
-                    let _: () =
-                        msg_send![ns_win, makeKeyAndOrderFront: std::ptr::null::<AnyObject>()];
-                }
-            }
         }
+
+        ensure_main_window_visible_in_background(window.app_handle().clone());
     }
 
-    #[cfg(target_os = "windows")]
-    {
-        use raw_window_handle::HasWindowHandle;
-        use windows::Win32::Foundation::HWND;
-        use windows::Win32::UI::WindowsAndMessaging::{
-            SetForegroundWindow, ShowWindow, SW_RESTORE,
-        };
+    Ok(())
+}
 
-        if let Ok(handle) = window.window_handle() {
-            if let raw_window_handle::RawWindowHandle::Win32(win32_handle) = handle.as_raw() {
-                unsafe {
-                    // Non-zero handle
-                    let hwnd = HWND(win32_handle.hwnd.get() as _);
-                    // Force restore if minimized and bring to front
-                    ShowWindow(hwnd, SW_RESTORE);
-                    SetForegroundWindow(hwnd);
-                }
-            }
+/// NSStatusWindowLevel, above the menu bar so the window can sit over the notch
+#[cfg(target_os = "macos")]
+const MAIN_WINDOW_LEVEL: i64 = 25;
+
+/// Re-apply level and collection behavior if they were reset, returns true if anything changed (macOS)
+#[cfg(target_os = "macos")]
+fn repair_main_window_style(window: &WebviewWindow) -> bool {
+    use objc2::runtime::AnyObject;
+    use objc2::*;
+    use raw_window_handle::HasWindowHandle;
+
+    let handle = match window.window_handle() {
+        Ok(handle) => handle,
+        Err(_) => return false,
+    };
+    let appkit_handle = match handle.as_raw() {
+        raw_window_handle::RawWindowHandle::AppKit(appkit_handle) => appkit_handle,
+        _ => return false,
+    };
+
+    let mut repaired = false;
+    unsafe {
+        let ns_view = appkit_handle.ns_view.as_ptr() as *mut AnyObject;
+        let ns_win: *mut AnyObject = msg_send![ns_view, window];
+        if ns_win.is_null() {
+            return false;
+        }
+
+        let level: i64 = msg_send![ns_win, level];
+        if level != MAIN_WINDOW_LEVEL {
+            let _: () = msg_send![ns_win, setLevel: MAIN_WINDOW_LEVEL];
+            repaired = true;
+        }
+
+        let behavior: u64 = msg_send![ns_win, collectionBehavior];
+        let expected = main_window_collection_behavior();
+        if behavior != expected {
+            let _: () = msg_send![ns_win, setCollectionBehavior: expected];
+            repaired = true;
         }
     }
 
-    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    repaired
+}
+
+/// Re-create the main window from the app config after it was destroyed. Must not run on the
+/// main thread, building a window there deadlocks on Windows.
+fn recreate_main_window(app_handle: &AppHandle) -> Result<WebviewWindow, String> {
+    let config = app_handle
+        .config()
+        .app
+        .windows
+        .iter()
+        .find(|w| w.label == "main")
+        .cloned()
+        .ok_or("Main window config not found")?;
+
+    let window = WebviewWindowBuilder::from_config(app_handle, &config)
+        .map_err(|e| e.to_string())?
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let _ = window.set_ignore_cursor_events(true);
+    setup_fixed_window_size(&window)?;
+
+    #[cfg(target_os = "macos")]
     {
-        window.set_focus().map_err(|e| e.to_string())?;
+        let radius = get_window_corner_radius();
+        if radius > 0.0 {
+            // AppKit calls must happen on the main thread
+            let styled = window.clone();
+            let _ = app_handle.run_on_main_thread(move || {
+                let _ = apply_corner_radius(&styled, radius);
+            });
+        }
     }
 
-    Ok(())
+    Ok(window)
 }
 
-/// Deactivate the window and reset activation policy (hide from dock)
+/// Make sure the main notch window still exists, is visible and (on macOS) keeps its level
+/// and collection behavior, fixing whatever isn't. Emits `main-window-recovered` and returns
+/// true if something had to be fixed. Async so a missing window is never rebuilt on the main
+/// thread.
 #[tauri::command]
-pub fn deactivate_window(window: Window) -> Result<(), String> {
+pub async fn ensure_main_window_visible(app_handle: AppHandle) -> Result<bool, String> {
+    let mut fixes: Vec<&str> = Vec::new();
+
+    let window = match app_handle.get_webview_window("main") {
+        Some(window) => window,
+        None => {
+            fixes.push("recreated");
+            recreate_main_window(&app_handle)?
+        }
+    };
+
+    // Hiding while locked or suppressed is intentional, the window is shown again afterwards
+    if !crate::power::is_hidden_by_lock()
+        && !is_overlay_suppressed()
+        && !window.is_visible().unwrap_or(true)
+    {
+        window.show().map_err(|e| e.to_string())?;
+        set_ui_active(true);
+        fixes.push("shown");
+    }
+
     #[cfg(target_os = "macos")]
     {
-        use objc2::runtime::AnyObject;
-        use objc2::*;
-        use raw_window_handle::HasWindowHandle;
+        // AppKit calls must happen on the main thread
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let styled = window.clone();
+        app_handle
+            .run_on_main_thread(move || {
+                let _ = tx.send(repair_main_window_style(&styled));
+            })
+            .map_err(|e| e.to_string())?;
+        if rx.await.unwrap_or(false) {
+            fixes.push("style");
+        }
+    }
 
-        unsafe {
-            // Get NSApplication shared instance
-            let ns_app: *mut AnyObject = msg_send![class!(NSApplication), sharedApplication];
-            // NSApplicationActivationPolicyAccessory = 1
-            // Revert to Accessory mode so it hides from Dock
-            let _: () = msg_send![ns_app, setActivationPolicy: 1_i64];
+    if fixes.is_empty() {
+        return Ok(false);
+    }
 
-            // Re-apply styles to the main notch window explicitly
-            if let Some(main_window) = window.app_handle().get_webview_window("main") {
-                if let Ok(handle) = main_window.window_handle() {
-                    if let raw_window_handle::RawWindowHandle::AppKit(appkit_handle) =
-                        handle.as_raw()
-                    {
-                        let ns_view = appkit_handle.ns_view.as_ptr() as *mut AnyObject;
-                        let ns_win: *mut AnyObject = msg_send![ns_view, window];
+    log::warn!("Main window recovered: {}", fixes.join(", "));
+    let _ = app_handle.emit("main-window-recovered", &fixes);
+    Ok(true)
+}
 
-                        let _: () = msg_send![ns_win, setLevel: 25_i64];
-                        let _: () = msg_send![ns_win, setCollectionBehavior: 17_u64];
-                    }
-                }
-            }
+/// Run ensure_main_window_visible on the async runtime without waiting for it, so it is safe to
+/// call from the main thread
+fn ensure_main_window_visible_in_background(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = ensure_main_window_visible(app_handle).await {
+            log::error!("Main window recovery failed: {}", e);
         }
-    }
+    });
+}
 
-    Ok(())
+/// Periodically check the main window hasn't disappeared (call on app setup)
+pub fn setup_main_window_watchdog(app_handle: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(std::time::Duration::from_secs(15));
+
+        if crate::power::is_monitoring_paused() {
+            continue;
+        }
+
+        ensure_main_window_visible_in_background(app_handle.clone());
+    });
 }
 
 /// Predefined haptic patterns
@@ -598,6 +2680,9 @@ impl Default for HapticConfig {
 #[tauri::command]
 pub fn trigger_haptics(config: Option<HapticConfig>) -> Result<(), String> {
     let config = config.unwrap_or_default();
+    if crate::quiet_hours::suppressed("haptics", &format!("{:?}", config.pattern)) {
+        return Ok(());
+    }
 
     #[cfg(target_os = "macos")]
     unsafe {
@@ -663,6 +2748,201 @@ pub fn trigger_haptics(config: Option<HapticConfig>) -> Result<(), String> {
     Ok(())
 }
 
+/// Skip activating the app on hover while another app has a text input focused (persisted)
+static AVOID_FOCUS_STEAL: AtomicBool = AtomicBool::new(true);
+
+/// Whether the system-wide focused UI element is a text input. Needs Accessibility
+/// permission; without it the lookup fails and this returns false.
+#[cfg(target_os = "macos")]
+fn text_input_focused() -> bool {
+    use core_foundation::base::{CFType, CFTypeRef, TCFType};
+    use core_foundation::string::{CFString, CFStringRef};
+
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXUIElementCreateSystemWide() -> CFTypeRef;
+        fn AXUIElementCopyAttributeValue(
+            element: CFTypeRef,
+            attribute: CFStringRef,
+            value: *mut CFTypeRef,
+        ) -> i32;
+    }
+
+    unsafe fn copy_attribute(element: &CFType, attribute: &str) -> Option<CFType> {
+        let attribute = CFString::new(attribute);
+        let mut value: CFTypeRef = std::ptr::null();
+        let error = AXUIElementCopyAttributeValue(
+            element.as_CFTypeRef(),
+            attribute.as_concrete_TypeRef(),
+            &mut value,
+        );
+        if error != 0 || value.is_null() {
+            return None;
+        }
+        Some(CFType::wrap_under_create_rule(value))
+    }
+
+    unsafe {
+        let system = AXUIElementCreateSystemWide();
+        if system.is_null() {
+            return false;
+        }
+        let system = CFType::wrap_under_create_rule(system);
+
+        let role = copy_attribute(&system, "AXFocusedUIElement")
+            .and_then(|focused| copy_attribute(&focused, "AXRole"))
+            .and_then(|role| role.downcast::<CFString>())
+            .map(|role| role.to_string());
+
+        matches!(
+            role.as_deref(),
+            Some("AXTextField" | "AXTextArea" | "AXComboBox")
+        )
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn load_avoid_focus_steal(app_handle: &AppHandle) {
+    let enabled = get_connection(app_handle)
+        .ok()
+        .and_then(|conn| {
+            let sql = "SELECT value FROM settings WHERE key = 'avoid_focus_steal'";
+            log_sql(sql);
+            conn.query_row(sql, [], |row| row.get::<_, String>(0)).ok()
+        })
+        .map(|value| value == "true")
+        .unwrap_or(true);
+
+    AVOID_FOCUS_STEAL.store(enabled, Ordering::Relaxed);
+}
+
+/// Enable or disable skipping app activation on hover while a text input is focused (macOS)
+#[tauri::command]
+pub fn set_avoid_focus_steal(app_handle: AppHandle, enabled: bool) -> Result<(), String> {
+    AVOID_FOCUS_STEAL.store(enabled, Ordering::Relaxed);
+
+    let conn = get_connection(&app_handle).map_err(|e| e.to_string())?;
+    let sql = "INSERT OR REPLACE INTO settings (key, value) VALUES ('avoid_focus_steal', ?1)";
+    log_sql(sql);
+    conn.execute(sql, rusqlite::params![enabled.to_string()])
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_avoid_focus_steal() -> bool {
+    AVOID_FOCUS_STEAL.load(Ordering::Relaxed)
+}
+
+/// What hovering the notch does besides enabling cursor events (persisted as `hover_activation_mode`)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HoverActivationMode {
+    /// Bring the whole app forward (activateIgnoringOtherApps / SetForegroundWindow)
+    ActivateApp,
+    /// Make the notch window key without activating the app, so keyboard input stays with the
+    /// frontmost app (macOS), foreground the window (Windows)
+    WindowOnly,
+    /// Only accept cursor events, leave focus where it is
+    CursorOnly,
+}
+
+impl HoverActivationMode {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "activate_app" => Some(Self::ActivateApp),
+            "window_only" => Some(Self::WindowOnly),
+            "cursor_only" => Some(Self::CursorOnly),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::ActivateApp => "activate_app",
+            Self::WindowOnly => "window_only",
+            Self::CursorOnly => "cursor_only",
+        }
+    }
+}
+
+static HOVER_ACTIVATION_MODE: RwLock<HoverActivationMode> =
+    RwLock::new(HoverActivationMode::ActivateApp);
+
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+fn load_hover_activation_mode(app_handle: &AppHandle) {
+    let stored = get_connection(app_handle)
+        .ok()
+        .and_then(|conn| {
+            let sql = "SELECT value FROM settings WHERE key = 'hover_activation_mode'";
+            log_sql(sql);
+            conn.query_row(sql, [], |row| row.get::<_, String>(0)).ok()
+        })
+        .and_then(|value| HoverActivationMode::parse(&value));
+
+    if let Some(mode) = stored {
+        if let Ok(mut current) = HOVER_ACTIVATION_MODE.write() {
+            *current = mode;
+        }
+    }
+}
+
+/// Choose whether hovering the notch activates the app ("activate_app", the default),
+/// only makes the notch window key ("window_only") or just enables cursor events ("cursor_only")
+#[tauri::command]
+pub fn set_hover_activation_mode(app_handle: AppHandle, mode: String) -> Result<(), String> {
+    let parsed = HoverActivationMode::parse(&mode)
+        .ok_or(format!("Invalid hover activation mode: {}", mode))?;
+
+    let conn = get_connection(&app_handle).map_err(|e| e.to_string())?;
+    let sql = "INSERT OR REPLACE INTO settings (key, value) VALUES ('hover_activation_mode', ?1)";
+    log_sql(sql);
+    conn.execute(sql, [parsed.as_str()])
+        .map_err(|e| e.to_string())?;
+
+    if let Ok(mut current) = HOVER_ACTIVATION_MODE.write() {
+        *current = parsed;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_hover_activation_mode() -> HoverActivationMode {
+    HOVER_ACTIVATION_MODE
+        .read()
+        .map(|mode| *mode)
+        .unwrap_or(HoverActivationMode::ActivateApp)
+}
+
+/// Make the notch window key without activating the app (macOS). The app stays inactive, so
+/// the frontmost app keeps keyboard input: the notch only gets it once the app is activated
+/// (e.g. by clicking it), and until then it just tracks the mouse and takes clicks.
+#[cfg(target_os = "macos")]
+fn make_main_window_key(app_handle: &AppHandle) {
+    use objc2::runtime::AnyObject;
+    use objc2::*;
+    use raw_window_handle::HasWindowHandle;
+
+    // Window APIs must be used from the main thread
+    let handle = app_handle.clone();
+    let _ = app_handle.run_on_main_thread(move || {
+        if let Some(window) = handle.get_webview_window("main") {
+            if let Ok(window_handle) = window.window_handle() {
+                if let raw_window_handle::RawWindowHandle::AppKit(appkit_handle) =
+                    window_handle.as_raw()
+                {
+                    unsafe {
+                        let ns_view = appkit_handle.ns_view.as_ptr() as *mut AnyObject;
+                        let ns_win: *mut AnyObject = msg_send![ns_view, window];
+                        let _: () = msg_send![ns_win, makeKeyWindow];
+                    }
+                }
+            }
+        }
+    });
+}
+
 /// Setup global mouse monitoring for the window
 /// Uses fast polling for minimal latency hover detection
 #[cfg(target_os = "macos")]
@@ -670,38 +2950,11 @@ pub fn setup_mouse_monitoring(app_handle: tauri::AppHandle) {
     use objc2::runtime::AnyObject;
     use objc2::*;
 
-    // Track whether mouse is currently in the UI area
-    static IS_INSIDE: AtomicBool = AtomicBool::new(false);
-
-    // Get initial screen info
-    let (screen_width, screen_height, notch_height, notch_width) =
-        get_screen_info(Some(&app_handle));
-
-    // Pre-compute window position (window is centered at top)
-    let settings = get_window_settings();
-    let win_width = notch_width + 160.0 + settings.extra_width;
-    let window_x = (screen_width - win_width) / 2.0;
-
-    // Compute fallback detection area
-    let effective_notch_width = if settings.non_notch_mode {
-        0.0
-    } else {
-        notch_width
-    };
-
-    let fallback_x_start = (screen_width - effective_notch_width) / 2.0;
-    let _fallback_x_end = fallback_x_start + effective_notch_width;
-    let _fallback_y_end = if settings.non_notch_mode {
-        1.0
-    } else {
-        notch_height
-    };
+    load_avoid_focus_steal(&app_handle);
+    load_hover_activation_mode(&app_handle);
 
     // Spawn monitoring thread
     std::thread::spawn(move || {
-        let mut cached_screen_height = screen_height;
-        let mut refresh_counter: u16 = 0;
-
         // Hysteresis to prevent flicker
         const PADDING_ENTER: f64 = 20.0;
         const PADDING_EXIT: f64 = 30.0;
@@ -709,9 +2962,25 @@ pub fn setup_mouse_monitoring(app_handle: tauri::AppHandle) {
         // Fast polling for low latency
         const POLL_MS: u64 = 20; // ~50fps
 
+        let mut reveal_gate = RevealGate::default();
+
         loop {
-            // Refresh settings and dimensions on every iteration to handle runtime toggles
+            // Nothing to hover while the screen is locked or asleep
+            if crate::power::is_monitoring_paused() {
+                crate::power::idle_while_paused(std::time::Duration::from_secs(1));
+                continue;
+            }
+
+            // Refresh settings and dimensions on every iteration to handle runtime toggles,
+            // display changes and notch geometry overrides (screen info is cached)
             let settings = get_window_settings();
+            let (screen_width, screen_height, notch_height, notch_width) =
+                get_screen_info(Some(&app_handle));
+
+            // Window is centered at the top, its width follows the collapsed content
+            let win_width = notch_width + collapsed_content_width() + settings.extra_width;
+            let window_x = (screen_width - win_width) / 2.0;
+
             let effective_notch_width = if settings.non_notch_mode {
                 0.0
             } else {
@@ -729,15 +2998,7 @@ pub fn setup_mouse_monitoring(app_handle: tauri::AppHandle) {
             // Get mouse position
             let (mouse_x, flipped_y) = unsafe {
                 let mouse_loc: CGPoint = msg_send![class!(NSEvent), mouseLocation];
-
-                // Refresh screen height occasionally
-                refresh_counter = refresh_counter.wrapping_add(1);
-                if refresh_counter % 500 == 0 {
-                    let (_, height, _, _) = get_screen_info(None);
-                    cached_screen_height = height;
-                }
-
-                (mouse_loc.x, cached_screen_height - mouse_loc.y)
+                (mouse_loc.x, screen_height - mouse_loc.y)
             };
 
             let was_inside = IS_INSIDE.load(Ordering::Relaxed);
@@ -753,6 +3014,7 @@ pub fn setup_mouse_monitoring(app_handle: tauri::AppHandle) {
                 && flipped_y <= broad_limit_y;
 
             if !is_in_interaction_zone && !was_inside {
+                reveal_gate.reset();
                 std::thread::sleep(std::time::Duration::from_millis(POLL_MS));
                 continue;
             }
@@ -784,9 +3046,11 @@ pub fn setup_mouse_monitoring(app_handle: tauri::AppHandle) {
                     && flipped_y >= -padding
                     && flipped_y <= (fallback_y_end + padding)
             };
+            let in_ui_area = reveal_gate.allows(&settings, in_ui_area, was_inside, flipped_y);
 
             // State transitions - emit events immediately
             if in_ui_area && !was_inside {
+                let detected_at = std::time::Instant::now();
                 IS_INSIDE.store(true, Ordering::Relaxed);
 
                 if let Ok(guard) = get_ui_bounds_store().try_read() {
@@ -806,17 +3070,40 @@ pub fn setup_mouse_monitoring(app_handle: tauri::AppHandle) {
                 let _ = app_handle.emit("mouse-entered-notch", ());
 
                 // Set cursor events and activate using native APIs (non-blocking)
-                unsafe {
-                    // Activate app
-                    let ns_app: *mut AnyObject =
-                        msg_send![class!(NSApplication), sharedApplication];
-                    let _: () = msg_send![ns_app, activateIgnoringOtherApps: true];
+                let activation_start = std::time::Instant::now();
+                match get_hover_activation_mode() {
+                    HoverActivationMode::CursorOnly => {}
+                    // Activating would pull keyboard focus out of a text field the user is typing in
+                    _ if AVOID_FOCUS_STEAL.load(Ordering::Relaxed) && text_input_focused() => {
+                        log::debug!("[mouse] Text input focused, not activating");
+                    }
+                    HoverActivationMode::WindowOnly => make_main_window_key(&app_handle),
+                    HoverActivationMode::ActivateApp => unsafe {
+                        // Activate app
+                        let ns_app: *mut AnyObject =
+                            msg_send![class!(NSApplication), sharedApplication];
+                        let _: () = msg_send![ns_app, activateIgnoringOtherApps: true];
+                    },
                 }
+                let activation_ms = activation_start.elapsed().as_secs_f64() * 1000.0;
 
                 // Set ignore cursor events via Tauri (this is fast)
+                let cursor_events_start = std::time::Instant::now();
                 if let Some(window) = app_handle.get_webview_window("main") {
                     let _ = window.set_ignore_cursor_events(false);
                 }
+
+                if HOVER_PROFILING.load(Ordering::Relaxed) {
+                    record_hover_latency(
+                        &app_handle,
+                        HoverLatencySample {
+                            activation_ms,
+                            cursor_events_ms: cursor_events_start.elapsed().as_secs_f64() * 1000.0,
+                            total_ms: detected_at.elapsed().as_secs_f64() * 1000.0,
+                            poll_interval_ms: POLL_MS,
+                        },
+                    );
+                }
             } else if !in_ui_area && was_inside {
                 IS_INSIDE.store(false, Ordering::Relaxed);
 
@@ -836,9 +3123,11 @@ pub fn setup_mouse_monitoring(app_handle: tauri::AppHandle) {
                 // Emit event first
                 let _ = app_handle.emit("mouse-exited-notch", ());
 
-                // Disable cursor events
-                if let Some(window) = app_handle.get_webview_window("main") {
-                    let _ = window.set_ignore_cursor_events(true);
+                // Disable cursor events, unless an app rule is holding the notch open
+                if !AUTO_EXPANDED.load(Ordering::Relaxed) {
+                    if let Some(window) = app_handle.get_webview_window("main") {
+                        let _ = window.set_ignore_cursor_events(true);
+                    }
                 }
             }
 
@@ -852,32 +3141,39 @@ pub fn setup_mouse_monitoring(app_handle: tauri::AppHandle) {
     use windows::Win32::Foundation::POINT;
     use windows::Win32::UI::WindowsAndMessaging::GetCursorPos;
 
-    // Track whether mouse is currently in the UI area
-    static IS_INSIDE: AtomicBool = AtomicBool::new(false);
+    load_hover_activation_mode(&app_handle);
 
-    let (screen_width, _screen_height, notch_height, notch_width) =
-        get_screen_info(Some(&app_handle));
+    std::thread::spawn(move || {
+        const POLL_MS: u64 = 20;
 
-    // Pre-compute window position (window is centered at top)
-    let settings = get_window_settings();
-    let effective_notch_width = if settings.non_notch_mode {
-        0.0
-    } else {
-        notch_width
-    };
+        let mut reveal_gate = RevealGate::default();
 
-    let win_width = if effective_notch_width > 0.0 {
-        effective_notch_width + 160.0 + settings.extra_width
-    } else {
-        800.0 + settings.extra_width
-    }; // Fallback width
+        loop {
+            if crate::power::is_monitoring_paused() {
+                crate::power::idle_while_paused(std::time::Duration::from_secs(1));
+                continue;
+            }
+
+            // Refresh settings and dimensions on every iteration to handle runtime toggles,
+            // display changes and notch geometry overrides (screen info is cached)
+            let settings = get_window_settings();
+            let (screen_width, _screen_height, _notch_height, notch_width) =
+                get_screen_info(Some(&app_handle));
+            let effective_notch_width = if settings.non_notch_mode {
+                0.0
+            } else {
+                notch_width
+            };
 
-    let window_x = (screen_width - win_width) / 2.0;
+            // Window is centered at the top, its width follows the collapsed content
+            let win_width = if effective_notch_width > 0.0 {
+                effective_notch_width + collapsed_content_width() + settings.extra_width
+            } else {
+                800.0 + settings.extra_width
+            }; // Fallback width
 
-    std::thread::spawn(move || {
-        const POLL_MS: u64 = 20;
+            let window_x = (screen_width - win_width) / 2.0;
 
-        loop {
             let mut point = POINT::default();
             let success = unsafe { GetCursorPos(&mut point) };
 
@@ -916,19 +3212,28 @@ pub fn setup_mouse_monitoring(app_handle: tauri::AppHandle) {
                         && mouse_y >= 0.0
                         && mouse_y <= (fallback_height + padding)
                 };
+                let in_ui_area = reveal_gate.allows(&settings, in_ui_area, was_inside, mouse_y);
 
                 if in_ui_area && !was_inside {
+                    let detected_at = std::time::Instant::now();
                     IS_INSIDE.store(true, Ordering::Relaxed);
                     let _ = app_handle.emit("mouse-entered-notch", ());
 
                     if let Some(window) = app_handle.get_webview_window("main") {
+                        let cursor_events_start = std::time::Instant::now();
                         let _ = window.set_ignore_cursor_events(false);
+                        let cursor_events_ms = cursor_events_start.elapsed().as_secs_f64() * 1000.0;
+
                         // Activate window
                         use raw_window_handle::HasWindowHandle;
                         use windows::Win32::Foundation::HWND;
                         use windows::Win32::UI::WindowsAndMessaging::SetForegroundWindow;
 
-                        if let Ok(handle) = window.window_handle() {
+                        // Windows has no app-level activation, both modes foreground the window
+                        let activation_start = std::time::Instant::now();
+                        if get_hover_activation_mode() == HoverActivationMode::CursorOnly {
+                            // Cursor events only
+                        } else if let Ok(handle) = window.window_handle() {
                             if let raw_window_handle::RawWindowHandle::Win32(win32_handle) =
                                 handle.as_raw()
                             {
@@ -938,12 +3243,27 @@ pub fn setup_mouse_monitoring(app_handle: tauri::AppHandle) {
                                 }
                             }
                         }
+
+                        if HOVER_PROFILING.load(Ordering::Relaxed) {
+                            record_hover_latency(
+                                &app_handle,
+                                HoverLatencySample {
+                                    activation_ms: activation_start.elapsed().as_secs_f64()
+                                        * 1000.0,
+                                    cursor_events_ms,
+                                    total_ms: detected_at.elapsed().as_secs_f64() * 1000.0,
+                                    poll_interval_ms: POLL_MS,
+                                },
+                            );
+                        }
                     }
                 } else if !in_ui_area && was_inside {
                     IS_INSIDE.store(false, Ordering::Relaxed);
                     let _ = app_handle.emit("mouse-exited-notch", ());
-                    if let Some(window) = app_handle.get_webview_window("main") {
-                        let _ = window.set_ignore_cursor_events(true);
+                    if !AUTO_EXPANDED.load(Ordering::Relaxed) {
+                        if let Some(window) = app_handle.get_webview_window("main") {
+                            let _ = window.set_ignore_cursor_events(true);
+                        }
                     }
                 }
             }
@@ -12,6 +12,7 @@ import { useTimerStore } from './stores/useTimerStore';
 import { useSessionStore } from './stores/useSessionStore';
 import { useWidgetStore } from './stores/useWidgetStore';
 import { useFileTrayStore } from './stores/useFileTrayStore';
+import { useDynamicIslandStore } from './stores/useDynamicIslandStore';
 
 function App() {
   // Initialize stores
@@ -27,12 +28,14 @@ function App() {
     const cleanupSession = useSessionStore.getState().setupListeners();
     const cleanupWidget = useWidgetStore.getState().setupListeners();
     const cleanupFileTray = useFileTrayStore.getState().setupListeners();
+    const cleanupIsland = useDynamicIslandStore.getState().setupListeners();
 
     return () => {
       cleanupTimer();
       cleanupSession();
       cleanupWidget();
       cleanupFileTray();
+      cleanupIsland();
     };
   }, []);
 
@@ -1,6 +1,7 @@
 import { memo, useMemo, useCallback, useState } from 'react';
 import { motion, AnimatePresence } from 'motion/react';
 import { IconPlayerPlayFilled, IconPlayerPauseFilled } from '@tabler/icons-react';
+import { artworkDataUrl } from '../utils/imageUtils';
 import './DynamicIsland.css'; // Assuming CSS is shared or moved. Ideally specific CSS should be here.
 
 interface AlbumCoverProps {
@@ -58,7 +59,7 @@ export const AlbumCover = memo(function AlbumCover({
         >
             {artwork ? (
                 <img
-                    src={`data:image/png;base64,${artwork}`}
+                    src={artworkDataUrl(artwork)}
                     alt={title || 'Album cover'}
                     className="album-cover__image"
                 />
@@ -1,7 +1,7 @@
 import { memo, useMemo, useState, useEffect } from 'react';
 import { motion } from 'motion/react';
 import { IconPlayerSkipBackFilled, IconPlayerPlayFilled, IconPlayerPauseFilled, IconPlayerSkipForwardFilled } from '@tabler/icons-react';
-import { getDominantColor } from '../../utils/imageUtils';
+import { artworkDataUrl, getDominantColor } from '../../utils/imageUtils';
 import { WidgetWrapper } from '../widgets/WidgetWrapper';
 import { useMediaPlayerStore } from '../../stores/useMediaPlayerStore';
 import { ScrollingText } from '../ui/scrolling-text';
@@ -43,7 +43,7 @@ export const ExpandedMedia = memo(function ExpandedMedia() {
     // Extract dominant color for glow
     useEffect(() => {
         if (nowPlaying.artwork_base64) {
-            const src = `data:image/png;base64,${nowPlaying.artwork_base64}`;
+            const src = artworkDataUrl(nowPlaying.artwork_base64);
             getDominantColor(src).then(rgb => {
                 if (rgb) {
                     setGlowColor(`rgb(${rgb[0]}, ${rgb[1]}, ${rgb[2]})`);
@@ -99,7 +99,7 @@ export const ExpandedMedia = memo(function ExpandedMedia() {
                 ></div>
                 {nowPlaying.artwork_base64 ? (
                     <img
-                        src={`data:image/png;base64,${nowPlaying.artwork_base64}`}
+                        src={artworkDataUrl(nowPlaying.artwork_base64)}
                         alt={nowPlaying.title || 'Album cover'}
                         className="w-full h-full object-cover rounded-[12px]"
                     />
@@ -1,11 +1,16 @@
 export interface NowPlayingData {
     title: string | null;
     artist: string | null;
+    artists: string[] | null;
     album: string | null;
+    genre: string | null;
+    year: number | null;
+    output_target: string | null;
     artwork_base64: string | null;
     duration: number | null;
     elapsed_time: number | null;
     is_playing: boolean;
     audio_levels: number[] | null;
     app_name: string | null;
+    content_type: "music" | "podcast" | "audiobook" | null;
 }
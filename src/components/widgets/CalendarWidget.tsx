@@ -14,6 +14,7 @@ interface CalendarEvent {
     location: string | null;
     is_all_day: boolean;
     color: string;
+    calendar_id: string;
 }
 
 // Zod schema for calendar event form
@@ -17,6 +17,9 @@ interface Reminder {
     is_completed: boolean;
     list_name: string;
     list_color: string;
+    is_flagged: boolean;
+    has_notes: boolean;
+    url: string | null;
 }
 
 // Zod schema for reminder form
@@ -1,5 +1,6 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 
 interface Settings {
     showCalendar: boolean;
@@ -64,6 +65,9 @@ interface DynamicIslandActions {
     // Compound actions
     handleIslandClick: (currentMode: string) => void;
     handleExpandCollapse: (expand: boolean) => void;
+
+    // Listeners
+    setupListeners: () => () => void;
 }
 
 type DynamicIslandStore = DynamicIslandState & DynamicIslandActions;
@@ -176,6 +180,17 @@ export const useDynamicIslandStore = create<DynamicIslandStore>((set, get) => ({
         setIsAnimating(true);
         invoke('trigger_haptics').catch(console.error);
     },
+
+    setupListeners: () => {
+        // The backend appends to notes (e.g. dropped text) and emits the full contents
+        const unlistenNotes = listen<string>('notes-updated', (event) => {
+            set({ notes: event.payload });
+        });
+
+        return () => {
+            unlistenNotes.then(fn => fn());
+        };
+    },
 }));
 
 // Selectors for optimized re-renders
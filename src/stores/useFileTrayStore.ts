@@ -136,12 +136,15 @@ export const useFileTrayStore = create<FileTrayStore>((set, get) => ({
         const unlistenBackendDragEnter = listen('drag-enter-event', handleDragEnter);
         const unlistenBackendFileDrop = listen('file-drop-event', handleFileDrop);
         const unlistenFileDropHover = listen('tauri://file-drop-hover', handleDragEnter);
+        // Backend added files (e.g. handle_drop saved a dropped image)
+        const unlistenTrayUpdated = listen('file-tray-updated', () => get().loadFiles());
 
         return () => {
             unlistenDragEnter.then(fn => fn());
             unlistenBackendDragEnter.then(fn => fn());
             unlistenBackendFileDrop.then(fn => fn());
             unlistenFileDropHover.then(fn => fn());
+            unlistenTrayUpdated.then(fn => fn());
         };
     }
 }));
@@ -1,7 +1,7 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
 import { NowPlayingData } from '../components/island/types';
-import { getDominantColor } from '../utils/imageUtils';
+import { artworkDataUrl, getDominantColor } from '../utils/imageUtils';
 
 // Cache for artwork colors to avoid re-processing
 const artworkColorCache = new Map<string, string | null>();
@@ -177,7 +177,7 @@ export const useMediaPlayerStore = create<MediaPlayerStore>((set, get) => ({
             return;
         }
 
-        const src = `data:image/png;base64,${artwork}`;
+        const src = artworkDataUrl(artwork);
         const rgb = await getDominantColor(src);
         const color = rgb ? `rgb(${rgb[0]}, ${rgb[1]}, ${rgb[2]})` : null;
         artworkColorCache.set(artwork, color);
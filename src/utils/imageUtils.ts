@@ -1,3 +1,12 @@
+/**
+ * Builds a data URL for base64 artwork from the backend, which is JPEG
+ * (downscaled) or PNG (transparent or passed through unchanged).
+ */
+export function artworkDataUrl(base64: string): string {
+    const mime = base64.startsWith('/9j/') ? 'image/jpeg' : 'image/png';
+    return `data:${mime};base64,${base64}`;
+}
+
 
 /**
  * Extracts the dominant color from an image base64 string or URL.